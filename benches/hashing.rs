@@ -0,0 +1,82 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rs_merkle::{
+    algorithms::{Sha256, Sha384},
+    Hasher, MerkleTree,
+};
+
+/// Hashes the pairs of nodes with the default [`Hasher::hash_pair`], which concatenates the
+/// nodes into a vector
+#[derive(Clone)]
+struct AllocatingSha256 {}
+
+impl Hasher for AllocatingSha256 {
+    type Hash = [u8; 32];
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        Sha256::hash(data)
+    }
+}
+
+#[derive(Clone)]
+struct AllocatingSha384 {}
+
+impl Hasher for AllocatingSha384 {
+    type Hash = [u8; 48];
+
+    fn hash(data: &[u8]) -> [u8; 48] {
+        Sha384::hash(data)
+    }
+}
+
+#[cfg(feature = "keccak256")]
+#[derive(Clone)]
+struct AllocatingKeccak256 {}
+
+#[cfg(feature = "keccak256")]
+impl Hasher for AllocatingKeccak256 {
+    type Hash = [u8; 32];
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        rs_merkle::algorithms::Keccak256::hash(data)
+    }
+}
+
+fn bench_hasher<T: Hasher>(criterion: &mut Criterion, group_name: &str, name: &str) {
+    let left = T::hash(b"left");
+    let right = T::hash(b"right");
+
+    let mut group = criterion.benchmark_group(format!("{}/concat_and_hash", group_name));
+    group.bench_function(name, |bencher| {
+        bencher.iter(|| T::concat_and_hash(black_box(&left), Some(black_box(&right))))
+    });
+    group.finish();
+
+    let mut group = criterion.benchmark_group(format!("{}/from_leaves", group_name));
+    for leaves_count in [1 << 10, 1 << 14] {
+        let leaves: Vec<T::Hash> = (0..leaves_count)
+            .map(|i: usize| T::hash(&i.to_be_bytes()))
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new(name, leaves_count),
+            &leaves,
+            |bencher, leaves| bencher.iter(|| MerkleTree::<T>::from_leaves(black_box(leaves))),
+        );
+    }
+    group.finish();
+}
+
+fn hashing(criterion: &mut Criterion) {
+    bench_hasher::<AllocatingSha256>(criterion, "sha256", "allocating");
+    bench_hasher::<Sha256>(criterion, "sha256", "hash_pair");
+    bench_hasher::<AllocatingSha384>(criterion, "sha384", "allocating");
+    bench_hasher::<Sha384>(criterion, "sha384", "hash_pair");
+    #[cfg(feature = "keccak256")]
+    {
+        bench_hasher::<AllocatingKeccak256>(criterion, "keccak256", "allocating");
+        bench_hasher::<rs_merkle::algorithms::Keccak256>(criterion, "keccak256", "hash_pair");
+    }
+}
+
+criterion_group!(benches, hashing);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+use crate::{prelude::*, Hasher, OddNodePolicy};
+use sha2::{digest::FixedOutput, Digest, Sha256};
+
+/// Bitcoin implementation of the [`Hasher`] trait. Nodes are hashed with double sha256, and the
+/// last node of an odd layer is duplicated, as described in [`OddNodePolicy::Duplicate`].
+///
+/// Hashes are in the internal byte order, so the transaction ids and the merkle roots displayed
+/// by block explorers and Bitcoin Core RPC need to be reversed before and after use.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, algorithms::Bitcoin, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // Transaction ids from the block 170, the first block with a non-coinbase transaction
+/// let mut coinbase_txid = [
+///     0xb1, 0xfe, 0xa5, 0x24, 0x86, 0xce, 0x0c, 0x62, 0xbb, 0x44, 0x2b, 0x53, 0x0a, 0x3f, 0x01,
+///     0x32, 0xb8, 0x26, 0xc7, 0x4e, 0x47, 0x3d, 0x1f, 0x2c, 0x22, 0x0b, 0xfa, 0x78, 0x11, 0x1c,
+///     0x50, 0x82,
+/// ];
+/// let mut txid = [
+///     0xf4, 0x18, 0x4f, 0xc5, 0x96, 0x40, 0x3b, 0x9d, 0x63, 0x87, 0x83, 0xcf, 0x57, 0xad, 0xfe,
+///     0x4c, 0x75, 0xc6, 0x05, 0xf6, 0x35, 0x6f, 0xbc, 0x91, 0x33, 0x85, 0x30, 0xe9, 0x83, 0x1e,
+///     0x9e, 0x16,
+/// ];
+/// coinbase_txid.reverse();
+/// txid.reverse();
+///
+/// let merkle_tree = MerkleTree::<Bitcoin>::from_leaves(&[coinbase_txid, txid]);
+/// let mut merkle_root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+/// merkle_root.reverse();
+///
+/// assert_eq!(
+///     rs_merkle::utils::collections::to_hex_string(&merkle_root),
+///     "7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct BitcoinAlgorithm {}
+
+impl Hasher for BitcoinAlgorithm {
+    type Hash = [u8; 32];
+
+    const ODD_NODE_POLICY: OddNodePolicy = OddNodePolicy::Duplicate;
+
+    const ALGORITHM_ID: u16 = 4;
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        let first_hash = Sha256::digest(data);
+        <[u8; 32]>::from(Sha256::new_with_prefix(first_hash).finalize_fixed())
+    }
+
+    fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let first_hash = Sha256::new()
+            .chain_update(left)
+            .chain_update(right)
+            .finalize();
+        <[u8; 32]>::from(Sha256::new_with_prefix(first_hash).finalize_fixed())
+    }
+
+    #[cfg(feature = "rayon")]
+    fn hash_layer_with<W: Hasher<Hash = [u8; 32]>>(nodes: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        crate::hasher::par_hash_layer::<W>(nodes)
+    }
+}
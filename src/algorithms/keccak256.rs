@@ -12,6 +12,8 @@ pub struct Keccak256Algorithm {}
 impl Hasher for Keccak256Algorithm {
     type Hash = [u8; 32];
 
+    const ALGORITHM_ID: u16 = 3;
+
     fn hash(data: &[u8]) -> Self::Hash {
         let mut hasher = Keccak::v256();
         let mut output = [0u8; 32];
@@ -19,4 +21,18 @@ impl Hasher for Keccak256Algorithm {
         hasher.finalize(&mut output);
         output
     }
+
+    fn hash_pair(left: &Self::Hash, right: &Self::Hash) -> Self::Hash {
+        let mut hasher = Keccak::v256();
+        let mut output = [0u8; 32];
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize(&mut output);
+        output
+    }
+
+    #[cfg(feature = "rayon")]
+    fn hash_layer_with<W: Hasher<Hash = Self::Hash>>(nodes: &[Self::Hash]) -> Vec<Self::Hash> {
+        crate::hasher::par_hash_layer::<W>(nodes)
+    }
 }
@@ -1,14 +1,20 @@
 //! This module contains built-in implementations of the [`Hasher`]
 //!
 //! [`Hasher`]: crate::Hasher
+mod bitcoin;
+mod rfc6962;
 mod sha256;
 mod sha384;
+mod sorted_pair;
 
 #[cfg(feature = "keccak256")]
 mod keccak256;
 
+pub use bitcoin::BitcoinAlgorithm as Bitcoin;
+pub use rfc6962::Rfc6962Algorithm as Rfc6962;
 pub use sha256::Sha256Algorithm as Sha256;
 pub use sha384::Sha384Algorithm as Sha384;
+pub use sorted_pair::SortedPairAlgorithm as SortedPair;
 
 #[cfg(feature = "keccak256")]
 pub use keccak256::Keccak256Algorithm as Keccak256;
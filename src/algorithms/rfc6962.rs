@@ -0,0 +1,111 @@
+use crate::{hasher, prelude::*, Hasher, LeafHashPolicy, OddNodePolicy};
+use core::marker::PhantomData;
+
+/// Domain separation prefix for the leaf hashes, as defined by RFC 6962
+const LEAF_PREFIX: u8 = 0x00;
+/// Domain separation prefix for the internal node hashes, as defined by RFC 6962
+const NODE_PREFIX: u8 = 0x01;
+/// Size of the stack buffer the internal nodes are prefixed in, enough for two 64 byte hashes
+const NODE_BUFFER_SIZE: usize = 1 + 2 * 64;
+
+/// [RFC 6962] implementation of the [`Hasher`] trait on top of any other [`Hasher`].
+///
+/// Leaves are hashed as `H(0x00 || data)` with [`Hasher::hash`], and internal nodes are hashed as
+/// `H(0x01 || left || right)`, so a leaf hash can never be passed off as an internal node.
+/// Nodes without a sibling are promoted to the next layer unchanged, which gives exactly the
+/// RFC 6962 tree shape, where a tree is split at the largest power of two smaller than its size.
+/// This makes roots, inclusion proofs and [`ConsistencyProof`]s compatible with Certificate
+/// Transparency logs when used with [`Sha256`]. The hash of the wrapped hasher has to implement
+/// `AsRef<[u8]>`, as all the built-in hashes do.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, algorithms::{Rfc6962, Sha256}, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = [&b""[..], b"\x00", b"\x10"]
+///     .iter()
+///     .map(|x| Rfc6962::<Sha256>::hash(x))
+///     .collect();
+///
+/// let merkle_tree = MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves);
+/// assert_eq!(
+///     merkle_tree.root_hex(),
+///     Some("aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77".to_string())
+/// );
+///
+/// let proof = merkle_tree.proof(&[2]);
+/// assert!(proof.verify(
+///     merkle_tree.root().ok_or("couldn't get the merkle root")?,
+///     &[2],
+///     &leaves[2..],
+///     leaves.len()
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [RFC 6962]: https://www.rfc-editor.org/rfc/rfc6962#section-2.1
+/// [`ConsistencyProof`]: crate::ConsistencyProof
+/// [`Sha256`]: crate::algorithms::Sha256
+#[derive(Clone)]
+pub struct Rfc6962Algorithm<H: Hasher> {
+    hasher: PhantomData<H>,
+}
+
+impl<H: Hasher> Hasher for Rfc6962Algorithm<H>
+where
+    H::Hash: AsRef<[u8]>,
+{
+    type Hash = H::Hash;
+
+    const ODD_NODE_POLICY: OddNodePolicy = OddNodePolicy::Promote;
+
+    /// The leaves are already prefixed by [`Hasher::hash`]
+    const LEAF_HASH_POLICY: LeafHashPolicy = LeafHashPolicy::Plain;
+
+    const ALGORITHM_ID: u16 = hasher::wrapped_algorithm_id(1, H::ALGORITHM_ID);
+
+    /// Returns the leaf hash of the data, i.e. `H(0x00 || data)`
+    fn hash(data: &[u8]) -> Self::Hash {
+        let mut prefixed = Vec::with_capacity(data.len() + 1);
+        prefixed.push(LEAF_PREFIX);
+        prefixed.extend_from_slice(data);
+
+        H::hash(&prefixed)
+    }
+
+    /// Returns the internal node hash, i.e. `H(0x01 || left || right)`. Nodes without a
+    /// sibling are promoted by the default [`Hasher::concat_and_hash`]. The nodes are prefixed
+    /// in a stack buffer, only hashes longer than 64 bytes are concatenated on the heap.
+    fn hash_pair(left: &Self::Hash, right: &Self::Hash) -> Self::Hash {
+        let (left, right) = (left.as_ref(), right.as_ref());
+        let len = 1 + left.len() + right.len();
+
+        if len > NODE_BUFFER_SIZE {
+            let mut concatenated = Vec::with_capacity(len);
+            concatenated.push(NODE_PREFIX);
+            concatenated.extend_from_slice(left);
+            concatenated.extend_from_slice(right);
+
+            return H::hash(&concatenated);
+        }
+
+        let mut buffer = [0u8; NODE_BUFFER_SIZE];
+        buffer[0] = NODE_PREFIX;
+        buffer[1..1 + left.len()].copy_from_slice(left);
+        buffer[1 + left.len()..len].copy_from_slice(right);
+
+        H::hash(&buffer[..len])
+    }
+
+    fn hash_size() -> usize {
+        H::hash_size()
+    }
+
+    /// Hashes the layer through the wrapped hasher, so the default [`Hasher::hash_layer`] of the
+    /// wrapper runs on multiple threads whenever the one of the wrapped hasher does.
+    fn hash_layer_with<W: Hasher<Hash = Self::Hash>>(nodes: &[Self::Hash]) -> Vec<Self::Hash> {
+        H::hash_layer_with::<W>(nodes)
+    }
+}
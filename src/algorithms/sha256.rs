@@ -34,10 +34,25 @@ pub struct Sha256Algorithm {}
 impl Hasher for Sha256Algorithm {
     type Hash = [u8; 32];
 
+    const ALGORITHM_ID: u16 = 1;
+
     fn hash(data: &[u8]) -> [u8; 32] {
         let mut hasher = Sha256::new();
 
         hasher.update(data);
         <[u8; 32]>::from(hasher.finalize_fixed())
     }
+
+    fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+
+        hasher.update(left);
+        hasher.update(right);
+        <[u8; 32]>::from(hasher.finalize_fixed())
+    }
+
+    #[cfg(feature = "rayon")]
+    fn hash_layer_with<W: Hasher<Hash = [u8; 32]>>(nodes: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        crate::hasher::par_hash_layer::<W>(nodes)
+    }
 }
@@ -38,9 +38,23 @@ pub struct Sha384Algorithm {}
 impl Hasher for Sha384Algorithm {
     type Hash = [u8; 48];
 
+    const ALGORITHM_ID: u16 = 2;
+
     fn hash(data: &[u8]) -> [u8; 48] {
         let mut hasher = Sha384::new();
         hasher.update(data);
         <[u8; 48]>::from(hasher.finalize_fixed())
     }
+
+    fn hash_pair(left: &[u8; 48], right: &[u8; 48]) -> [u8; 48] {
+        let mut hasher = Sha384::new();
+        hasher.update(left);
+        hasher.update(right);
+        <[u8; 48]>::from(hasher.finalize_fixed())
+    }
+
+    #[cfg(feature = "rayon")]
+    fn hash_layer_with<W: Hasher<Hash = [u8; 48]>>(nodes: &[[u8; 48]]) -> Vec<[u8; 48]> {
+        crate::hasher::par_hash_layer::<W>(nodes)
+    }
 }
@@ -0,0 +1,76 @@
+use crate::{hasher, prelude::*, Hasher, LeafHashPolicy, OddNodePolicy};
+use core::marker::PhantomData;
+
+/// Commutative implementation of the [`Hasher`] trait on top of any other [`Hasher`].
+///
+/// The pair of nodes is sorted before being concatenated and hashed, as OpenZeppelin's
+/// [`MerkleProof.sol`] does, so proofs don't need to carry the positions of the nodes. Together
+/// with `Keccak256`, the proofs produced by [`MerkleTree`] and [`SimpleMerkleTree`] can be
+/// verified on chain with `MerkleProof.verify`. Nodes without a sibling are propagated to the
+/// next layer unchanged. The nodes are compared as byte slices, so the hash of the wrapped
+/// hasher has to implement `AsRef<[u8]>`, as all the built-in hashes do.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, algorithms::{SortedPair, Sha256}, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let merkle_tree = MerkleTree::<SortedPair<Sha256>>::from_leaves(&leaves);
+/// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+///
+/// let proof = merkle_tree.proof(&[1]);
+/// assert!(proof.verify_leaf(root, leaves[1]));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleProof.sol`]: https://docs.openzeppelin.com/contracts/5.x/api/utils#MerkleProof
+/// [`MerkleTree`]: crate::MerkleTree
+/// [`SimpleMerkleTree`]: crate::SimpleMerkleTree
+#[derive(Clone)]
+pub struct SortedPairAlgorithm<H: Hasher> {
+    hasher: PhantomData<H>,
+}
+
+impl<H: Hasher> Hasher for SortedPairAlgorithm<H>
+where
+    H::Hash: AsRef<[u8]>,
+{
+    type Hash = H::Hash;
+
+    const ODD_NODE_POLICY: OddNodePolicy = OddNodePolicy::Promote;
+
+    const ALGORITHM_ID: u16 = hasher::wrapped_algorithm_id(2, H::ALGORITHM_ID);
+
+    const LEAF_HASH_POLICY: LeafHashPolicy = H::LEAF_HASH_POLICY;
+
+    fn hash(data: &[u8]) -> Self::Hash {
+        H::hash(data)
+    }
+
+    /// Hashes the nodes with [`Hasher::hash_pair`] of the wrapped hasher, in the ascending byte
+    /// order. The nodes are compared in place, so nothing is allocated on top of what the
+    /// wrapped hasher allocates.
+    fn hash_pair(left: &Self::Hash, right: &Self::Hash) -> Self::Hash {
+        if right.as_ref() < left.as_ref() {
+            H::hash_pair(right, left)
+        } else {
+            H::hash_pair(left, right)
+        }
+    }
+
+    fn hash_size() -> usize {
+        H::hash_size()
+    }
+
+    /// Hashes the layer through the wrapped hasher, so the default [`Hasher::hash_layer`] of the
+    /// wrapper runs on multiple threads whenever the one of the wrapped hasher does.
+    fn hash_layer_with<W: Hasher<Hash = Self::Hash>>(nodes: &[Self::Hash]) -> Vec<Self::Hash> {
+        H::hash_layer_with::<W>(nodes)
+    }
+}
@@ -0,0 +1,75 @@
+//! `BorshSerialize` and `BorshDeserialize` implementations for [`MerkleProof`]. The proof is
+//! serialized as its hashes, the same way as a `Vec` of them, i.e. as the little endian `u32`
+//! number of hashes followed by their bytes, and then as the metadata of the proof, the same way
+//! as an `Option<(Vec<u64>, u64)>` of the leaf indices and the number of leaves.
+
+use crate::{prelude::*, Hasher, MerkleProof};
+use borsh::io::{Error, ErrorKind, Read, Result, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::convert::TryFrom;
+
+type MetadataRepr = Option<(Vec<u64>, u64)>;
+
+fn metadata_repr<T: Hasher>(proof: &MerkleProof<T>) -> MetadataRepr {
+    let leaf_indices = proof.leaf_indices()?;
+    let total_leaves_count = proof.total_leaves_count()?;
+
+    Some((
+        leaf_indices.iter().map(|index| *index as u64).collect(),
+        total_leaves_count as u64,
+    ))
+}
+
+fn to_usize(number: u64) -> Result<usize> {
+    usize::try_from(number).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            "proof metadata doesn't fit into usize",
+        )
+    })
+}
+
+impl<T: Hasher> BorshSerialize for MerkleProof<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let hashes_len = u32::try_from(self.proof_hashes().len())
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "too many proof hashes"))?;
+        hashes_len.serialize(writer)?;
+        for hash in self.proof_hashes() {
+            let bytes: Vec<u8> = (*hash).into();
+            writer.write_all(&bytes)?;
+        }
+
+        metadata_repr(self).serialize(writer)
+    }
+}
+
+impl<T: Hasher> BorshDeserialize for MerkleProof<T> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let hashes_len = u32::deserialize_reader(reader)?;
+
+        let mut proof_hashes = Vec::new();
+        for _ in 0..hashes_len {
+            let mut bytes = vec![0u8; T::hash_size()];
+            reader.read_exact(&mut bytes)?;
+            let hash = T::Hash::try_from(bytes).map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    "proof hash doesn't match the hasher hash type",
+                )
+            })?;
+            proof_hashes.push(hash);
+        }
+
+        match MetadataRepr::deserialize_reader(reader)? {
+            Some((leaf_indices, total_leaves_count)) => Ok(MerkleProof::with_metadata(
+                proof_hashes,
+                leaf_indices
+                    .into_iter()
+                    .map(to_usize)
+                    .collect::<Result<_>>()?,
+                to_usize(total_leaves_count)?,
+            )),
+            None => Ok(MerkleProof::new(proof_hashes)),
+        }
+    }
+}
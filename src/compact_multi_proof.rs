@@ -0,0 +1,421 @@
+use crate::{error::Error, prelude::*, utils, Hasher};
+use core::convert::TryFrom;
+
+/// [`CompactMultiProof`] proves that several leaves belong to a [`MerkleTree`] by encoding a
+/// depth-first traversal of the tree, like the partial merkle trees of Bitcoin's
+/// [BIP 37](https://github.com/bitcoin/bips/blob/master/bip-0037.mediawiki#partial-merkle-branch-format).
+///
+/// Every visited node has a flag that tells whether it's an ancestor of a proven leaf. The
+/// traversal descends into the flagged nodes, and takes the hash of every other visited node
+/// from the proof. Unlike BIP 37, the proven leaves themselves aren't included in the proof, they
+/// are passed to the verifier in the order of their indices, the same way as for
+/// [`MerkleProof::verify`].
+///
+/// The proof holds exactly the same hashes as a [`MerkleProof`] of the same leaves, so it's
+/// never smaller than a proof serialized with [`DirectHashesOrder`]: [`CompactMultiProof::to_bytes`]
+/// adds the number of leaves, usually 1 to 5 bytes, and one bit per flag to the hashes. What the
+/// flags replace are the leaf indices, which the verifier of a [`DirectHashesOrder`] proof has
+/// to get some other way. Compared to [`SelfDescribing`], which carries them in the proof at 8
+/// bytes per index, the compact proof of a large batch of leaves is noticeably smaller.
+///
+/// Proofs are produced by [`MerkleTree::compact_multi_proof`].
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, CompactMultiProof, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+/// let proof_bytes = merkle_tree.compact_multi_proof(&[4, 1])?.to_bytes();
+///
+/// let proof = CompactMultiProof::<Sha256>::from_bytes(&proof_bytes)?;
+/// let (root, leaf_indices) = proof.extract_matches(&[leaves[1], leaves[4]])?;
+///
+/// assert_eq!(Some(root), merkle_tree.root());
+/// assert_eq!(leaf_indices, vec![1, 4]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleTree`]: crate::MerkleTree
+/// [`MerkleTree::compact_multi_proof`]: crate::MerkleTree::compact_multi_proof
+/// [`MerkleProof`]: crate::MerkleProof
+/// [`MerkleProof::verify`]: crate::MerkleProof::verify
+/// [`SelfDescribing`]: crate::proof_serializers::SelfDescribing
+/// [`DirectHashesOrder`]: crate::proof_serializers::DirectHashesOrder
+pub struct CompactMultiProof<T: Hasher> {
+    leaves_count: usize,
+    proof_hashes: Vec<T::Hash>,
+    proof_flags: Vec<bool>,
+}
+
+impl<T: Hasher> CompactMultiProof<T> {
+    /// Creates a proof from the number of leaves of the tree, and the hashes and the flags of
+    /// the traversal. Proofs are usually produced by [`MerkleTree::compact_multi_proof`] or
+    /// parsed with [`CompactMultiProof::from_bytes`]; the proof is checked on verification.
+    ///
+    /// [`MerkleTree::compact_multi_proof`]: crate::MerkleTree::compact_multi_proof
+    pub fn new(leaves_count: usize, proof_hashes: Vec<T::Hash>, proof_flags: Vec<bool>) -> Self {
+        CompactMultiProof {
+            leaves_count,
+            proof_hashes,
+            proof_flags,
+        }
+    }
+
+    /// Traverses a tree of `leaves_count` leaves, whose nodes are returned by `node`. The leaf
+    /// indices have to be sorted, unique and in bounds of the tree.
+    pub(crate) fn build<F>(
+        leaves_count: usize,
+        sorted_leaf_indices: &[usize],
+        node: F,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(usize, usize) -> Result<Option<T::Hash>, Error>,
+    {
+        let mut proof = Self::new(leaves_count, Vec::new(), Vec::new());
+        let layer_lens = utils::indices::layer_lens(leaves_count);
+
+        if let Some(root_layer) = layer_lens.len().checked_sub(1) {
+            proof.traverse(&layer_lens, root_layer, 0, sorted_leaf_indices, &node)?;
+        }
+
+        Ok(proof)
+    }
+
+    /// Visits the node and its descendants. `leaf_indices` are the proven leaves under the node.
+    fn traverse<F>(
+        &mut self,
+        layer_lens: &[usize],
+        layer_index: usize,
+        node_index: usize,
+        leaf_indices: &[usize],
+        node: &F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(usize, usize) -> Result<Option<T::Hash>, Error>,
+    {
+        let is_ancestor = !leaf_indices.is_empty();
+        self.proof_flags.push(is_ancestor);
+
+        if !is_ancestor {
+            let hash = node(layer_index, node_index)?.ok_or_else(|| {
+                Error::node_store_error(format!(
+                    "node {} of layer {} is missing",
+                    node_index, layer_index
+                ))
+            })?;
+            self.proof_hashes.push(hash);
+            return Ok(());
+        }
+        if layer_index == 0 {
+            return Ok(());
+        }
+
+        let child_layer = layer_index - 1;
+        let left_child = node_index * 2;
+        // Leaves of the left child have the lowest bit of their ancestor index on the child
+        // layer unset
+        let split = leaf_indices.partition_point(|leaf| (leaf >> child_layer) & 1 == 0);
+
+        self.traverse(
+            layer_lens,
+            child_layer,
+            left_child,
+            &leaf_indices[..split],
+            node,
+        )?;
+        if left_child + 1 < layer_lens[child_layer] {
+            self.traverse(
+                layer_lens,
+                child_layer,
+                left_child + 1,
+                &leaf_indices[split..],
+                node,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Calculates the root from the proven leaves, which have to be sorted by their indices, and
+    /// returns it together with the indices of the leaves.
+    ///
+    /// For examples, please check [`CompactMultiProof`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the proof runs out of flags, hashes or leaves during the traversal,
+    /// or if any of them are left after it.
+    pub fn extract_matches(&self, leaves: &[T::Hash]) -> Result<(T::Hash, Vec<usize>), Error> {
+        let layer_lens = utils::indices::layer_lens(self.leaves_count);
+        let root_layer = layer_lens
+            .len()
+            .checked_sub(1)
+            .ok_or_else(Error::not_enough_hashes_to_calculate_root)?;
+
+        let mut extractor = Extractor::<T> {
+            layer_lens: &layer_lens,
+            proof_hashes: self.proof_hashes.iter(),
+            proof_flags: self.proof_flags.iter(),
+            leaves: leaves.iter(),
+            leaf_indices: Vec::new(),
+        };
+        let root = extractor.extract(root_layer, 0)?;
+
+        if extractor.proof_flags.next().is_some()
+            || extractor.proof_hashes.next().is_some()
+            || extractor.leaves.next().is_some()
+        {
+            return Err(Error::invalid_proof_flags(
+                self.proof_flags.len(),
+                leaves.len(),
+                self.proof_hashes.len(),
+            ));
+        }
+
+        Ok((root, extractor.leaf_indices))
+    }
+
+    /// Checks that the leaves with the given indices belong to the tree with the given root.
+    /// The leaves have to be sorted by their indices.
+    pub fn verify(&self, root: T::Hash, leaf_indices: &[usize], leaves: &[T::Hash]) -> bool {
+        match self.extract_matches(leaves) {
+            Ok((extracted_root, extracted_indices)) => {
+                extracted_root == root && extracted_indices == leaf_indices
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the number of leaves in the tree the proof was made for
+    pub fn leaves_count(&self) -> usize {
+        self.leaves_count
+    }
+
+    /// Returns the hashes of the pruned nodes in the order of the traversal
+    pub fn proof_hashes(&self) -> &[T::Hash] {
+        &self.proof_hashes
+    }
+
+    /// Returns all hashes from the proof as a vector of lower hex strings.
+    /// For a slice of [`Hasher::Hash`], see [`CompactMultiProof::proof_hashes`]
+    pub fn proof_hashes_hex(&self) -> Vec<String> {
+        self.proof_hashes
+            .iter()
+            .map(utils::collections::to_hex_string)
+            .collect()
+    }
+
+    /// Returns the flags of the visited nodes in the order of the traversal. `true` means that
+    /// the node is an ancestor of a proven leaf or a proven leaf itself.
+    pub fn proof_flags(&self) -> &[bool] {
+        &self.proof_flags
+    }
+
+    /// Serializes the proof as the leaves count, as an unsigned LEB128 integer, followed by the
+    /// flags packed into bytes, least significant bit first, and the hashes. The number of flags
+    /// and hashes isn't stored, as it follows from the flags and the shape of the tree.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            MAX_VARINT_SIZE
+                + utils::indices::div_ceil(self.proof_flags.len(), 8)
+                + self.proof_hashes.len() * T::hash_size(),
+        );
+        write_varint(&mut bytes, self.leaves_count as u64);
+
+        for flags in self.proof_flags.chunks(8) {
+            let byte = flags
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (bit, flag)| byte | (u8::from(*flag) << bit));
+            bytes.push(byte);
+        }
+        for hash in &self.proof_hashes {
+            let hash: Vec<u8> = (*hash).into();
+            bytes.extend_from_slice(&hash);
+        }
+
+        bytes
+    }
+
+    /// Parses a proof serialized with [`CompactMultiProof::to_bytes`]. The flags are read until
+    /// the traversal of the tree is complete, and the rest of the input has to be the hashes of
+    /// the pruned nodes.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the input runs out of flags during the traversal, or if the size of
+    /// the rest of the input doesn't match the number of the pruned nodes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (leaves_count, flags_start) = read_varint(bytes)?;
+        let layer_lens = utils::indices::layer_lens(leaves_count);
+
+        let flag_bits = bytes[flags_start..]
+            .iter()
+            .flat_map(|byte| (0..8).map(move |bit| byte & (1 << bit) != 0));
+        let mut counter = FlagCounter {
+            layer_lens: &layer_lens,
+            flags: flag_bits,
+            flags_count: 0,
+            hashes_count: 0,
+        };
+        if let Some(root_layer) = layer_lens.len().checked_sub(1) {
+            counter
+                .count(root_layer, 0)
+                .ok_or_else(|| Error::serialized_proof_too_short(bytes.len(), bytes.len() + 1))?;
+        }
+
+        let hash_size = T::hash_size();
+        let hashes_start = flags_start + utils::indices::div_ceil(counter.flags_count, 8);
+        let expected_len = counter
+            .hashes_count
+            .checked_mul(hash_size)
+            .and_then(|hashes_size| hashes_size.checked_add(hashes_start))
+            .ok_or_else(|| Error::serialized_proof_too_short(bytes.len(), usize::MAX))?;
+        if bytes.len() != expected_len {
+            return Err(Error::serialized_proof_size_mismatch(
+                bytes.len(),
+                expected_len,
+            ));
+        }
+
+        let proof_flags = (0..counter.flags_count)
+            .map(|flag| bytes[flags_start + flag / 8] & (1 << (flag % 8)) != 0)
+            .collect();
+        let proof_hashes = bytes[hashes_start..]
+            .chunks_exact(hash_size)
+            .map(|chunk| {
+                T::Hash::try_from(chunk.to_vec()).map_err(|_| Error::vec_to_hash_conversion_error())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(leaves_count, proof_hashes, proof_flags))
+    }
+}
+
+/// Replays the traversal of [`CompactMultiProof::build`], consuming the flags, the hashes and
+/// the leaves
+struct Extractor<'a, T: Hasher> {
+    layer_lens: &'a [usize],
+    proof_hashes: core::slice::Iter<'a, T::Hash>,
+    proof_flags: core::slice::Iter<'a, bool>,
+    leaves: core::slice::Iter<'a, T::Hash>,
+    leaf_indices: Vec<usize>,
+}
+
+impl<T: Hasher> Extractor<'_, T> {
+    fn extract(&mut self, layer_index: usize, node_index: usize) -> Result<T::Hash, Error> {
+        let is_ancestor = *self
+            .proof_flags
+            .next()
+            .ok_or_else(Error::not_enough_hashes_to_calculate_root)?;
+
+        if !is_ancestor {
+            return self
+                .proof_hashes
+                .next()
+                .copied()
+                .ok_or_else(Error::not_enough_hashes_to_calculate_root);
+        }
+        if layer_index == 0 {
+            self.leaf_indices.push(node_index);
+            return self
+                .leaves
+                .next()
+                .copied()
+                .ok_or_else(Error::not_enough_hashes_to_calculate_root);
+        }
+
+        let child_layer = layer_index - 1;
+        let left_child = node_index * 2;
+        let left = self.extract(child_layer, left_child)?;
+        let right = if left_child + 1 < self.layer_lens[child_layer] {
+            Some(self.extract(child_layer, left_child + 1)?)
+        } else {
+            None
+        };
+
+        Ok(T::concat_and_hash(&left, right.as_ref()))
+    }
+}
+
+/// Replays the traversal of [`CompactMultiProof::build`] on the flags alone, counting the flags
+/// and the hashes it consumes
+struct FlagCounter<'a, I: Iterator<Item = bool>> {
+    layer_lens: &'a [usize],
+    flags: I,
+    flags_count: usize,
+    hashes_count: usize,
+}
+
+impl<I: Iterator<Item = bool>> FlagCounter<'_, I> {
+    /// Returns [`None`] if the traversal runs out of flags
+    fn count(&mut self, layer_index: usize, node_index: usize) -> Option<()> {
+        let is_ancestor = self.flags.next()?;
+        self.flags_count += 1;
+
+        if !is_ancestor {
+            self.hashes_count += 1;
+            return Some(());
+        }
+        if layer_index == 0 {
+            return Some(());
+        }
+
+        let child_layer = layer_index - 1;
+        let left_child = node_index * 2;
+        self.count(child_layer, left_child)?;
+        if left_child + 1 < self.layer_lens[child_layer] {
+            self.count(child_layer, left_child + 1)?;
+        }
+
+        Some(())
+    }
+}
+
+/// The maximum size of a u64 encoded as an unsigned LEB128 integer
+const MAX_VARINT_SIZE: usize = 10;
+
+/// Appends the value as an unsigned LEB128 integer, 7 bits per byte, least significant first
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads an unsigned LEB128 integer from the start of the input, and returns it together with
+/// its size
+fn read_varint(bytes: &[u8]) -> Result<(usize, usize), Error> {
+    let mut value = 0u64;
+    for (index, byte) in bytes.iter().take(MAX_VARINT_SIZE).enumerate() {
+        let bits = u64::from(byte & 0x7f);
+        let shift = 7 * index as u32;
+        // Counts that don't fit into usize can't be satisfied by the input either
+        if shift == 63 && bits > 1 {
+            return Err(Error::serialized_proof_too_short(bytes.len(), usize::MAX));
+        }
+        value |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            let value = usize::try_from(value)
+                .map_err(|_| Error::serialized_proof_too_short(bytes.len(), usize::MAX))?;
+            return Ok((value, index + 1));
+        }
+    }
+
+    // Either the input ends in the middle of the integer, or the integer doesn't fit into u64
+    let expected_len = if bytes.len() < MAX_VARINT_SIZE {
+        bytes.len() + 1
+    } else {
+        usize::MAX
+    };
+    Err(Error::serialized_proof_too_short(bytes.len(), expected_len))
+}
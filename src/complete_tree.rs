@@ -0,0 +1,263 @@
+use crate::prelude::*;
+use crate::{error::Error, utils::indices, Hasher, MerkleProof};
+
+/// [`CompleteTree`] keeps all nodes of a tree in a single contiguous vector, layer after layer
+/// from the leaves to the root. A node is located by adding its index to the offset of its
+/// layer, so lookups take constant time and no index is stored next to the hashes, unlike in
+/// [`PartialTree`].
+///
+/// Every layer has room for more nodes than it currently has, so that appending leaves only
+/// moves the nodes when the room runs out. The room is doubled every time, which makes appends
+/// take amortized constant time. A tree built at once, with [`CompleteTree::from_leaves`] or by
+/// a single commit of a [`MerkleTree`], has no spare room at all.
+///
+/// This is the layout of [`MemoryStore`], which keeps the committed nodes of a [`MerkleTree`]
+/// by default.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{CompleteTree, MerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let tree = CompleteTree::<Sha256>::from_leaves(&leaves);
+/// let root = tree.root().ok_or("couldn't get the merkle root")?;
+///
+/// assert_eq!(Some(*root), MerkleTree::<Sha256>::from_leaves(&leaves).root());
+/// assert_eq!(tree.get(1, 2), Some(&leaves[4]));
+/// assert!(tree.proof(&[1, 4]).verify(*root, &[1, 4], &[leaves[1], leaves[4]], leaves.len()));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`PartialTree`]: crate::PartialTree
+/// [`MerkleTree`]: crate::MerkleTree
+/// [`MemoryStore`]: crate::node_stores::MemoryStore
+#[derive(Clone)]
+pub struct CompleteTree<T: Hasher> {
+    nodes: Vec<T::Hash>,
+    /// Offsets of the layers in `nodes`, followed by the size of the room for all layers
+    offsets: Vec<usize>,
+    layer_lens: Vec<usize>,
+}
+
+impl<T: Hasher> Default for CompleteTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hasher> CompleteTree<T> {
+    /// Creates an empty tree
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            offsets: vec![0],
+            layer_lens: Vec::new(),
+        }
+    }
+
+    /// Builds the tree from the leaves, calculating the parent nodes the same way as
+    /// [`MerkleTree`] does.
+    ///
+    /// [`MerkleTree`]: crate::MerkleTree
+    pub fn from_leaves(leaves: &[T::Hash]) -> Self {
+        let layer_lens = indices::layer_lens(leaves.len());
+        let offsets = layout(leaves.len());
+        let mut nodes = Vec::with_capacity(offsets[offsets.len() - 1]);
+        nodes.extend_from_slice(leaves);
+
+        for layer_index in 1..layer_lens.len() {
+            let layer = &nodes[offsets[layer_index - 1]..];
+            // The root is carried over unchanged, so it doesn't get rehashed by the odd node
+            // policy of the hasher
+            let parents = match layer {
+                [root] => vec![*root],
+                _ => T::hash_layer(layer),
+            };
+            nodes.extend(parents);
+        }
+
+        Self {
+            nodes,
+            offsets,
+            layer_lens,
+        }
+    }
+
+    /// Returns the number of leaves in the tree
+    pub fn leaves_len(&self) -> usize {
+        self.layer_len(0)
+    }
+
+    /// Returns the number of layers, including the root layer
+    pub fn layers_len(&self) -> usize {
+        self.layer_lens.len()
+    }
+
+    /// Returns the number of nodes on the given layer
+    pub fn layer_len(&self, layer_index: usize) -> usize {
+        self.layer_lens.get(layer_index).copied().unwrap_or(0)
+    }
+
+    /// Returns the nodes of the given layer, or an empty slice if there is no such layer
+    pub fn layer(&self, layer_index: usize) -> &[T::Hash] {
+        match self.layer_lens.get(layer_index) {
+            Some(layer_len) => {
+                let offset = self.offsets[layer_index];
+                &self.nodes[offset..offset + layer_len]
+            }
+            None => &[],
+        }
+    }
+
+    /// Returns all layers of the tree, from the leaves to the root
+    pub fn layers(&self) -> Vec<&[T::Hash]> {
+        (0..self.layers_len())
+            .map(|layer_index| self.layer(layer_index))
+            .collect()
+    }
+
+    /// Returns the node with the given index on the given layer, or [`None`] if it's out of
+    /// bounds of the layer. Takes constant time.
+    pub fn get(&self, layer_index: usize, node_index: usize) -> Option<&T::Hash> {
+        if node_index >= self.layer_len(layer_index) {
+            return None;
+        }
+
+        self.nodes.get(self.offsets[layer_index] + node_index)
+    }
+
+    /// Returns the root of the tree, or [`None`] if the tree is empty
+    pub fn root(&self) -> Option<&T::Hash> {
+        self.get(self.layers_len().checked_sub(1)?, 0)
+    }
+
+    /// Returns the Merkle proof for the given leaves, the same as [`MerkleTree::proof`]. Takes
+    /// `O(k log n)` time for `k` leaves of a tree of `n` leaves.
+    ///
+    /// For examples, please check [`CompleteTree`]
+    ///
+    /// [`MerkleTree::proof`]: crate::MerkleTree::proof
+    pub fn proof(&self, leaf_indices: &[usize]) -> MerkleProof<T> {
+        let mut layer_indices = leaf_indices.to_vec();
+        layer_indices.sort_unstable();
+        layer_indices.dedup();
+
+        let mut proof_hashes = Vec::new();
+        for layer_index in 0..self.layers_len() {
+            for index in indices::helper_indices(&layer_indices, self.layer_len(layer_index)) {
+                proof_hashes.extend(self.get(layer_index, index));
+            }
+            layer_indices = indices::parent_indices(&layer_indices);
+        }
+
+        MerkleProof::with_metadata(proof_hashes, leaf_indices.to_vec(), self.leaves_len())
+    }
+
+    /// Writes a single node, replacing the existing one or appending it to the end of the
+    /// layer, see [`NodeStore::put`].
+    ///
+    /// [`NodeStore::put`]: crate::NodeStore::put
+    pub(crate) fn set(
+        &mut self,
+        layer_index: usize,
+        node_index: usize,
+        hash: T::Hash,
+    ) -> Result<(), Error> {
+        if layer_index > self.layers_len() || node_index > self.layer_len(layer_index) {
+            return Err(Error::node_store_error(format!(
+                "node {} of layer {} would leave a gap in the tree",
+                node_index, layer_index
+            )));
+        }
+
+        if layer_index == self.layers_len() {
+            self.layer_lens.push(0);
+        }
+        if node_index == self.layer_lens[layer_index] {
+            if !self.has_room(layer_index, node_index + 1) {
+                let mut layer_lens = self.layer_lens.clone();
+                layer_lens[layer_index] = node_index + 1;
+                self.reserve(&layer_lens);
+            }
+            self.layer_lens[layer_index] += 1;
+        }
+
+        let position = self.offsets[layer_index] + node_index;
+        match self.nodes.get_mut(position) {
+            Some(node) => *node = hash,
+            None => self.nodes.resize(position + 1, hash),
+        }
+
+        Ok(())
+    }
+
+    /// Resizes the layers to the given lengths, removing the layers above them. The nodes
+    /// added to the layers have to be written with [`CompleteTree::set`] afterwards.
+    pub(crate) fn resize_layers(&mut self, layer_lens: &[usize]) {
+        self.reserve(layer_lens);
+        self.layer_lens = layer_lens.to_vec();
+    }
+
+    /// Checks whether the given layer has room for `layer_len` nodes
+    fn has_room(&self, layer_index: usize, layer_len: usize) -> bool {
+        match self.offsets.get(layer_index + 1) {
+            Some(end) => end - self.offsets[layer_index] >= layer_len,
+            None => layer_len == 0,
+        }
+    }
+
+    /// Moves the layers apart, so that they have room for the given number of nodes. The room
+    /// is at least doubled, unless the tree is empty.
+    fn reserve(&mut self, layer_lens: &[usize]) {
+        let fits = |tree: &Self| {
+            layer_lens
+                .iter()
+                .enumerate()
+                .all(|(layer_index, layer_len)| tree.has_room(layer_index, *layer_len))
+        };
+        if fits(self) {
+            return;
+        }
+
+        let capacity = self.offsets.get(1).copied().unwrap_or(0);
+        let mut new_capacity = (capacity * 2).max(layer_lens.first().copied().unwrap_or(0));
+        let mut new_tree = Self {
+            nodes: Vec::new(),
+            offsets: layout(new_capacity),
+            layer_lens: Vec::new(),
+        };
+        while !fits(&new_tree) {
+            new_capacity = (new_capacity * 2).max(1);
+            new_tree.offsets = layout(new_capacity);
+        }
+
+        for (layer_index, layer) in self.layers().into_iter().enumerate() {
+            if let Some(first_node) = layer.first() {
+                new_tree
+                    .nodes
+                    .resize(new_tree.offsets[layer_index], *first_node);
+                new_tree.nodes.extend_from_slice(layer);
+            }
+        }
+
+        self.nodes = new_tree.nodes;
+        self.offsets = new_tree.offsets;
+    }
+}
+
+/// Returns the offsets of the layers of a tree with room for `capacity` leaves, followed by the
+/// size of the room for all layers
+fn layout(capacity: usize) -> Vec<usize> {
+    let mut offsets = vec![0];
+    for layer_len in indices::layer_lens(capacity) {
+        offsets.push(offsets[offsets.len() - 1] + layer_len);
+    }
+
+    offsets
+}
@@ -0,0 +1,178 @@
+use crate::{
+    error::Error,
+    prelude::*,
+    proof_serializers::{DirectHashesOrder, MerkleProofSerializer},
+    utils, Hasher, MerkleProof,
+};
+use core::fmt::{Debug, Formatter};
+
+/// [`ConsistencyProof`] proves that a tree is an append-only extension of its older version,
+/// i.e. that the first `old_size` leaves of the new tree are exactly the leaves of the old one.
+///
+/// The proof format follows [RFC 6962] and [RFC 9162], so it is compatible with Certificate
+/// Transparency logs and monitors, as long as the tree nodes are hashed the same way, e.g. with
+/// [`Rfc6962`]. Proofs are produced by [`MerkleTree::consistency_proof`].
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let old_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..3]);
+/// let new_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+/// let old_root = old_tree.root().ok_or("couldn't get the merkle root")?;
+/// let new_root = new_tree.root().ok_or("couldn't get the merkle root")?;
+///
+/// let proof = new_tree.consistency_proof(3, 6)?;
+/// assert!(proof.verify(old_root, new_root, 3, 6));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [RFC 6962]: https://www.rfc-editor.org/rfc/rfc6962#section-2.1.2
+/// [RFC 9162]: https://www.rfc-editor.org/rfc/rfc9162#section-2.1.4
+/// [`MerkleTree::consistency_proof`]: crate::MerkleTree::consistency_proof
+/// [`Rfc6962`]: crate::algorithms::Rfc6962
+#[derive(Clone)]
+pub struct ConsistencyProof<T: Hasher> {
+    proof_hashes: Vec<T::Hash>,
+}
+
+impl<T: Hasher> ConsistencyProof<T> {
+    /// Creates a proof from the hashes in the order defined by RFC 6962, as returned by
+    /// [`ConsistencyProof::proof_hashes`]
+    pub fn new(proof_hashes: Vec<T::Hash>) -> Self {
+        ConsistencyProof { proof_hashes }
+    }
+
+    /// Verifies that the tree of `old_size` leaves with the `old_root` is a prefix of the tree
+    /// of `new_size` leaves with the `new_root`. Implements the verification algorithm from
+    /// RFC 9162, section 2.1.4.2.
+    ///
+    /// For examples, please check [`ConsistencyProof`]
+    pub fn verify(
+        &self,
+        old_root: T::Hash,
+        new_root: T::Hash,
+        old_size: usize,
+        new_size: usize,
+    ) -> bool {
+        if old_size == 0 || old_size > new_size {
+            return false;
+        }
+
+        if old_size == new_size {
+            return self.proof_hashes.is_empty() && old_root == new_root;
+        }
+
+        if self.proof_hashes.is_empty() {
+            return false;
+        }
+
+        let mut proof_hashes = self.proof_hashes.iter();
+        // If the old tree is a complete subtree of the new one, its root is the first node
+        // of the path
+        let first_hash = if old_size.is_power_of_two() {
+            old_root
+        } else {
+            match proof_hashes.next() {
+                Some(hash) => *hash,
+                None => return false,
+            }
+        };
+
+        let mut first_node = old_size - 1;
+        let mut second_node = new_size - 1;
+        while first_node & 1 == 1 {
+            first_node >>= 1;
+            second_node >>= 1;
+        }
+
+        let mut first_root = first_hash;
+        let mut second_root = first_hash;
+
+        for hash in proof_hashes {
+            if second_node == 0 {
+                return false;
+            }
+
+            if first_node & 1 == 1 || first_node == second_node {
+                first_root = T::concat_and_hash(hash, Some(&first_root));
+                second_root = T::concat_and_hash(hash, Some(&second_root));
+                while first_node & 1 == 0 && first_node != 0 {
+                    first_node >>= 1;
+                    second_node >>= 1;
+                }
+            } else {
+                second_root = T::concat_and_hash(&second_root, Some(hash));
+            }
+
+            first_node >>= 1;
+            second_node >>= 1;
+        }
+
+        second_node == 0 && first_root == old_root && second_root == new_root
+    }
+
+    /// Returns all hashes from the proof in the order defined by RFC 6962
+    pub fn proof_hashes(&self) -> &[T::Hash] {
+        &self.proof_hashes
+    }
+
+    /// Returns all hashes from the proof as a vector of lower hex strings.
+    /// For a slice of [`Hasher::Hash`], see [`ConsistencyProof::proof_hashes`]
+    pub fn proof_hashes_hex(&self) -> Vec<String> {
+        self.proof_hashes
+            .iter()
+            .map(utils::collections::to_hex_string)
+            .collect()
+    }
+
+    /// Serializes the proof hashes in direct order.
+    /// To use a different hashes serializer, see [`ConsistencyProof::serialize`]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.serialize::<DirectHashesOrder>()
+    }
+
+    /// Parses a proof serialized with [`ConsistencyProof::to_bytes`]
+    ///
+    /// ## Errors
+    ///
+    /// In case of a parsing error result will contain [`Error`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::deserialize::<DirectHashesOrder>(bytes)
+    }
+
+    /// Serializes the proof hashes with the given [`MerkleProofSerializer`]
+    pub fn serialize<S: MerkleProofSerializer>(&self) -> Vec<u8> {
+        S::serialize(&MerkleProof::<T>::new(self.proof_hashes.clone()))
+    }
+
+    /// Parses a proof serialized with [`ConsistencyProof::serialize`]
+    ///
+    /// ## Errors
+    ///
+    /// In case of a parsing error result will contain [`Error`]
+    pub fn deserialize<S: MerkleProofSerializer>(bytes: &[u8]) -> Result<Self, Error> {
+        let proof = S::deserialize::<T>(bytes)?;
+        Ok(Self::new(proof.proof_hashes().to_vec()))
+    }
+}
+
+// Implemented by hand, since deriving it would require the hasher to be `Debug` rather than
+// its hashes
+impl<T: Hasher> Debug for ConsistencyProof<T>
+where
+    T::Hash: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ConsistencyProof")
+            .field("proof_hashes", &self.proof_hashes)
+            .finish()
+    }
+}
@@ -23,6 +23,50 @@ pub enum ErrorKind {
     LeavesIndicesCountMismatch,
     DuplicateLeafIndex,
     LeafIndexOutOfBounds,
+    /// The commit isn't present in the [`MerkleTree`] history.
+    ///
+    /// [`MerkleTree`]: crate::MerkleTree
+    CommitNotFound,
+    /// Tree sizes passed to build a consistency proof are out of bounds of the tree or
+    /// don't follow each other.
+    InvalidTreeSize,
+    /// The number of proof flags doesn't match the number of leaves and proof hashes of
+    /// a [`SortedMultiProof`].
+    ///
+    /// [`SortedMultiProof`]: crate::SortedMultiProof
+    InvalidProofFlags,
+    /// A leaf value can't be encoded with the leaf encoding of a `StandardMerkleTree`.
+    InvalidLeafValue,
+    /// A dumped tree can't be loaded because it's malformed or its nodes don't match its values.
+    InvalidTreeDump,
+    /// The key isn't present in the [`SparseMerkleTree`].
+    ///
+    /// [`SparseMerkleTree`]: crate::SparseMerkleTree
+    KeyNotFound,
+    /// The depth of an [`IncrementalMerkleTree`] is zero or too big for the number of its leaves
+    /// to fit into `usize`.
+    ///
+    /// [`IncrementalMerkleTree`]: crate::IncrementalMerkleTree
+    InvalidTreeDepth,
+    /// All the leaves of a fixed depth tree are already taken.
+    TreeIsFull,
+    /// A tree serialized to bytes can't be parsed because it's malformed.
+    InvalidSerializedTree,
+    /// A [`NodeStore`] failed to read or write the tree nodes, or was asked to write a batch
+    /// that leaves gaps in the tree layers.
+    ///
+    /// [`NodeStore`]: crate::NodeStore
+    NodeStoreError,
+    /// A tree serialized to bytes has a format version that isn't supported by this version of
+    /// the library.
+    UnsupportedFormatVersion,
+    /// The root or the nodes of a serialized tree don't match its leaves.
+    RootMismatch,
+    /// A self-describing proof was made with a different hash algorithm than the one used to
+    /// parse it.
+    HashAlgorithmMismatch,
+    /// A proof has hashes left over after its root is calculated.
+    TooManyProofHashes,
 }
 
 /// The error type for tree traversals/parsing errors of the [`MerkleProof`] and [`PartialTree`].
@@ -100,6 +144,130 @@ impl Error {
         )
     }
 
+    pub fn commit_not_found() -> Self {
+        Self::new(
+            ErrorKind::CommitNotFound,
+            String::from("commit is not present in the tree history"),
+        )
+    }
+
+    pub fn invalid_tree_size(old_size: usize, new_size: usize, leaves_count: usize) -> Self {
+        Self::new(
+            ErrorKind::InvalidTreeSize,
+            format!(
+                "tree sizes {} and {} don't satisfy 0 < old size <= new size <= {}",
+                old_size, new_size, leaves_count
+            ),
+        )
+    }
+
+    pub fn invalid_proof_flags(flags_len: usize, leaves_len: usize, proof_len: usize) -> Self {
+        Self::new(
+            ErrorKind::InvalidProofFlags,
+            format!(
+                "{} proof flags can't combine {} leaves and {} proof hashes into a root",
+                flags_len, leaves_len, proof_len
+            ),
+        )
+    }
+
+    pub fn invalid_leaf_value(message: String) -> Self {
+        Self::new(ErrorKind::InvalidLeafValue, message)
+    }
+
+    pub fn invalid_tree_dump(message: String) -> Self {
+        Self::new(ErrorKind::InvalidTreeDump, message)
+    }
+
+    pub fn key_not_found() -> Self {
+        Self::new(
+            ErrorKind::KeyNotFound,
+            String::from("key is not present in the tree"),
+        )
+    }
+
+    pub fn invalid_tree_depth(depth: usize, max_depth: usize) -> Self {
+        Self::new(
+            ErrorKind::InvalidTreeDepth,
+            format!(
+                "tree depth {} doesn't satisfy 0 < depth <= {}",
+                depth, max_depth
+            ),
+        )
+    }
+
+    pub fn tree_is_full(capacity: usize) -> Self {
+        Self::new(
+            ErrorKind::TreeIsFull,
+            format!("tree is full, its capacity is {} leaves", capacity),
+        )
+    }
+
+    pub fn invalid_serialized_tree(message: String) -> Self {
+        Self::new(ErrorKind::InvalidSerializedTree, message)
+    }
+
+    pub fn node_store_error(message: String) -> Self {
+        Self::new(ErrorKind::NodeStoreError, message)
+    }
+
+    pub fn unsupported_format_version(version: u8, supported_version: u8) -> Self {
+        Self::new(
+            ErrorKind::UnsupportedFormatVersion,
+            format!(
+                "format version {} isn't supported, the supported version is {}",
+                version, supported_version
+            ),
+        )
+    }
+
+    pub fn root_mismatch() -> Self {
+        Self::new(
+            ErrorKind::RootMismatch,
+            String::from("serialized root and nodes don't match the leaves of the tree"),
+        )
+    }
+
+    pub fn hash_algorithm_mismatch(expected_id: u16, actual_id: u16) -> Self {
+        Self::new(
+            ErrorKind::HashAlgorithmMismatch,
+            format!(
+                "proof was made with the hash algorithm {:#06x}, expected {:#06x}",
+                actual_id, expected_id
+            ),
+        )
+    }
+
+    pub fn serialized_proof_too_short(proof_len: usize, expected_len: usize) -> Self {
+        Self::new(
+            ErrorKind::SerializedProofSizeIsIncorrect,
+            format!(
+                "proof of size {} bytes is shorter than the expected {} bytes",
+                proof_len, expected_len
+            ),
+        )
+    }
+
+    pub fn serialized_proof_size_mismatch(proof_len: usize, expected_len: usize) -> Self {
+        Self::new(
+            ErrorKind::SerializedProofSizeIsIncorrect,
+            format!(
+                "proof of size {} bytes doesn't match the size of {} bytes from its header",
+                proof_len, expected_len
+            ),
+        )
+    }
+
+    pub fn too_many_proof_hashes(proof_len: usize, used_len: usize) -> Self {
+        Self::new(
+            ErrorKind::TooManyProofHashes,
+            format!(
+                "proof has {} hashes, but only {} of them are needed to calculate the root",
+                proof_len, used_len
+            ),
+        )
+    }
+
     pub fn kind(&self) -> ErrorKind {
         self.kind
     }
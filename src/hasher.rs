@@ -45,30 +45,168 @@ pub trait Hasher: Clone {
     /// Used by `concat_and_hash` function to build a tree from concatenated hashes
     fn hash(data: &[u8]) -> Self::Hash;
 
-    /// Used by [`MerkleTree`] and [`PartialTree`] when calculating the root.
-    /// The provided default implementation propagates the left node if it doesn't
-    /// have a sibling. The left node should always be present. The right node is optional.
+    /// Defines what happens to the last node of a layer with an odd number of nodes.
+    /// Defaults to [`OddNodePolicy::Promote`]. Used by the default implementation
+    /// of [`Hasher::concat_and_hash`].
+    const ODD_NODE_POLICY: OddNodePolicy = OddNodePolicy::Promote;
+
+    /// Defines how [`Hasher::hash_leaf`] turns the leaf data into a leaf hash. Used by
+    /// [`MerkleTree::from_data`], [`MerkleTree::insert_data`] and [`MerkleProof::verify_data`].
+    /// Defaults to [`LeafHashPolicy::DoubleHash`], so the leaves hashed by the library can't be
+    /// taken for internal nodes. Hashers that separate the leaves on their own in
+    /// [`Hasher::hash`], such as [`Rfc6962`], use [`LeafHashPolicy::Plain`].
+    ///
+    /// [`Rfc6962`]: crate::algorithms::Rfc6962
+    /// [`MerkleTree::from_data`]: crate::MerkleTree::from_data
+    /// [`MerkleTree::insert_data`]: crate::MerkleTree::insert_data
+    /// [`MerkleProof::verify_data`]: crate::MerkleProof::verify_data
+    const LEAF_HASH_POLICY: LeafHashPolicy = LeafHashPolicy::DoubleHash;
+
+    /// Identifies the hash algorithm in self-describing proofs, see [`SelfDescribing`].
+    /// Defaults to `0`, which stands for an unknown algorithm. Built-in hashers use the
+    /// identifiers from `1` to `0xff`, and wrappers of other hashers, such as [`SortedPair`],
+    /// record themselves in the high byte, so that every combination of up to five nested
+    /// wrappers gets its own identifier. Nesting more wrappers doesn't compile.
     ///
-    /// For the tree to be compatible with different types of proofs this function
-    /// needs to be overridden. For example, in Bitcoin implementation,
-    /// if the left node doesn't have a sibling it is concatenated to itself and
-    /// then hashed instead of just being propagated to the next level.
+    /// [`SelfDescribing`]: crate::proof_serializers::SelfDescribing
+    /// [`SortedPair`]: crate::algorithms::SortedPair
+    const ALGORITHM_ID: u16 = 0;
+
+    /// Used by [`MerkleTree`] and [`PartialTree`] when calculating the root.
+    /// The left node should always be present. The right node is optional.
+    /// The provided default implementation hashes the pair of nodes with
+    /// [`Hasher::hash_pair`], and handles the left node without a sibling according
+    /// to [`Hasher::ODD_NODE_POLICY`], so it only needs to be overridden when the nodes have to
+    /// be hashed differently, for example, with a domain separation prefix.
     ///
     /// [`MerkleTree`]: crate::MerkleTree
     /// [`PartialTree`]: crate::PartialTree
     fn concat_and_hash(left: &Self::Hash, right: Option<&Self::Hash>) -> Self::Hash {
-        let mut concatenated: Vec<u8> = (*left).into();
-
         match right {
-            Some(right_node) => {
-                let mut right_node_clone: Vec<u8> = (*right_node).into();
-                concatenated.append(&mut right_node_clone);
-                Self::hash(&concatenated)
-            }
-            None => *left,
+            Some(right_node) => Self::hash_pair(left, right_node),
+            None => match Self::ODD_NODE_POLICY {
+                OddNodePolicy::Promote => *left,
+                OddNodePolicy::Duplicate => Self::hash_pair(left, left),
+                OddNodePolicy::HashWithZero => {
+                    let mut concatenated: Vec<u8> = (*left).into();
+                    concatenated.resize(concatenated.len() * 2, 0);
+                    Self::hash(&concatenated)
+                }
+            },
         }
     }
 
+    /// Returns the hash of the concatenation of two nodes. Used by the default implementation
+    /// of [`Hasher::concat_and_hash`] for every parent node of a tree, so it's the hottest
+    /// path of the tree construction.
+    ///
+    /// The provided default implementation converts both nodes into vectors and hashes their
+    /// concatenation, which allocates on every call. Hashers that can feed the nodes into an
+    /// incremental digest should override it, as the built-in hashers do:
+    ///
+    /// ```
+    /// use rs_merkle::Hasher;
+    /// use sha2::{Sha256, Digest, digest::FixedOutput};
+    ///
+    /// #[derive(Clone)]
+    /// pub struct Sha256Algorithm {}
+    ///
+    /// impl Hasher for Sha256Algorithm {
+    ///     type Hash = [u8; 32];
+    ///
+    ///     fn hash(data: &[u8]) -> [u8; 32] {
+    ///         <[u8; 32]>::from(Sha256::digest(data))
+    ///     }
+    ///
+    ///     fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    ///         let mut hasher = Sha256::new();
+    ///
+    ///         hasher.update(left);
+    ///         hasher.update(right);
+    ///         <[u8; 32]>::from(hasher.finalize_fixed())
+    ///     }
+    /// }
+    /// ```
+    fn hash_pair(left: &Self::Hash, right: &Self::Hash) -> Self::Hash {
+        let mut concatenated: Vec<u8> = (*left).into();
+        let mut right_node: Vec<u8> = (*right).into();
+        concatenated.append(&mut right_node);
+
+        Self::hash(&concatenated)
+    }
+
+    /// Hashes the nodes of a layer in pairs with [`Hasher::concat_and_hash`] and returns the
+    /// parent layer. Used by [`MerkleTree`] and [`PartialTree`] to hash a whole layer at once.
+    ///
+    /// The provided default implementation is [`Hasher::hash_layer_with`] of the hasher itself.
+    /// An override must return the same nodes as the default implementation.
+    ///
+    /// [`MerkleTree`]: crate::MerkleTree
+    /// [`PartialTree`]: crate::PartialTree
+    fn hash_layer(nodes: &[Self::Hash]) -> Vec<Self::Hash> {
+        Self::hash_layer_with::<Self>(nodes)
+    }
+
+    /// Hashes the nodes of a layer in pairs with [`Hasher::concat_and_hash`] of `W`, which
+    /// shares the hash type of this hasher. Wrappers such as [`SortedPair`] and [`Rfc6962`] hash
+    /// their layers through the wrapped hasher this way, so they get to use the same threads.
+    ///
+    /// The provided default implementation hashes the pairs one by one. With the `rayon`
+    /// feature, the built-in hashers override it with [`par_hash_layer`] to hash large layers on
+    /// multiple threads, and so can other hashers with `Send` and `Sync` hashes. An override
+    /// must return the same nodes as the default implementation.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{Hasher, algorithms::{Sha256, SortedPair}};
+    /// let nodes: Vec<[u8; 32]> = ["a", "b", "c"].iter().map(|x| Sha256::hash(x.as_bytes())).collect();
+    ///
+    /// assert_eq!(
+    ///     Sha256::hash_layer_with::<SortedPair<Sha256>>(&nodes),
+    ///     vec![SortedPair::<Sha256>::hash_pair(&nodes[0], &nodes[1]), nodes[2]]
+    /// );
+    /// ```
+    ///
+    /// [`SortedPair`]: crate::algorithms::SortedPair
+    /// [`Rfc6962`]: crate::algorithms::Rfc6962
+    /// [`par_hash_layer`]: crate::par_hash_layer
+    fn hash_layer_with<W: Hasher<Hash = Self::Hash>>(nodes: &[Self::Hash]) -> Vec<Self::Hash> {
+        nodes
+            .chunks(2)
+            .map(|pair| W::concat_and_hash(&pair[0], pair.get(1)))
+            .collect()
+    }
+
+    /// Returns the leaf hash of the data according to [`Hasher::LEAF_HASH_POLICY`].
+    /// Usually doesn't need to be overridden.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{Hasher, LeafHashPolicy, algorithms::Sha256};
+    /// #[derive(Clone)]
+    /// pub struct DoubleSha256 {}
+    ///
+    /// impl Hasher for DoubleSha256 {
+    ///     type Hash = [u8; 32];
+    ///
+    ///     const LEAF_HASH_POLICY: LeafHashPolicy = LeafHashPolicy::DoubleHash;
+    ///
+    ///     fn hash(data: &[u8]) -> [u8; 32] {
+    ///         Sha256::hash(data)
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     DoubleSha256::hash_leaf(b"a"),
+    ///     Sha256::hash(&Sha256::hash(b"a"))
+    /// );
+    /// ```
+    fn hash_leaf(data: &[u8]) -> Self::Hash {
+        Self::LEAF_HASH_POLICY.hash_leaf::<Self>(data)
+    }
+
     /// Returns the byte size of `Self::Hash`. Default implementation returns
     /// `mem::size_of::<Self::Hash>()`. Usually doesn't need to be overridden.
     /// Used internally by `MerkleProof` to parse hashes from a serialized proof.
@@ -76,3 +214,155 @@ pub trait Hasher: Clone {
         mem::size_of::<Self::Hash>()
     }
 }
+
+/// Returns the [`Hasher::ALGORITHM_ID`] of a built-in wrapper around the `inner` hasher. The high
+/// byte of an identifier holds the stack of wrappers as base 3 digits, with `wrapper` being `1`
+/// or `2`, so that the wrappers nested in a different order get different identifiers.
+pub(crate) const fn wrapped_algorithm_id(wrapper: u16, inner: u16) -> u16 {
+    let wrappers = (inner >> 8) * 3 + wrapper;
+    assert!(
+        wrappers <= 0xff,
+        "too many nested hasher wrappers to identify the algorithm"
+    );
+
+    wrappers << 8 | (inner & 0xff)
+}
+
+/// Minimal number of parents hashed by a single rayon task. Smaller layers, such as the layers
+/// of a proof or of a small diff, are effectively hashed on the current thread.
+#[cfg(feature = "rayon")]
+const MIN_PARALLEL_CHUNK_LEN: usize = 1024;
+
+/// Hashes a layer like the default [`Hasher::hash_layer`] of `T`, in parallel chunks. Only the
+/// hashers with `Send` and `Sync` hashes can use it, so the built-in hashers call it from their
+/// own [`Hasher::hash_layer_with`], and other hashers can do the same.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{Hasher, algorithms::Sha256};
+/// #[derive(Clone)]
+/// pub struct ParallelSha256 {}
+///
+/// impl Hasher for ParallelSha256 {
+///     type Hash = [u8; 32];
+///
+///     fn hash(data: &[u8]) -> [u8; 32] {
+///         Sha256::hash(data)
+///     }
+///
+///     fn hash_layer_with<W: Hasher<Hash = [u8; 32]>>(nodes: &[[u8; 32]]) -> Vec<[u8; 32]> {
+///         rs_merkle::par_hash_layer::<W>(nodes)
+///     }
+/// }
+///
+/// let nodes: Vec<[u8; 32]> = (0..5u8).map(|i| Sha256::hash(&[i])).collect();
+/// assert_eq!(ParallelSha256::hash_layer(&nodes), Sha256::hash_layer(&nodes));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_hash_layer<T: Hasher>(nodes: &[T::Hash]) -> Vec<T::Hash>
+where
+    T::Hash: Send + Sync,
+{
+    use rayon::prelude::*;
+
+    nodes
+        .par_chunks(2)
+        .with_min_len(MIN_PARALLEL_CHUNK_LEN)
+        .map(|pair| T::concat_and_hash(&pair[0], pair.get(1)))
+        .collect()
+}
+
+/// Returns the roots of the empty subtrees of heights from 0 to `height`. The empty leaf is a hash
+/// made of zero bytes, or the hash of no data if the hash type can't be made from bytes.
+pub(crate) fn empty_hashes<T: Hasher>(height: usize) -> Vec<T::Hash> {
+    let empty_leaf = T::Hash::try_from(vec![0u8; T::hash_size()]).unwrap_or_else(|_| T::hash(&[]));
+
+    let mut empty_hashes = Vec::with_capacity(height + 1);
+    empty_hashes.push(empty_leaf);
+    for layer in 0..height {
+        let empty_hash = empty_hashes[layer];
+        empty_hashes.push(T::concat_and_hash(&empty_hash, Some(&empty_hash)));
+    }
+
+    empty_hashes
+}
+
+/// Defines how [`Hasher::concat_and_hash`] handles the last node of a layer with an odd number
+/// of nodes. The root of a tree is never rehashed, regardless of the policy, so the root of a tree
+/// with a single leaf is always the leaf itself.
+///
+/// The policy doesn't affect which hashes are included in a [`MerkleProof`]: the last node of an
+/// odd layer never needs a sibling from the proof, as it's either promoted or hashed with a
+/// sibling that can be derived from the node itself.
+///
+/// [`MerkleProof`]: crate::MerkleProof
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OddNodePolicy {
+    /// The node is propagated to the next layer unchanged. This produces the same tree shape as
+    /// [RFC 6962](https://www.rfc-editor.org/rfc/rfc6962#section-2.1).
+    Promote,
+    /// The node is concatenated to itself and hashed, as in Bitcoin. Note that this makes the
+    /// trees with the leaves `[a, b, c]` and `[a, b, c, c]` have the same root (CVE-2012-2459),
+    /// so the users of this policy must reject duplicate leaves on their own.
+    Duplicate,
+    /// The node is concatenated with a zeroed hash of the same size and hashed.
+    HashWithZero,
+}
+
+/// Defines how [`Hasher::hash_leaf`] hashes the leaf data. Hashing the leaves differently from
+/// the internal nodes prevents a second preimage attack, where the concatenation of two nodes is
+/// passed off as the data of a leaf to prove a leaf that isn't in the tree.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LeafHashPolicy {
+    /// The data is hashed once with [`Hasher::hash`], the same way as the internal nodes. Leaves
+    /// can't be told apart from the internal nodes, so only use it with trusted data or with a
+    /// hasher that separates them on its own, such as [`Rfc6962`]. Other hashers only use it
+    /// when it's passed explicitly, e.g. to [`MerkleTree::from_data_with_policy`].
+    ///
+    /// [`Rfc6962`]: crate::algorithms::Rfc6962
+    /// [`MerkleTree::from_data_with_policy`]: crate::MerkleTree::from_data_with_policy
+    Plain,
+    /// The hash of the data is hashed again, as in OpenZeppelin's `StandardMerkleTree`. The
+    /// second hash always takes a single hash as the input, while an internal node takes two.
+    /// This is the default policy of the hashers.
+    DoubleHash,
+    /// The data is prefixed with the given byte and hashed. Fully separates the leaves from the
+    /// internal nodes only if the nodes are hashed with a different prefix, as in RFC 6962;
+    /// otherwise a node whose left child starts with the same byte can still be taken for a leaf.
+    Prefixed(u8),
+}
+
+impl LeafHashPolicy {
+    /// Returns the leaf hash of the data according to this policy, with the hash function of
+    /// `T`. Used by [`Hasher::hash_leaf`] and by the helpers that take the policy as an
+    /// argument, such as [`MerkleTree::from_data_with_policy`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{Hasher, LeafHashPolicy, algorithms::Sha256};
+    /// assert_eq!(
+    ///     LeafHashPolicy::DoubleHash.hash_leaf::<Sha256>(b"a"),
+    ///     Sha256::hash(&Sha256::hash(b"a"))
+    /// );
+    /// ```
+    ///
+    /// [`MerkleTree::from_data_with_policy`]: crate::MerkleTree::from_data_with_policy
+    pub fn hash_leaf<T: Hasher>(self, data: &[u8]) -> T::Hash {
+        match self {
+            LeafHashPolicy::Plain => T::hash(data),
+            LeafHashPolicy::DoubleHash => {
+                let hash: Vec<u8> = T::hash(data).into();
+                T::hash(&hash)
+            }
+            LeafHashPolicy::Prefixed(prefix) => {
+                let mut prefixed = Vec::with_capacity(data.len() + 1);
+                prefixed.push(prefix);
+                prefixed.extend_from_slice(data);
+
+                T::hash(&prefixed)
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{error::Error, hasher, prelude::*, utils, Hasher};
+use core::convert::TryFrom;
+
+/// Size of the serialized depth and leaves count in bytes
+const HEADER_SIZE: usize = 9;
+
+/// [`IncrementalMerkleTree`] is an append-only Merkle tree of a fixed depth that stores only its
+/// rightmost branch, the frontier, as the Ethereum deposit contract and the Zcash note commitment
+/// tree do. It takes `O(depth)` memory regardless of the number of leaves, so it can hold
+/// billions of them, but it can't produce proofs for the leaves.
+///
+/// The tree has `2^depth` leaves; the ones that weren't appended yet are empty leaves made of
+/// zero bytes, and the empty subtrees are represented by precomputed hashes. Its root is the same
+/// as the root of a [`MerkleTree`] of the appended leaves padded with empty leaves to `2^depth`.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{IncrementalMerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut tree = IncrementalMerkleTree::<Sha256>::new(32)?;
+/// tree.append(Sha256::hash("a".as_bytes()))?
+///     .append(Sha256::hash("b".as_bytes()))?;
+///
+/// // Save a checkpoint and restore the tree from it
+/// let checkpoint = tree.to_bytes();
+/// let restored_tree = IncrementalMerkleTree::<Sha256>::from_bytes(&checkpoint)?;
+/// assert_eq!(restored_tree.root(), tree.root());
+/// assert_eq!(restored_tree.leaves_len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleTree`]: crate::MerkleTree
+#[derive(Clone)]
+pub struct IncrementalMerkleTree<T: Hasher> {
+    depth: usize,
+    leaves_len: usize,
+    /// The roots of the complete subtrees on the left of the next leaf, one for every height,
+    /// up to the root of the full tree. Only the heights of the bits set in the number of leaves
+    /// are meaningful.
+    frontier: Vec<T::Hash>,
+    /// Roots of the empty subtrees of every height, from the empty leaf to the empty tree root
+    empty_hashes: Vec<T::Hash>,
+}
+
+impl<T: Hasher> IncrementalMerkleTree<T> {
+    /// The maximum depth of the tree, at which the number of its leaves still fits into `usize`
+    pub const MAX_DEPTH: usize = usize::BITS as usize - 1;
+
+    /// Creates an empty tree of the given depth
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] unless `0 < depth <= MAX_DEPTH`
+    pub fn new(depth: usize) -> Result<Self, Error> {
+        if depth == 0 || depth > Self::MAX_DEPTH {
+            return Err(Error::invalid_tree_depth(depth, Self::MAX_DEPTH));
+        }
+
+        let empty_hashes = hasher::empty_hashes::<T>(depth);
+
+        Ok(Self {
+            depth,
+            leaves_len: 0,
+            frontier: empty_hashes.clone(),
+            empty_hashes,
+        })
+    }
+
+    /// Appends a leaf to the tree. Takes at most `depth` hashes.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the tree already has `2^depth` leaves
+    pub fn append(&mut self, leaf: T::Hash) -> Result<&mut Self, Error> {
+        if self.leaves_len == self.capacity() {
+            return Err(Error::tree_is_full(self.capacity()));
+        }
+
+        let mut node = leaf;
+        let mut size = self.leaves_len;
+        for subtree_root in self.frontier.iter_mut() {
+            if size & 1 == 0 {
+                *subtree_root = node;
+                break;
+            }
+            node = T::concat_and_hash(subtree_root, Some(&node));
+            size >>= 1;
+        }
+        self.leaves_len += 1;
+
+        Ok(self)
+    }
+
+    /// Returns the root of the tree. The root of an empty tree is the root of an empty subtree
+    /// of the tree depth.
+    pub fn root(&self) -> T::Hash {
+        if self.leaves_len == self.capacity() {
+            return self.frontier[self.depth];
+        }
+
+        let mut node = self.empty_hashes[0];
+        for (height, (subtree_root, empty_hash)) in self
+            .frontier
+            .iter()
+            .zip(&self.empty_hashes)
+            .take(self.depth)
+            .enumerate()
+        {
+            node = if self.leaves_len >> height & 1 == 1 {
+                T::concat_and_hash(subtree_root, Some(&node))
+            } else {
+                T::concat_and_hash(&node, Some(empty_hash))
+            };
+        }
+        node
+    }
+
+    /// Same as [`root`](IncrementalMerkleTree::root), but serialized as a hex string
+    pub fn root_hex(&self) -> String {
+        utils::collections::to_hex_string(&self.root())
+    }
+
+    /// Returns the depth of the tree
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of appended leaves
+    pub fn leaves_len(&self) -> usize {
+        self.leaves_len
+    }
+
+    /// Returns the number of leaves the tree can hold, i.e. `2^depth`
+    pub fn capacity(&self) -> usize {
+        1 << self.depth
+    }
+
+    /// Returns the roots of the complete subtrees on the left of the next leaf, from the lowest
+    /// to the highest. There is one subtree for every bit set in the number of leaves.
+    pub fn frontier(&self) -> Vec<T::Hash> {
+        self.frontier
+            .iter()
+            .enumerate()
+            .filter(|(height, _)| self.leaves_len >> height & 1 == 1)
+            .map(|(_, subtree_root)| *subtree_root)
+            .collect()
+    }
+
+    /// Serializes the tree into a checkpoint: the depth as a single byte, the number of leaves
+    /// as a big endian `u64` and the hashes of the [`frontier`](IncrementalMerkleTree::frontier)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let frontier = self.frontier();
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + frontier.len() * T::hash_size());
+
+        bytes.push(self.depth as u8);
+        bytes.extend_from_slice(&(self.leaves_len as u64).to_be_bytes());
+        for subtree_root in frontier {
+            bytes.append(&mut subtree_root.into());
+        }
+
+        bytes
+    }
+
+    /// Restores the tree from a checkpoint made with [`IncrementalMerkleTree::to_bytes`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the checkpoint is malformed
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(Error::invalid_serialized_tree(format!(
+                "checkpoint of {} bytes is shorter than its {} bytes header",
+                bytes.len(),
+                HEADER_SIZE
+            )));
+        }
+
+        let mut tree = Self::new(usize::from(bytes[0]))?;
+
+        let mut leaves_len = [0u8; 8];
+        leaves_len.copy_from_slice(&bytes[1..HEADER_SIZE]);
+        let leaves_len = usize::try_from(u64::from_be_bytes(leaves_len))
+            .ok()
+            .filter(|leaves_len| *leaves_len <= tree.capacity())
+            .ok_or_else(|| {
+                Error::invalid_serialized_tree(String::from(
+                    "number of leaves exceeds the tree capacity",
+                ))
+            })?;
+
+        let hashes = &bytes[HEADER_SIZE..];
+        let frontier_len = leaves_len.count_ones() as usize;
+        if hashes.len() != frontier_len * T::hash_size() {
+            return Err(Error::invalid_serialized_tree(format!(
+                "expected {} frontier hashes of {} bytes, got {} bytes",
+                frontier_len,
+                T::hash_size(),
+                hashes.len()
+            )));
+        }
+
+        let mut chunks = hashes.chunks(T::hash_size());
+        for (height, subtree_root) in tree.frontier.iter_mut().enumerate() {
+            if leaves_len >> height & 1 == 1 {
+                let chunk = chunks.next().unwrap_or_default();
+                *subtree_root = T::Hash::try_from(chunk.to_vec())
+                    .map_err(|_| Error::vec_to_hash_conversion_error())?;
+            }
+        }
+        tree.leaves_len = leaves_len;
+
+        Ok(tree)
+    }
+}
@@ -56,6 +56,24 @@
 //! # }
 //! ```
 //!
+//! The leaves can also be hashed by the library, according to the [`LeafHashPolicy`] of the
+//! hasher, which keeps the leaves apart from the internal nodes, or to the policy given to
+//! [`MerkleTree::from_data_with_policy`]:
+//!
+//! ```
+//! # use rs_merkle::{MerkleTree, algorithms::Sha256};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let leaf_values = ["a", "b", "c", "d", "e", "f"];
+//!
+//! let merkle_tree = MerkleTree::<Sha256>::from_data(&leaf_values);
+//! let merkle_root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+//! let merkle_proof = merkle_tree.proof(&[3, 4]);
+//!
+//! assert!(merkle_proof.verify_data(merkle_root, &[3, 4], &["d", "e"], leaf_values.len()));
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! Advanced usage with rolling several commits back:
 //!
 //! ```
@@ -133,22 +151,64 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+pub use compact_multi_proof::CompactMultiProof;
+pub use complete_tree::CompleteTree;
+pub use consistency_proof::ConsistencyProof;
 pub use error::Error;
 pub use error::ErrorKind;
+#[cfg(feature = "rayon")]
+pub use hasher::par_hash_layer;
 pub use hasher::Hasher;
+pub use hasher::LeafHashPolicy;
+pub use hasher::OddNodePolicy;
+pub use incremental_merkle_tree::IncrementalMerkleTree;
+pub use merkle_mountain_range::MerkleMountainRange;
+pub use merkle_mountain_range_proof::AncestryProof;
+pub use merkle_mountain_range_proof::MerkleMountainRangeProof;
 pub use merkle_proof::MerkleProof;
+pub use merkle_root_builder::MerkleRootBuilder;
+pub use merkle_tree::CommitId;
 pub use merkle_tree::MerkleTree;
+pub use merkle_witness::MerkleWitness;
+pub use node_stores::NodeStore;
 pub use partial_tree::PartialTree;
 pub use proof_serializers::MerkleProofSerializer;
+pub use simple_merkle_tree::SimpleMerkleTree;
+pub use sorted_multi_proof::SortedMultiProof;
+pub use sparse_merkle_proof::SparseMerkleProof;
+pub use sparse_merkle_tree::SparseMerkleTree;
+#[cfg(feature = "keccak256")]
+pub use standard_merkle_tree::StandardMerkleTree;
 
+#[cfg(feature = "borsh")]
+mod borsh_impls;
+mod compact_multi_proof;
+mod complete_tree;
+mod consistency_proof;
 mod error;
 mod hasher;
+mod incremental_merkle_tree;
+mod merkle_mountain_range;
+mod merkle_mountain_range_proof;
 mod merkle_proof;
+mod merkle_root_builder;
 mod merkle_tree;
+mod merkle_witness;
 mod partial_tree;
 mod prelude;
+#[cfg(feature = "parity-scale-codec")]
+mod scale_codec_impls;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod simple_merkle_tree;
+mod sorted_multi_proof;
+mod sparse_merkle_proof;
+mod sparse_merkle_tree;
+#[cfg(feature = "keccak256")]
+mod standard_merkle_tree;
 #[doc(hidden)]
 pub mod utils;
 
 pub mod algorithms;
+pub mod node_stores;
 pub mod proof_serializers;
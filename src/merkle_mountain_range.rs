@@ -0,0 +1,175 @@
+use crate::{
+    error::Error,
+    merkle_mountain_range_proof::{bag_peaks, fold_mountains, mountains},
+    prelude::*,
+    utils, AncestryProof, Hasher, MerkleMountainRangeProof,
+};
+
+/// [`MerkleMountainRange`] is an append-only accumulator: a list of perfect binary Merkle trees,
+/// called mountains, of strictly decreasing heights. Appending a leaf adds a mountain of height
+/// zero and merges the mountains of equal heights, which takes `O(log n)` hashes and never
+/// changes the existing nodes.
+///
+/// The peaks of the mountains are bagged into a single root from the right. Because the nodes
+/// never change, a proof for a range of some size stays valid after more leaves are appended:
+/// [`MerkleMountainRange::ancestry_proof`] proves that the old root is an ancestor of the current
+/// one.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleMountainRange, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut mmr = MerkleMountainRange::<Sha256>::new();
+/// for leaf in ["a", "b", "c"] {
+///     mmr.push(Sha256::hash(leaf.as_bytes()));
+/// }
+///
+/// let ab = Sha256::concat_and_hash(&mmr.leaves()[0], Some(&mmr.leaves()[1]));
+/// assert_eq!(mmr.peaks(), vec![ab, mmr.leaves()[2]]);
+/// assert_eq!(mmr.root(), Some(Sha256::concat_and_hash(&ab, Some(&mmr.leaves()[2]))));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct MerkleMountainRange<T: Hasher> {
+    /// Nodes of every height, from the leaves up. The node `i` of the height `h` is the root of
+    /// the perfect subtree of the leaves from `i * 2^h` to `(i + 1) * 2^h`.
+    layers: Vec<Vec<T::Hash>>,
+}
+
+impl<T: Hasher> Default for MerkleMountainRange<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hasher> MerkleMountainRange<T> {
+    /// Creates an empty range
+    pub fn new() -> Self {
+        Self {
+            layers: vec![Vec::new()],
+        }
+    }
+
+    /// Creates a range from the leaves
+    pub fn from_leaves(leaves: &[T::Hash]) -> Self {
+        let mut mmr = Self::new();
+        for leaf in leaves {
+            mmr.push(*leaf);
+        }
+        mmr
+    }
+
+    /// Appends a leaf and merges the mountains of equal heights
+    pub fn push(&mut self, leaf: T::Hash) -> &mut Self {
+        self.layers[0].push(leaf);
+
+        let mut height = 0;
+        while self.layers[height].len() % 2 == 0 {
+            let layer = &self.layers[height];
+            let parent = T::concat_and_hash(&layer[layer.len() - 2], Some(&layer[layer.len() - 1]));
+
+            if self.layers.len() == height + 1 {
+                self.layers.push(Vec::new());
+            }
+            self.layers[height + 1].push(parent);
+            height += 1;
+        }
+
+        self
+    }
+
+    /// Returns the root of the range, i.e. its peaks bagged from the right, or `None` if the
+    /// range is empty
+    pub fn root(&self) -> Option<T::Hash> {
+        bag_peaks::<T>(&self.peaks())
+    }
+
+    /// Same as [`root`](MerkleMountainRange::root), but serialized as a hex string
+    pub fn root_hex(&self) -> Option<String> {
+        let root = self.root()?;
+        Some(utils::collections::to_hex_string(&root))
+    }
+
+    /// Returns the peaks of the mountains, from the highest to the lowest
+    pub fn peaks(&self) -> Vec<T::Hash> {
+        mountains(self.leaves_len())
+            .into_iter()
+            .map(|(height, index)| self.layers[height][index])
+            .collect()
+    }
+
+    /// Returns the leaves of the range
+    pub fn leaves(&self) -> &[T::Hash] {
+        &self.layers[0]
+    }
+
+    /// Returns the number of leaves in the range
+    pub fn leaves_len(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    /// Returns the proof that the leaves with the given indices are included in the range.
+    /// Multiple leaves share the proof hashes of their common subtrees.
+    ///
+    /// For examples, please check [`MerkleMountainRangeProof`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if an index is out of bounds or duplicated
+    pub fn proof(&self, leaf_indices: &[usize]) -> Result<MerkleMountainRangeProof<T>, Error> {
+        let nodes: Vec<(usize, usize, ())> =
+            leaf_indices.iter().map(|index| (0, *index, ())).collect();
+        self.proof_for_nodes(&nodes)
+    }
+
+    /// Returns the proof that the range of the first `old_leaves_len` leaves is a prefix of
+    /// the current range
+    ///
+    /// For examples, please check [`AncestryProof`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] unless `0 < old_leaves_len <= leaves_len()`
+    pub fn ancestry_proof(&self, old_leaves_len: usize) -> Result<AncestryProof<T>, Error> {
+        let leaves_len = self.leaves_len();
+        if old_leaves_len == 0 || old_leaves_len > leaves_len {
+            return Err(Error::invalid_tree_size(
+                old_leaves_len,
+                leaves_len,
+                leaves_len,
+            ));
+        }
+
+        let old_mountains = mountains(old_leaves_len);
+        let old_peaks = old_mountains
+            .iter()
+            .map(|(height, index)| self.layers[*height][*index])
+            .collect();
+        let nodes: Vec<(usize, usize, ())> = old_mountains
+            .into_iter()
+            .map(|(height, index)| (height, index, ()))
+            .collect();
+
+        Ok(AncestryProof::new(old_peaks, self.proof_for_nodes(&nodes)?))
+    }
+
+    fn proof_for_nodes(
+        &self,
+        nodes: &[(usize, usize, ())],
+    ) -> Result<MerkleMountainRangeProof<T>, Error> {
+        let mut proof_hashes = Vec::new();
+        fold_mountains(
+            self.leaves_len(),
+            nodes,
+            |height, index| {
+                proof_hashes.push(self.layers[height][index]);
+                Ok(())
+            },
+            |_, _| (),
+        )?;
+
+        Ok(MerkleMountainRangeProof::new(proof_hashes))
+    }
+}
@@ -0,0 +1,348 @@
+use crate::{
+    error::Error,
+    prelude::*,
+    proof_serializers::{DirectHashesOrder, MerkleProofSerializer},
+    utils, Hasher, MerkleProof,
+};
+
+/// [`MerkleMountainRangeProof`] proves that leaves are included in a [`MerkleMountainRange`]
+/// of a given size.
+///
+/// The proof contains the hashes needed to recalculate the peaks of the mountains that contain
+/// the proven leaves, followed by the peaks of the other mountains, from left to right.
+/// The proof for a range of a given size stays valid after more leaves are appended, together
+/// with an [`AncestryProof`] that the old root is an ancestor of the new one.
+///
+/// The root doesn't commit to the number of leaves, so the verifier needs to get the size of
+/// the range from a trusted source along with the root.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleMountainRange, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f", "g"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+/// let root = mmr.root().ok_or("couldn't get the root")?;
+///
+/// let proof = mmr.proof(&[1, 4, 6])?;
+/// let leaves_to_prove = [leaves[1], leaves[4], leaves[6]];
+/// assert!(proof.verify(root, &[1, 4, 6], &leaves_to_prove, leaves.len()));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleMountainRange`]: crate::MerkleMountainRange
+pub struct MerkleMountainRangeProof<T: Hasher> {
+    proof_hashes: Vec<T::Hash>,
+}
+
+impl<T: Hasher> MerkleMountainRangeProof<T> {
+    pub fn new(proof_hashes: Vec<T::Hash>) -> Self {
+        MerkleMountainRangeProof { proof_hashes }
+    }
+
+    /// Verifies that the leaves with the given indices are included in the range of
+    /// `total_leaves_count` leaves with the given root.
+    ///
+    /// For examples, please check [`MerkleMountainRangeProof`]
+    pub fn verify(
+        &self,
+        root: T::Hash,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> bool {
+        match self.root(leaf_indices, leaf_hashes, total_leaves_count) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Calculates the root of the range based on the provided leaves and proof hashes.
+    /// Used inside the [`MerkleMountainRangeProof::verify`] method.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the leaves don't match the indices, the indices are out of bounds
+    /// or duplicated, or the proof doesn't have exactly the hashes needed to calculate the root.
+    pub fn root(
+        &self,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> Result<T::Hash, Error> {
+        if leaf_indices.len() != leaf_hashes.len() {
+            return Err(Error::leaves_indices_count_mismatch(
+                leaf_indices.len(),
+                leaf_hashes.len(),
+            ));
+        }
+
+        let nodes: Vec<(usize, usize, T::Hash)> = leaf_indices
+            .iter()
+            .zip(leaf_hashes)
+            .map(|(index, hash)| (0, *index, *hash))
+            .collect();
+
+        self.root_from_nodes(&nodes, total_leaves_count)
+    }
+
+    /// Calculates the root of the range from nodes given as `(height, index, hash)` tuples,
+    /// where `index` is the position of the node among the nodes of the same height
+    pub(crate) fn root_from_nodes(
+        &self,
+        nodes: &[(usize, usize, T::Hash)],
+        total_leaves_count: usize,
+    ) -> Result<T::Hash, Error> {
+        let mut proof_hashes = self.proof_hashes.iter();
+        let peaks = fold_mountains(
+            total_leaves_count,
+            nodes,
+            |_, _| {
+                proof_hashes
+                    .next()
+                    .copied()
+                    .ok_or_else(Error::not_enough_hashes_to_calculate_root)
+            },
+            |left, right| T::concat_and_hash(left, Some(right)),
+        )?;
+
+        let unused_len = proof_hashes.len();
+        if unused_len > 0 {
+            return Err(Error::too_many_proof_hashes(
+                self.proof_hashes.len(),
+                self.proof_hashes.len() - unused_len,
+            ));
+        }
+
+        bag_peaks::<T>(&peaks).ok_or_else(Error::not_enough_hashes_to_calculate_root)
+    }
+
+    /// Returns all hashes from the proof, mountain by mountain from the left, bottom to top.
+    pub fn proof_hashes(&self) -> &[T::Hash] {
+        &self.proof_hashes
+    }
+
+    /// Returns all hashes from the proof as a vector of lower hex strings.
+    /// For a slice of [`Hasher::Hash`], see [`MerkleMountainRangeProof::proof_hashes`]
+    pub fn proof_hashes_hex(&self) -> Vec<String> {
+        self.proof_hashes
+            .iter()
+            .map(utils::collections::to_hex_string)
+            .collect()
+    }
+
+    /// Serializes the proof hashes in direct order.
+    /// To use a different hashes serializer, see [`MerkleMountainRangeProof::serialize`]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.serialize::<DirectHashesOrder>()
+    }
+
+    /// Parses a proof serialized with [`MerkleMountainRangeProof::to_bytes`]
+    ///
+    /// ## Errors
+    ///
+    /// In case of a parsing error result will contain [`Error`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::deserialize::<DirectHashesOrder>(bytes)
+    }
+
+    /// Serializes the proof hashes with the given [`MerkleProofSerializer`]
+    pub fn serialize<S: MerkleProofSerializer>(&self) -> Vec<u8> {
+        S::serialize(&MerkleProof::<T>::new(self.proof_hashes.clone()))
+    }
+
+    /// Parses a proof serialized with [`MerkleMountainRangeProof::serialize`]
+    ///
+    /// ## Errors
+    ///
+    /// In case of a parsing error result will contain [`Error`]
+    pub fn deserialize<S: MerkleProofSerializer>(bytes: &[u8]) -> Result<Self, Error> {
+        let proof = S::deserialize::<T>(bytes)?;
+        Ok(Self::new(proof.proof_hashes().to_vec()))
+    }
+}
+
+/// [`AncestryProof`] proves that a [`MerkleMountainRange`] is an append-only extension of its
+/// older version, i.e. that the old root is an ancestor of the new one.
+///
+/// The proof contains the peaks of the old range, which bag into the old root, and a
+/// [`MerkleMountainRangeProof`] that the old peaks are nodes of the new range.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleMountainRange, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut mmr = MerkleMountainRange::<Sha256>::new();
+/// for leaf in ["a", "b", "c"] {
+///     mmr.push(Sha256::hash(leaf.as_bytes()));
+/// }
+/// let old_root = mmr.root().ok_or("couldn't get the root")?;
+///
+/// for leaf in ["d", "e"] {
+///     mmr.push(Sha256::hash(leaf.as_bytes()));
+/// }
+/// let new_root = mmr.root().ok_or("couldn't get the root")?;
+///
+/// let proof = mmr.ancestry_proof(3)?;
+/// assert!(proof.verify(old_root, new_root, 3, 5));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleMountainRange`]: crate::MerkleMountainRange
+pub struct AncestryProof<T: Hasher> {
+    old_peaks: Vec<T::Hash>,
+    proof: MerkleMountainRangeProof<T>,
+}
+
+impl<T: Hasher> AncestryProof<T> {
+    pub fn new(old_peaks: Vec<T::Hash>, proof: MerkleMountainRangeProof<T>) -> Self {
+        AncestryProof { old_peaks, proof }
+    }
+
+    /// Verifies that the range of `old_leaves_count` leaves with the `old_root` is a prefix of
+    /// the range of `new_leaves_count` leaves with the `new_root`.
+    ///
+    /// For examples, please check [`AncestryProof`]
+    pub fn verify(
+        &self,
+        old_root: T::Hash,
+        new_root: T::Hash,
+        old_leaves_count: usize,
+        new_leaves_count: usize,
+    ) -> bool {
+        if old_leaves_count == 0 || old_leaves_count > new_leaves_count {
+            return false;
+        }
+
+        let old_mountains = mountains(old_leaves_count);
+        if old_mountains.len() != self.old_peaks.len()
+            || bag_peaks::<T>(&self.old_peaks) != Some(old_root)
+        {
+            return false;
+        }
+
+        let nodes: Vec<(usize, usize, T::Hash)> = old_mountains
+            .iter()
+            .zip(&self.old_peaks)
+            .map(|((height, index), peak)| (*height, *index, *peak))
+            .collect();
+
+        match self.proof.root_from_nodes(&nodes, new_leaves_count) {
+            Ok(extracted_root) => extracted_root == new_root,
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the peaks of the old range, from left to right
+    pub fn old_peaks(&self) -> &[T::Hash] {
+        &self.old_peaks
+    }
+
+    /// Returns the proof that the old peaks are nodes of the new range
+    pub fn proof(&self) -> &MerkleMountainRangeProof<T> {
+        &self.proof
+    }
+}
+
+/// Returns the peaks of a range of the given size as `(height, index)` pairs, from left to
+/// right. Each mountain is a perfect binary tree, and there is one mountain for every bit set
+/// in the number of leaves.
+pub(crate) fn mountains(leaves_count: usize) -> Vec<(usize, usize)> {
+    (0..usize::BITS as usize)
+        .rev()
+        .filter(|height| leaves_count >> height & 1 == 1)
+        .map(|height| (height, (leaves_count >> height) - 1))
+        .collect()
+}
+
+/// Calculates the peaks of a range of the given size from the known nodes, given as
+/// `(height, index, value)` tuples. `missing` is called, in the order the proof hashes are
+/// stored, with the position of every node that can't be calculated from the known nodes:
+/// for every mountain, the siblings layer by layer from the bottom, or the peak itself if the
+/// mountain has no known nodes.
+pub(crate) fn fold_mountains<V: Copy>(
+    leaves_count: usize,
+    nodes: &[(usize, usize, V)],
+    mut missing: impl FnMut(usize, usize) -> Result<V, Error>,
+    combine: impl Fn(&V, &V) -> V,
+) -> Result<Vec<V>, Error> {
+    let mountains = mountains(leaves_count);
+    let in_range = |(height, index): (usize, usize)| {
+        mountains.iter().any(|(peak_height, peak_index)| {
+            height <= *peak_height && index >> (peak_height - height) == *peak_index
+        })
+    };
+    if nodes
+        .iter()
+        .any(|(height, index, _)| !in_range((*height, *index)))
+    {
+        return Err(Error::leaf_index_out_of_bounds(leaves_count));
+    }
+
+    let mut peaks = Vec::with_capacity(mountains.len());
+
+    for (peak_height, peak_index) in mountains {
+        let mut layer: Vec<(usize, V)> = Vec::new();
+
+        for height in 0..=peak_height {
+            layer.extend(
+                nodes
+                    .iter()
+                    .filter(|(node_height, index, _)| {
+                        *node_height == height && index >> (peak_height - height) == peak_index
+                    })
+                    .map(|(_, index, value)| (*index, *value)),
+            );
+            layer.sort_by_key(|(index, _)| *index);
+            if layer.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+                return Err(Error::duplicate_leaf_index());
+            }
+
+            if height == peak_height {
+                break;
+            }
+
+            let mut parents = Vec::with_capacity(layer.len() / 2 + 1);
+            let mut nodes_iter = layer.iter().peekable();
+            while let Some((index, value)) = nodes_iter.next() {
+                let parent = if index % 2 == 1 {
+                    combine(&missing(height, index - 1)?, value)
+                } else if let Some((_, sibling)) =
+                    nodes_iter.next_if(|(sibling_index, _)| *sibling_index == index + 1)
+                {
+                    combine(value, sibling)
+                } else {
+                    combine(value, &missing(height, index + 1)?)
+                };
+                parents.push((index / 2, parent));
+            }
+            layer = parents;
+        }
+
+        let peak = match layer.first() {
+            Some((_, peak)) => *peak,
+            None => missing(peak_height, peak_index)?,
+        };
+        peaks.push(peak);
+    }
+
+    Ok(peaks)
+}
+
+/// Bags the peaks into a single root, folding them from the right:
+/// `H(peak_0 || H(peak_1 || ... H(peak_n-2 || peak_n-1)))`
+pub(crate) fn bag_peaks<T: Hasher>(peaks: &[T::Hash]) -> Option<T::Hash> {
+    peaks
+        .iter()
+        .rev()
+        .copied()
+        .reduce(|right, left| T::concat_and_hash(&left, Some(&right)))
+}
@@ -1,9 +1,10 @@
 use crate::{
+    algorithms::SortedPair,
     error::Error,
     partial_tree::PartialTree,
     prelude::*,
     proof_serializers::{DirectHashesOrder, MerkleProofSerializer},
-    utils, Hasher,
+    utils, Hasher, LeafHashPolicy,
 };
 use core::convert::TryFrom;
 
@@ -48,11 +49,34 @@ use core::convert::TryFrom;
 /// [`algorithms::Sha256`]: crate::algorithms::Sha256
 pub struct MerkleProof<T: Hasher> {
     proof_hashes: Vec<T::Hash>,
+    /// Indices of the proven leaves and the number of leaves in the tree, if they are known
+    metadata: Option<(Vec<usize>, usize)>,
 }
 
 impl<T: Hasher> MerkleProof<T> {
     pub fn new(proof_hashes: Vec<T::Hash>) -> Self {
-        MerkleProof { proof_hashes }
+        MerkleProof {
+            proof_hashes,
+            metadata: None,
+        }
+    }
+
+    /// Creates a proof that knows the indices of the leaves it proves and the number of leaves
+    /// in the tree, so it can be verified with [`MerkleProof::verify_with_metadata`] and
+    /// serialized with [`SelfDescribing`]. Proofs made by [`MerkleTree::proof`] carry the
+    /// metadata already.
+    ///
+    /// [`SelfDescribing`]: crate::proof_serializers::SelfDescribing
+    /// [`MerkleTree::proof`]: crate::MerkleTree::proof
+    pub fn with_metadata(
+        proof_hashes: Vec<T::Hash>,
+        leaf_indices: Vec<usize>,
+        total_leaves_count: usize,
+    ) -> Self {
+        MerkleProof {
+            proof_hashes,
+            metadata: Some((leaf_indices, total_leaves_count)),
+        }
     }
 
     /// Creates a proof from a slice of bytes, direct hashes order. If you're looking for
@@ -169,6 +193,101 @@ impl<T: Hasher> MerkleProof<T> {
         }
     }
 
+    /// Same as [`MerkleProof::verify`], but takes the data of the leaves instead of the leaf
+    /// hashes, and hashes it with [`Hasher::hash_leaf`], the same way as
+    /// [`MerkleTree::from_data`] and [`MerkleTree::insert_data`] do.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = ["a", "b", "c", "d", "e"];
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_data(&data);
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    /// let proof = merkle_tree.proof(&[1, 3]);
+    ///
+    /// assert!(proof.verify_data(root, &[1, 3], &["b", "d"], data.len()));
+    /// assert!(!proof.verify_data(root, &[1, 3], &["b", "e"], data.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MerkleTree::from_data`]: crate::MerkleTree::from_data
+    /// [`MerkleTree::insert_data`]: crate::MerkleTree::insert_data
+    pub fn verify_data<D: AsRef<[u8]>>(
+        &self,
+        root: T::Hash,
+        leaf_indices: &[usize],
+        leaf_data: &[D],
+        total_leaves_count: usize,
+    ) -> bool {
+        let leaf_hashes: Vec<T::Hash> = leaf_data
+            .iter()
+            .map(|data| T::hash_leaf(data.as_ref()))
+            .collect();
+
+        self.verify(root, leaf_indices, &leaf_hashes, total_leaves_count)
+    }
+
+    /// Same as [`MerkleProof::verify_data`], but hashes the leaves according to the given policy
+    /// instead of [`Hasher::LEAF_HASH_POLICY`]. Verifies the proofs of the trees built with
+    /// [`MerkleTree::from_data_with_policy`] and the same policy.
+    ///
+    /// [`MerkleTree::from_data_with_policy`]: crate::MerkleTree::from_data_with_policy
+    pub fn verify_data_with_policy<D: AsRef<[u8]>>(
+        &self,
+        root: T::Hash,
+        leaf_indices: &[usize],
+        leaf_data: &[D],
+        total_leaves_count: usize,
+        policy: LeafHashPolicy,
+    ) -> bool {
+        let leaf_hashes: Vec<T::Hash> = leaf_data
+            .iter()
+            .map(|data| policy.hash_leaf::<T>(data.as_ref()))
+            .collect();
+
+        self.verify(root, leaf_indices, &leaf_hashes, total_leaves_count)
+    }
+
+    /// Same as [`MerkleProof::verify`], but takes the leaf indices and the number of leaves
+    /// from the proof metadata, so the verifier only needs the root and the leaves. The leaves
+    /// have to be in the same order as [`MerkleProof::leaf_indices`]. Returns `false` if the
+    /// proof has no metadata.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher};
+    /// # use rs_merkle::proof_serializers::SelfDescribing;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    /// let proof_bytes = merkle_tree.proof(&[1, 3]).serialize::<SelfDescribing>();
+    ///
+    /// // The verifier doesn't need to know the indices and the size of the tree
+    /// let proof = MerkleProof::<Sha256>::deserialize::<SelfDescribing>(&proof_bytes)?;
+    /// assert_eq!(proof.leaf_indices(), Some(&[1, 3][..]));
+    /// assert!(proof.verify_with_metadata(root, &[leaves[1], leaves[3]]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify_with_metadata(&self, root: T::Hash, leaf_hashes: &[T::Hash]) -> bool {
+        match &self.metadata {
+            Some((leaf_indices, total_leaves_count)) => {
+                self.verify(root, leaf_indices, leaf_hashes, *total_leaves_count)
+            }
+            None => false,
+        }
+    }
+
     /// Calculates Merkle root based on provided leaves and proof hashes. Used inside the
     /// [`MerkleProof::verify`] method, but sometimes can be used on its own.
     ///
@@ -234,7 +353,7 @@ impl<T: Hasher> MerkleProof<T> {
             .zip(leaf_hashes.iter().cloned())
             .collect();
         // Sorting leaves by indexes in case they weren't sorted already
-        leaf_tuples.sort_by(|(a, _), (b, _)| a.cmp(b));
+        leaf_tuples.sort_by_key(|(index, _)| *index);
         // Getting back _sorted_ indices
         let (sorted_indices, _): (Vec<_>, Vec<_>) = leaf_tuples.iter().cloned().unzip();
 
@@ -256,7 +375,7 @@ impl<T: Hasher> MerkleProof<T> {
         match proof_layers.first_mut() {
             Some(first_layer) => {
                 first_layer.append(&mut leaf_tuples);
-                first_layer.sort_by(|(a, _), (b, _)| a.cmp(b));
+                first_layer.sort_by_key(|(index, _)| *index);
             }
             None => proof_layers.push(leaf_tuples),
         }
@@ -342,6 +461,21 @@ impl<T: Hasher> MerkleProof<T> {
         &self.proof_hashes
     }
 
+    /// Returns the indices of the proven leaves, or [`None`] if the proof has no metadata
+    pub fn leaf_indices(&self) -> Option<&[usize]> {
+        self.metadata
+            .as_ref()
+            .map(|(leaf_indices, _)| leaf_indices.as_slice())
+    }
+
+    /// Returns the number of leaves in the tree the proof was made for, or [`None`] if the
+    /// proof has no metadata
+    pub fn total_leaves_count(&self) -> Option<usize> {
+        self.metadata
+            .as_ref()
+            .map(|(_, total_leaves_count)| *total_leaves_count)
+    }
+
     /// Returns all hashes from the proof, sorted from the left to right,
     /// bottom to top, as a vector of lower hex strings.
     /// For a slice of [`Hasher::Hash`], see [`MerkleProof::proof_hashes`]
@@ -472,6 +606,46 @@ impl<T: Hasher> MerkleProof<T> {
     }
 }
 
+impl<H: Hasher> MerkleProof<SortedPair<H>>
+where
+    H::Hash: AsRef<[u8]>,
+{
+    /// Calculates the root from a single leaf, without knowing the position of the leaf or the
+    /// size of the tree. The proof hashes are applied bottom up, the same way OpenZeppelin's
+    /// `MerkleProof.processProof` does.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::{SortedPair, Sha256}, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<SortedPair<Sha256>>::from_leaves(&leaves);
+    /// let proof = merkle_tree.proof(&[4]);
+    ///
+    /// assert_eq!(Some(proof.root_from_leaf(leaves[4])), merkle_tree.root());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn root_from_leaf(&self, leaf: H::Hash) -> H::Hash {
+        self.proof_hashes.iter().fold(leaf, |node, proof_hash| {
+            SortedPair::<H>::concat_and_hash(&node, Some(proof_hash))
+        })
+    }
+
+    /// Checks that the leaf belongs to the tree with the given root. Compatible with
+    /// OpenZeppelin's `MerkleProof.verify`.
+    ///
+    /// For examples, please check [`SortedPair`](crate::algorithms::SortedPair)
+    pub fn verify_leaf(&self, root: H::Hash, leaf: H::Hash) -> bool {
+        self.root_from_leaf(leaf) == root
+    }
+}
+
 impl<T: Hasher> TryFrom<Vec<u8>> for MerkleProof<T> {
     type Error = Error;
 
@@ -0,0 +1,189 @@
+use crate::{prelude::*, utils, Hasher};
+use core::iter::FromIterator;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+/// [`MerkleRootBuilder`] calculates the root of a tree from a stream of leaves, without keeping
+/// the leaves or the layers of the tree in memory. The root is the same as the root of a
+/// [`MerkleTree`] made of the same leaves.
+///
+/// The builder keeps at most one pending node per layer: the left node that waits for its
+/// right sibling. That's `O(log n)` hashes for `n` leaves, so arbitrarily large datasets can
+/// be hashed in constant memory.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleRootBuilder, MerkleTree, algorithms::Sha256, Hasher};
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let mut builder = MerkleRootBuilder::<Sha256>::new();
+/// for leaf in &leaves {
+///     builder.insert(*leaf);
+/// }
+///
+/// assert_eq!(builder.root(), MerkleTree::<Sha256>::from_leaves(&leaves).root());
+///
+/// // The builder can also be collected from an iterator of leaves
+/// let builder: MerkleRootBuilder<Sha256> = leaves.iter().copied().collect();
+/// assert_eq!(builder.leaves_len(), 5);
+/// ```
+///
+/// [`MerkleTree`]: crate::MerkleTree
+#[derive(Clone)]
+pub struct MerkleRootBuilder<T: Hasher> {
+    /// The left nodes that wait for their right siblings, one slot per layer
+    pending: Vec<Option<T::Hash>>,
+    leaves_count: usize,
+}
+
+impl<T: Hasher> Default for MerkleRootBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hasher> MerkleRootBuilder<T> {
+    /// Creates a builder without leaves
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            leaves_count: 0,
+        }
+    }
+
+    /// Adds a leaf to the end of the tree. Takes amortized constant time, since every node is
+    /// hashed once, as soon as both of its children are known.
+    pub fn insert(&mut self, leaf: T::Hash) -> &mut Self {
+        let mut node = leaf;
+
+        for pending_node in self.pending.iter_mut() {
+            match pending_node.take() {
+                Some(left_node) => node = T::concat_and_hash(&left_node, Some(&node)),
+                None => {
+                    *pending_node = Some(node);
+                    self.leaves_count += 1;
+                    return self;
+                }
+            }
+        }
+
+        self.pending.push(Some(node));
+        self.leaves_count += 1;
+        self
+    }
+
+    /// Splits the data from the reader into chunks of `chunk_size` bytes, and adds the hash
+    /// of every chunk as a leaf. The last chunk can be shorter. Returns the number of added
+    /// leaves.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleRootBuilder, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = vec![7u8; 10_000];
+    ///
+    /// let mut builder = MerkleRootBuilder::<Sha256>::new();
+    /// let leaves_count = builder.read_leaves(data.as_slice(), 4096)?;
+    ///
+    /// let expected_builder: MerkleRootBuilder<Sha256> =
+    ///     data.chunks(4096).map(Sha256::hash).collect();
+    /// assert_eq!(leaves_count, 3);
+    /// assert_eq!(builder.root(), expected_builder.root());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns the errors of the reader, and an error of the
+    /// [`io::ErrorKind::InvalidInput`] kind if `chunk_size` is zero.
+    #[cfg(feature = "std")]
+    pub fn read_leaves<R: Read>(&mut self, mut reader: R, chunk_size: usize) -> io::Result<usize> {
+        if chunk_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "chunk size must be greater than zero",
+            ));
+        }
+
+        let mut chunk = vec![0u8; chunk_size];
+        let mut leaves_count = 0;
+        loop {
+            let mut chunk_len = 0;
+            while chunk_len < chunk_size {
+                match reader.read(&mut chunk[chunk_len..]) {
+                    Ok(0) => break,
+                    Ok(read_len) => chunk_len += read_len,
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(error) => return Err(error),
+                }
+            }
+
+            if chunk_len == 0 {
+                return Ok(leaves_count);
+            }
+            self.insert(T::hash(&chunk[..chunk_len]));
+            leaves_count += 1;
+
+            if chunk_len < chunk_size {
+                return Ok(leaves_count);
+            }
+        }
+    }
+
+    /// Returns the number of inserted leaves
+    pub fn leaves_len(&self) -> usize {
+        self.leaves_count
+    }
+
+    /// Calculates the root of the leaves inserted so far, or returns [`None`] if there are no
+    /// leaves. Only hashes the last node of every layer, so it takes `O(log n)` time, and more
+    /// leaves can be inserted afterwards.
+    pub fn root(&self) -> Option<T::Hash> {
+        // The last node of the current layer, made of the leaves that don't fill a complete
+        // subtree of the layer
+        let mut last_node: Option<T::Hash> = None;
+        let mut layer_len = self.leaves_count;
+        let mut layer_index = 0;
+
+        loop {
+            let pending_node = self.pending.get(layer_index).copied().flatten();
+            // The root is carried over unchanged, so it doesn't get rehashed by the odd node
+            // policy of the hasher
+            if layer_len <= 1 {
+                return pending_node.or(last_node);
+            }
+
+            last_node = match (pending_node, last_node) {
+                (Some(left_node), right_node) => {
+                    Some(T::concat_and_hash(&left_node, right_node.as_ref()))
+                }
+                (None, Some(node)) => Some(T::concat_and_hash(&node, None)),
+                (None, None) => None,
+            };
+            layer_len = utils::indices::div_ceil(layer_len, 2);
+            layer_index += 1;
+        }
+    }
+}
+
+impl<T: Hasher> Extend<T::Hash> for MerkleRootBuilder<T> {
+    fn extend<I: IntoIterator<Item = T::Hash>>(&mut self, leaves: I) {
+        for leaf in leaves {
+            self.insert(leaf);
+        }
+    }
+}
+
+impl<T: Hasher> FromIterator<T::Hash> for MerkleRootBuilder<T> {
+    fn from_iter<I: IntoIterator<Item = T::Hash>>(leaves: I) -> Self {
+        let mut builder = Self::new();
+        builder.extend(leaves);
+        builder
+    }
+}
@@ -1,5 +1,18 @@
 use crate::prelude::*;
-use crate::{partial_tree::PartialTree, utils, utils::indices, Hasher, MerkleProof};
+use crate::{
+    error::Error,
+    node_stores::{MemoryStore, NodeBatch},
+    partial_tree::PartialTree,
+    utils,
+    utils::indices,
+    CompactMultiProof, CompleteTree, ConsistencyProof, Hasher, LeafHashPolicy, MerkleProof,
+    NodeStore,
+};
+use alloc::collections::BTreeMap;
+use core::convert::TryFrom;
+
+/// Version of the format produced by [`MerkleTree::to_bytes`]
+const SERIALIZATION_VERSION: u8 = 1;
 
 /// [`MerkleTree`] is a Merkle Tree that is well suited for both basic and advanced usage.
 ///
@@ -9,16 +22,59 @@ use crate::{partial_tree::PartialTree, utils, utils::indices, Hasher, MerkleProo
 /// Advanced features include being able to make transactional changes to a tree with being able to
 /// roll back to any previously committed state of the tree. This scenario is similar to Git and
 /// can be found in databases and file systems.
+///
+/// The committed nodes are kept in a [`NodeStore`], which is [`MemoryStore`] by default. Other
+/// stores, such as [`FileStore`], allow to build trees that don't fit into memory and to reopen
+/// them with [`MerkleTree::with_store`]. The commit history is always kept in memory.
+///
+/// [`MemoryStore`]: crate::node_stores::MemoryStore
+/// [`FileStore`]: crate::node_stores::FileStore
 #[derive(Clone)]
-pub struct MerkleTree<T: Hasher> {
-    current_working_tree: PartialTree<T>,
-    history: Vec<PartialTree<T>>,
+pub struct MerkleTree<T: Hasher, S: NodeStore<T> = MemoryStore<T>> {
+    store: S,
+    history: Vec<Commit<T>>,
     uncommitted_leaves: Vec<T::Hash>,
+    uncommitted_updates: BTreeMap<usize, T::Hash>,
+    /// Amount of committed leaves that are left in the tree after [`MerkleTree::truncate`]
+    uncommitted_truncation: Option<usize>,
+}
+
+/// Identifies a commit in the [`MerkleTree`] history. Returned by [`MerkleTree::commit`] and
+/// [`MerkleTree::commits`], and can be used to roll the tree back to the state it had after
+/// the commit with [`MerkleTree::rollback_to`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommitId<H> {
+    sequence: usize,
+    root: Option<H>,
+}
+
+impl<H: Copy> CommitId<H> {
+    /// Position of the commit in the tree history, starting from 0 for the first commit
+    pub fn sequence(&self) -> usize {
+        self.sequence
+    }
+
+    /// Root of the tree after the commit. [`None`] if the tree was empty after the commit
+    pub fn root(&self) -> Option<H> {
+        self.root
+    }
+}
+
+/// A single entry of the [`MerkleTree`] history
+#[derive(Clone)]
+struct Commit<T: Hasher> {
+    id: CommitId<T::Hash>,
+    /// Previous values of the nodes that were replaced or removed by the commit
+    overwritten: PartialTree<T>,
+    /// Amount of leaves in the tree after the commit was applied
+    leaves_count: usize,
+    /// Amount of leaves in the tree before the commit was applied
+    previous_leaves_count: usize,
 }
 
-impl<T: Hasher> Default for MerkleTree<T> {
+impl<T: Hasher, S: NodeStore<T> + Default> Default for MerkleTree<T, S> {
     fn default() -> Self {
-        Self::new()
+        Self::with_store(S::default())
     }
 }
 
@@ -35,11 +91,7 @@ impl<T: Hasher> MerkleTree<T> {
     /// let another_merkle_tree = MerkleTree::<Sha256>::new();
     /// ```
     pub fn new() -> Self {
-        Self {
-            current_working_tree: PartialTree::new(),
-            history: Vec::new(),
-            uncommitted_leaves: Vec::new(),
-        }
+        Self::with_store(MemoryStore::new())
     }
 
     /// Clones the leaves and builds the tree from them
@@ -66,6 +118,386 @@ impl<T: Hasher> MerkleTree<T> {
         tree
     }
 
+    /// Hashes the data of every leaf with [`Hasher::hash_leaf`] and builds the tree from the
+    /// leaf hashes. The leaves are hashed according to [`Hasher::LEAF_HASH_POLICY`], so the
+    /// proofs of the tree should be verified with [`MerkleProof::verify_data`].
+    ///
+    /// The built-in hashers hash the leaves with [`LeafHashPolicy::DoubleHash`], except for
+    /// [`Rfc6962`] which prefixes them on its own, so the concatenation of two nodes can't be
+    /// proven as the data of a leaf. Note that the root is therefore different from the root of
+    /// [`MerkleTree::from_leaves`] with the leaves hashed by [`Hasher::hash`]. To hash the leaves
+    /// differently, see [`MerkleTree::from_data_with_policy`].
+    ///
+    /// [`Rfc6962`]: crate::algorithms::Rfc6962
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = ["a", "b", "c"];
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_data(&data);
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// let proof = merkle_tree.proof(&[1]);
+    /// assert!(proof.verify_data(root, &[1], &data[1..2], data.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_data<D: AsRef<[u8]>>(data: &[D]) -> Self {
+        let mut leaves: Vec<T::Hash> = data
+            .iter()
+            .map(|leaf_data| T::hash_leaf(leaf_data.as_ref()))
+            .collect();
+
+        let mut tree = Self::new();
+        tree.append(&mut leaves);
+        tree.commit();
+        tree
+    }
+
+    /// Same as [`MerkleTree::from_data`], but hashes the leaves according to the given policy
+    /// instead of [`Hasher::LEAF_HASH_POLICY`]. The proofs of the tree should be verified with
+    /// [`MerkleProof::verify_data_with_policy`] and the same policy.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, LeafHashPolicy};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = ["a", "b", "c"];
+    /// // The leaves are hashed the same way as the internal nodes, so only trusted data should be
+    /// // hashed with this policy
+    /// let policy = LeafHashPolicy::Plain;
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_data_with_policy(&data, policy);
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// let proof = merkle_tree.proof(&[1]);
+    /// assert!(proof.verify_data_with_policy(root, &[1], &data[1..2], data.len(), policy));
+    /// assert!(!proof.verify_data(root, &[1], &data[1..2], data.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_data_with_policy<D: AsRef<[u8]>>(data: &[D], policy: LeafHashPolicy) -> Self {
+        let mut leaves: Vec<T::Hash> = data
+            .iter()
+            .map(|leaf_data| policy.hash_leaf::<T>(leaf_data.as_ref()))
+            .collect();
+
+        let mut tree = Self::new();
+        tree.append(&mut leaves);
+        tree.commit();
+        tree
+    }
+
+    /// Serializes the whole tree: the committed nodes, the commit history and the uncommitted
+    /// changes, so it can be restored with [`MerkleTree::from_bytes`].
+    ///
+    /// The format starts with a version byte, currently `1`. All numbers are big endian `u64`,
+    /// and optional values are prefixed with a `0` or `1` byte. The version is followed by:
+    ///
+    /// - the hash size, the optional root and the number of leaves;
+    /// - the nodes of every committed layer, from the leaves to the root;
+    /// - the number of commits, and the optional root, the number of leaves after and before
+    ///   the commit and the overwritten nodes of every commit. The overwritten nodes are stored
+    ///   as the number of layers, and the number of nodes followed by the index and the hash
+    ///   of every node for each layer;
+    /// - the number of uncommitted leaves followed by their hashes, the number of uncommitted
+    ///   updates followed by their indices and hashes, and the optional uncommitted truncation.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut merkle_tree = MerkleTree::<Sha256>::new();
+    /// let first_commit = merkle_tree
+    ///     .insert(Sha256::hash("a".as_bytes()))
+    ///     .commit()
+    ///     .ok_or("nothing to commit")?;
+    /// merkle_tree.insert(Sha256::hash("b".as_bytes())).commit();
+    /// merkle_tree.insert(Sha256::hash("c".as_bytes()));
+    ///
+    /// let mut restored_tree = MerkleTree::<Sha256>::from_bytes(&merkle_tree.to_bytes())?;
+    /// assert_eq!(restored_tree.root(), merkle_tree.root());
+    /// assert_eq!(restored_tree.uncommitted_root(), merkle_tree.uncommitted_root());
+    ///
+    /// restored_tree.rollback_to(&first_commit)?;
+    /// assert_eq!(restored_tree.root(), Some(Sha256::hash("a".as_bytes())));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let parts = self.to_parts();
+        let mut bytes = vec![SERIALIZATION_VERSION];
+        write_number(&mut bytes, T::hash_size());
+        write_optional_hash::<T>(&mut bytes, parts.root);
+        write_number(&mut bytes, self.leaves_len());
+        for layer in parts.layers {
+            for hash in layer {
+                bytes.append(&mut hash.into());
+            }
+        }
+
+        write_number(&mut bytes, parts.commits.len());
+        for commit in parts.commits {
+            write_optional_hash::<T>(&mut bytes, commit.root);
+            write_number(&mut bytes, commit.leaves_count);
+            write_number(&mut bytes, commit.previous_leaves_count);
+            write_number(&mut bytes, commit.overwritten.layers().len());
+            for layer in commit.overwritten.layers() {
+                write_number(&mut bytes, layer.len());
+                for (index, hash) in layer {
+                    write_number(&mut bytes, *index);
+                    bytes.append(&mut (*hash).into());
+                }
+            }
+        }
+
+        write_number(&mut bytes, parts.uncommitted_leaves.len());
+        for hash in parts.uncommitted_leaves {
+            bytes.append(&mut hash.into());
+        }
+        write_number(&mut bytes, parts.uncommitted_updates.len());
+        for (index, hash) in parts.uncommitted_updates {
+            write_number(&mut bytes, index);
+            bytes.append(&mut hash.into());
+        }
+        match parts.uncommitted_truncation {
+            Some(leaves_count) => {
+                bytes.push(1);
+                write_number(&mut bytes, leaves_count);
+            }
+            None => bytes.push(0),
+        }
+
+        bytes
+    }
+
+    /// Restores a tree serialized with [`MerkleTree::to_bytes`]. The committed nodes are
+    /// rebuilt from the leaves and checked against the serialized nodes and root.
+    ///
+    /// For examples, please check [`MerkleTree::to_bytes`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the format version isn't supported, if the bytes are malformed or
+    /// were serialized with a hasher of a different hash size, if the history or the
+    /// uncommitted changes don't fit the tree, or if the nodes don't match the leaves.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = ByteReader { bytes };
+
+        let version = reader.read_flag_byte(u8::MAX)?;
+        if version != SERIALIZATION_VERSION {
+            return Err(Error::unsupported_format_version(
+                version,
+                SERIALIZATION_VERSION,
+            ));
+        }
+        let hash_size = reader.read_number()?;
+        if hash_size != T::hash_size() {
+            return Err(Error::invalid_serialized_tree(format!(
+                "tree was serialized with {} bytes hashes, expected {} bytes hashes",
+                hash_size,
+                T::hash_size()
+            )));
+        }
+
+        let root = reader.read_optional_hash::<T>()?;
+        let leaves_count = reader.read_number()?;
+        let mut layers = Vec::new();
+        for layer_len in indices::layer_lens(leaves_count) {
+            layers.push(reader.read_hashes::<T>(layer_len)?);
+        }
+
+        let mut commits = Vec::new();
+        for _ in 0..reader.read_number()? {
+            let root = reader.read_optional_hash::<T>()?;
+            let leaves_count = reader.read_number()?;
+            let previous_leaves_count = reader.read_number()?;
+            let mut overwritten_layers = Vec::new();
+            for _ in 0..reader.read_number()? {
+                let mut layer = Vec::new();
+                for _ in 0..reader.read_number()? {
+                    layer.push((reader.read_number()?, reader.read_hash::<T>()?));
+                }
+                overwritten_layers.push(layer);
+            }
+
+            commits.push(CommitParts {
+                root,
+                leaves_count,
+                previous_leaves_count,
+                overwritten: PartialTree::try_from_layers(overwritten_layers)?,
+            });
+        }
+
+        let uncommitted_leaves_len = reader.read_number()?;
+        let uncommitted_leaves = reader.read_hashes::<T>(uncommitted_leaves_len)?;
+        let mut uncommitted_updates = Vec::new();
+        for _ in 0..reader.read_number()? {
+            uncommitted_updates.push((reader.read_number()?, reader.read_hash::<T>()?));
+        }
+        let uncommitted_truncation = match reader.read_flag_byte(1)? {
+            1 => Some(reader.read_number()?),
+            _ => None,
+        };
+
+        if !reader.bytes.is_empty() {
+            return Err(Error::invalid_serialized_tree(format!(
+                "{} unexpected bytes after the tree",
+                reader.bytes.len()
+            )));
+        }
+
+        Self::from_parts(TreeParts {
+            root,
+            layers,
+            commits,
+            uncommitted_leaves,
+            uncommitted_updates,
+            uncommitted_truncation,
+        })
+    }
+
+    /// Splits the tree into the parts that are serialized
+    pub(crate) fn to_parts(&self) -> TreeParts<T> {
+        TreeParts {
+            root: self.root(),
+            layers: self
+                .store
+                .layers()
+                .into_iter()
+                .map(|layer| layer.to_vec())
+                .collect(),
+            commits: self
+                .history
+                .iter()
+                .map(|commit| CommitParts {
+                    root: commit.id.root,
+                    leaves_count: commit.leaves_count,
+                    previous_leaves_count: commit.previous_leaves_count,
+                    overwritten: commit.overwritten.clone(),
+                })
+                .collect(),
+            uncommitted_leaves: self.uncommitted_leaves.clone(),
+            uncommitted_updates: self
+                .uncommitted_updates
+                .iter()
+                .map(|(index, hash)| (*index, *hash))
+                .collect(),
+            uncommitted_truncation: self.uncommitted_truncation,
+        }
+    }
+
+    /// Restores the tree from deserialized parts. The committed nodes are rebuilt from the
+    /// leaves and checked against the deserialized nodes and root, and the history and the
+    /// uncommitted changes are checked to fit the tree.
+    pub(crate) fn from_parts(parts: TreeParts<T>) -> Result<Self, Error> {
+        let leaves_count = parts.layers.first().map_or(0, Vec::len);
+        let rebuilt_tree =
+            CompleteTree::<T>::from_leaves(parts.layers.first().map_or(&[], Vec::as_slice));
+        let layers_match = rebuilt_tree.layers_len() == parts.layers.len()
+            && rebuilt_tree
+                .layers()
+                .iter()
+                .zip(&parts.layers)
+                .all(|(rebuilt_layer, layer)| *rebuilt_layer == layer.as_slice());
+        if !layers_match || rebuilt_tree.root() != parts.root.as_ref() {
+            return Err(Error::root_mismatch());
+        }
+
+        let mut history: Vec<Commit<T>> = Vec::new();
+        for (sequence, commit) in parts.commits.into_iter().enumerate() {
+            let follows_previous_commit = match history.last() {
+                Some(previous) => previous.leaves_count == commit.previous_leaves_count,
+                None => true,
+            };
+            if !follows_previous_commit {
+                return Err(Error::invalid_serialized_tree(format!(
+                    "commit {} doesn't follow the previous commit",
+                    sequence
+                )));
+            }
+
+            history.push(Commit {
+                id: CommitId {
+                    sequence,
+                    root: commit.root,
+                },
+                overwritten: commit.overwritten,
+                leaves_count: commit.leaves_count,
+                previous_leaves_count: commit.previous_leaves_count,
+            });
+        }
+        if let Some(commit) = history.last() {
+            if commit.leaves_count != leaves_count || commit.id.root != parts.root {
+                return Err(Error::invalid_serialized_tree(String::from(
+                    "last commit doesn't match the committed tree",
+                )));
+            }
+        }
+
+        let mut uncommitted_updates = BTreeMap::new();
+        for (index, hash) in parts.uncommitted_updates {
+            if uncommitted_updates.insert(index, hash).is_some() {
+                return Err(Error::invalid_serialized_tree(format!(
+                    "leaf {} is updated twice",
+                    index
+                )));
+            }
+        }
+
+        let retained_leaves_count = parts.uncommitted_truncation.unwrap_or(leaves_count);
+        let updates_are_retained = uncommitted_updates
+            .keys()
+            .all(|index| *index < retained_leaves_count);
+        if retained_leaves_count > leaves_count || !updates_are_retained {
+            return Err(Error::invalid_serialized_tree(String::from(
+                "uncommitted changes are out of bounds of the tree",
+            )));
+        }
+
+        Ok(Self {
+            store: MemoryStore::from_tree(rebuilt_tree),
+            history,
+            uncommitted_leaves: parts.uncommitted_leaves,
+            uncommitted_updates,
+            uncommitted_truncation: parts.uncommitted_truncation,
+        })
+    }
+}
+
+impl<T: Hasher, S: NodeStore<T>> MerkleTree<T, S> {
+    /// Creates a tree on top of the given store. The nodes that are already in the store become
+    /// the committed state of the tree, so a tree can be reopened from a persistent store. The
+    /// commit history isn't stored, so the reopened tree can't be rolled back.
+    ///
+    /// Stores that can fail, such as [`FileStore`], should be used with [`MerkleTree::try_commit`]
+    /// and [`MerkleTree::try_rollback`]. The methods that read the committed nodes without
+    /// returning a [`Result`], such as [`MerkleTree::root`] and [`MerkleTree::proof`], panic if
+    /// the store fails to read a node.
+    ///
+    /// For examples, please check [`FileStore`]
+    ///
+    /// [`FileStore`]: crate::node_stores::FileStore
+    pub fn with_store(store: S) -> Self {
+        Self {
+            store,
+            history: Vec::new(),
+            uncommitted_leaves: Vec::new(),
+            uncommitted_updates: BTreeMap::new(),
+            uncommitted_truncation: None,
+        }
+    }
+
+    /// Returns the store with the committed nodes of the tree
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
     /// Returns the tree root - the top hash of the tree. Used in the inclusion proof verification.
     ///
     /// ## Examples
@@ -93,7 +525,8 @@ impl<T: Hasher> MerkleTree<T> {
     /// # }
     /// ```
     pub fn root(&self) -> Option<T::Hash> {
-        Some(self.layer_tuples().last()?.first()?.1)
+        let root_layer_index = self.store.layers_len().checked_sub(1)?;
+        self.expect_node(root_layer_index, 0)
     }
 
     /// Similar to [`MerkleTree::root`], but returns a hex encoded string instead of
@@ -131,7 +564,7 @@ impl<T: Hasher> MerkleTree<T> {
     fn helper_nodes(&self, leaf_indices: &[usize]) -> Vec<T::Hash> {
         let mut helper_nodes = Vec::<T::Hash>::new();
 
-        for layer in self.helper_node_tuples(leaf_indices) {
+        for layer in self.helper_node_tuples(leaf_indices, self.leaves_len()) {
             for (_index, hash) in layer {
                 helper_nodes.push(hash)
             }
@@ -141,25 +574,32 @@ impl<T: Hasher> MerkleTree<T> {
     }
 
     /// Gets all helper nodes required to build a partial merkle tree for the given indices,
-    /// cloning all required hashes into the resulting vector.
-    fn helper_node_tuples(&self, leaf_indices: &[usize]) -> Vec<Vec<(usize, T::Hash)>> {
+    /// cloning all required hashes into the resulting vector. Nodes that are out of bounds
+    /// of a tree with `leaves_count` leaves are skipped, which allows to build a partial tree
+    /// for a tree that is smaller than the committed one.
+    fn helper_node_tuples(
+        &self,
+        leaf_indices: &[usize],
+        leaves_count: usize,
+    ) -> Vec<Vec<(usize, T::Hash)>> {
         let mut current_layer_indices = leaf_indices.to_vec();
+        current_layer_indices.sort_unstable();
+        current_layer_indices.dedup();
         let mut helper_nodes: Vec<Vec<(usize, T::Hash)>> = Vec::new();
+        let mut layer_width = leaves_count;
 
-        for tree_layer in self.layer_tuples() {
+        for layer_index in 0..self.store.layers_len() {
             let mut helpers_layer = Vec::new();
-            let siblings = utils::indices::sibling_indices(&current_layer_indices);
-            // Filter all nodes that do not require an additional hash to be calculated
-            let helper_indices = utils::collections::difference(&siblings, &current_layer_indices);
-
-            for index in helper_indices {
-                if let Some(tuple) = tree_layer.get(index) {
-                    helpers_layer.push(*tuple);
+            // Only the siblings that can't be calculated from the nodes themselves are needed
+            for index in indices::helper_indices(&current_layer_indices, layer_width) {
+                if let Some(hash) = self.expect_node(layer_index, index) {
+                    helpers_layer.push((index, hash));
                 }
             }
 
             helper_nodes.push(helpers_layer);
             current_layer_indices = indices::parent_indices(&current_layer_indices);
+            layer_width = indices::div_ceil(layer_width, 2);
         }
 
         helper_nodes
@@ -194,7 +634,104 @@ impl<T: Hasher> MerkleTree<T> {
     /// # }
     /// ```
     pub fn proof(&self, leaf_indices: &[usize]) -> MerkleProof<T> {
-        MerkleProof::<T>::new(self.helper_nodes(leaf_indices))
+        MerkleProof::<T>::with_metadata(
+            self.helper_nodes(leaf_indices),
+            leaf_indices.to_vec(),
+            self.leaves_len(),
+        )
+    }
+
+    /// Returns the proof for the given leaves in the compact format of [`CompactMultiProof`], which
+    /// encodes the positions of the leaves as traversal flags, so the verifier doesn't need to
+    /// know the indices and recovers them with [`CompactMultiProof::extract_matches`]. The
+    /// leaves have to be passed to the verifier in the order of their indices, regardless of the
+    /// order of `leaf_indices`.
+    ///
+    /// For examples, please check [`CompactMultiProof`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if any of the leaf indices is out of bounds or duplicated, or if the node
+    /// store fails to read a node.
+    pub fn compact_multi_proof(
+        &self,
+        leaf_indices: &[usize],
+    ) -> Result<CompactMultiProof<T>, Error> {
+        let leaves_count = self.leaves_len();
+        if leaf_indices.iter().any(|index| *index >= leaves_count) {
+            return Err(Error::leaf_index_out_of_bounds(leaves_count));
+        }
+
+        let mut sorted_indices = leaf_indices.to_vec();
+        sorted_indices.sort_unstable();
+        if sorted_indices.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(Error::duplicate_leaf_index());
+        }
+
+        CompactMultiProof::build(leaves_count, &sorted_indices, |layer_index, node_index| {
+            self.store.get(layer_index, node_index)
+        })
+    }
+
+    /// Returns a proof that the tree made of the first `old_size` leaves is a prefix of the tree
+    /// made of the first `new_size` leaves. The proof is built as described in RFC 6962, section
+    /// 2.1.2, and can be verified with [`ConsistencyProof::verify`] against the roots of both
+    /// trees. `new_size` can be smaller than the current tree size, for example, to prove the
+    /// consistency between two past commits of an append-only log.
+    ///
+    /// The proof is only meaningful for hashers that use [`OddNodePolicy::Promote`], as other
+    /// policies don't produce the RFC 6962 tree shape.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f", "g"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let mut merkle_tree = MerkleTree::<Sha256>::new();
+    /// let old_commit = merkle_tree
+    ///     .append(leaves[..4].to_vec().as_mut())
+    ///     .commit()
+    ///     .ok_or("nothing to commit")?;
+    /// let new_commit = merkle_tree
+    ///     .append(leaves[4..].to_vec().as_mut())
+    ///     .commit()
+    ///     .ok_or("nothing to commit")?;
+    ///
+    /// let proof = merkle_tree.consistency_proof(4, 7)?;
+    /// assert!(proof.verify(
+    ///     old_commit.root().ok_or("couldn't get the merkle root")?,
+    ///     new_commit.root().ok_or("couldn't get the merkle root")?,
+    ///     4,
+    ///     7
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] unless `0 < old_size <= new_size <= leaves_len()`
+    ///
+    /// [`OddNodePolicy::Promote`]: crate::OddNodePolicy::Promote
+    pub fn consistency_proof(
+        &self,
+        old_size: usize,
+        new_size: usize,
+    ) -> Result<ConsistencyProof<T>, Error> {
+        let leaves_count = self.leaves_len();
+        if old_size == 0 || old_size > new_size || new_size > leaves_count {
+            return Err(Error::invalid_tree_size(old_size, new_size, leaves_count));
+        }
+
+        let mut proof_hashes = Vec::new();
+        self.consistency_subproof(old_size, 0, new_size, true, &mut proof_hashes)?;
+
+        Ok(ConsistencyProof::new(proof_hashes))
     }
 
     /// Inserts a new leaf. Please note it won't modify the root just yet; For the changes
@@ -247,6 +784,49 @@ impl<T: Hasher> MerkleTree<T> {
         self
     }
 
+    /// Hashes the leaf data with [`Hasher::hash_leaf`] and inserts the leaf hash, see
+    /// [`MerkleTree::insert`]. Like the insertion, the change has to be committed.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// let mut merkle_tree = MerkleTree::<Sha256>::new();
+    /// merkle_tree.insert_data("a").insert_data(b"b").commit();
+    ///
+    /// assert_eq!(merkle_tree.root(), MerkleTree::<Sha256>::from_data(&["a", "b"]).root());
+    /// ```
+    pub fn insert_data<D: AsRef<[u8]>>(&mut self, data: D) -> &mut Self {
+        self.insert(T::hash_leaf(data.as_ref()))
+    }
+
+    /// Same as [`MerkleTree::insert_data`], but hashes the leaf according to the given policy
+    /// instead of [`Hasher::LEAF_HASH_POLICY`], see [`MerkleTree::from_data_with_policy`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, LeafHashPolicy};
+    /// let policy = LeafHashPolicy::Prefixed(0x00);
+    /// let mut merkle_tree = MerkleTree::<Sha256>::new();
+    /// merkle_tree
+    ///     .insert_data_with_policy("a", policy)
+    ///     .insert_data_with_policy(b"b", policy)
+    ///     .commit();
+    ///
+    /// assert_eq!(
+    ///     merkle_tree.root(),
+    ///     MerkleTree::<Sha256>::from_data_with_policy(&["a", "b"], policy).root()
+    /// );
+    /// ```
+    pub fn insert_data_with_policy<D: AsRef<[u8]>>(
+        &mut self,
+        data: D,
+        policy: LeafHashPolicy,
+    ) -> &mut Self {
+        self.insert(policy.hash_leaf::<T>(data.as_ref()))
+    }
+
     /// Appends leaves to the tree. Behaves similarly to [`MerkleTree::insert`], but for a list of
     /// items. Takes ownership of the elements of the [`std::vec::Vec<T>`],
     /// similarly to [`std::vec::Vec::append`].
@@ -278,10 +858,201 @@ impl<T: Hasher> MerkleTree<T> {
         self
     }
 
-    /// Commits the changes made by [`MerkleTree::insert`] and [`MerkleTree::append`]
-    /// and modifies the root.
+    /// Replaces the leaf at the given index. Just like [`MerkleTree::insert`], the change is
+    /// staged and won't modify the root until [`MerkleTree::commit`] is called, so it can be
+    /// inspected with [`MerkleTree::uncommitted_root`] and rolled back with
+    /// [`MerkleTree::rollback`] after the commit. Only the path from the leaf to the root is
+    /// recalculated on commit.
+    ///
+    /// The index can point both to a committed leaf and to a leaf that was inserted, but not
+    /// committed yet.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, Error, utils};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&[
+    ///     Sha256::hash("a".as_bytes()),
+    ///     Sha256::hash("b".as_bytes()),
+    ///     Sha256::hash("c".as_bytes()),
+    /// ]);
+    /// let expected_tree = MerkleTree::<Sha256>::from_leaves(&[
+    ///     Sha256::hash("a".as_bytes()),
+    ///     Sha256::hash("d".as_bytes()),
+    ///     Sha256::hash("c".as_bytes()),
+    /// ]);
+    ///
+    /// merkle_tree.update(1, Sha256::hash("d".as_bytes()))?;
+    /// assert_eq!(merkle_tree.uncommitted_root(), expected_tree.root());
+    ///
+    /// merkle_tree.commit();
+    /// assert_eq!(merkle_tree.root(), expected_tree.root());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the index is out of bounds of the uncommitted tree
+    pub fn update(&mut self, index: usize, leaf: T::Hash) -> Result<&mut Self, Error> {
+        self.update_batch(&[(index, leaf)])
+    }
+
+    /// Replaces multiple leaves at once. Behaves similarly to [`MerkleTree::update`], but takes
+    /// a list of `(index, leaf)` pairs. If an index is present more than once, the last
+    /// leaf wins. If any of the indices is out of bounds, no changes are staged.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, Error, utils};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    /// let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    ///
+    /// merkle_tree
+    ///     .update_batch(&[(0, Sha256::hash("f".as_bytes())), (4, Sha256::hash("g".as_bytes()))])?
+    ///     .commit();
+    ///
+    /// let expected_leaves: Vec<[u8; 32]> = ["f", "b", "c", "d", "g"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    /// assert_eq!(merkle_tree.leaves(), Some(expected_leaves));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if any of the indices is out of bounds of the uncommitted tree
+    pub fn update_batch(&mut self, leaves: &[(usize, T::Hash)]) -> Result<&mut Self, Error> {
+        let retained_leaves_count = self.retained_leaves_len();
+        let uncommitted_leaves_count = retained_leaves_count + self.uncommitted_leaves.len();
+
+        if leaves
+            .iter()
+            .any(|(index, _)| *index >= uncommitted_leaves_count)
+        {
+            return Err(Error::leaf_index_out_of_bounds(uncommitted_leaves_count));
+        }
+
+        for (index, leaf) in leaves.iter().cloned() {
+            match index.checked_sub(retained_leaves_count) {
+                // Leaf hasn't been committed yet, so it can be replaced in place
+                Some(uncommitted_index) => self.uncommitted_leaves[uncommitted_index] = leaf,
+                None => {
+                    self.uncommitted_updates.insert(index, leaf);
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Shortens the tree, keeping the first `leaves_count` leaves and dropping the rest. If
+    /// `leaves_count` is greater or equal to the current amount of leaves, this has no effect.
+    /// Similarly to [`MerkleTree::insert`], the change is staged and is applied to the tree
+    /// only after [`MerkleTree::commit`], so it can be rolled back with [`MerkleTree::rollback`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, Error, utils};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    /// let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    ///
+    /// merkle_tree.truncate(3).commit();
+    ///
+    /// assert_eq!(merkle_tree.leaves_len(), 3);
+    /// assert_eq!(
+    ///     merkle_tree.root(),
+    ///     MerkleTree::<Sha256>::from_leaves(&leaves[..3]).root()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn truncate(&mut self, leaves_count: usize) -> &mut Self {
+        let retained_leaves_count = self.retained_leaves_len();
+
+        match leaves_count.checked_sub(retained_leaves_count) {
+            // Only uncommitted leaves are affected
+            Some(uncommitted_leaves_count) => {
+                self.uncommitted_leaves.truncate(uncommitted_leaves_count)
+            }
+            None => {
+                self.uncommitted_leaves.clear();
+                // Updates of the removed leaves don't make sense anymore
+                self.uncommitted_updates.split_off(&leaves_count);
+                self.uncommitted_truncation = Some(leaves_count);
+            }
+        }
+
+        self
+    }
+
+    /// Removes the last leaf from the tree and returns it, or [`None`] if the tree is empty.
+    /// Behaves similarly to [`MerkleTree::truncate`]: the change is staged until
+    /// [`MerkleTree::commit`] is called.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, Error, utils};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves = [
+    ///     Sha256::hash("a".as_bytes()),
+    ///     Sha256::hash("b".as_bytes()),
+    ///     Sha256::hash("c".as_bytes()),
+    /// ];
+    /// let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    ///
+    /// assert_eq!(merkle_tree.pop(), Some(leaves[2]));
+    /// merkle_tree.commit();
+    ///
+    /// assert_eq!(merkle_tree.leaves(), Some(leaves[..2].to_vec()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pop(&mut self) -> Option<T::Hash> {
+        if let Some(leaf) = self.uncommitted_leaves.pop() {
+            return Some(leaf);
+        }
+
+        let last_index = self.retained_leaves_len().checked_sub(1)?;
+        let leaf = match self.uncommitted_updates.get(&last_index) {
+            Some(leaf) => *leaf,
+            None => self.expect_node(0, last_index)?,
+        };
+        self.truncate(last_index);
+
+        Some(leaf)
+    }
+
+    /// Commits the changes made by [`MerkleTree::insert`], [`MerkleTree::append`],
+    /// [`MerkleTree::update`] and [`MerkleTree::truncate`] and modifies the root.
     /// Commits are saved to the history, so the tree can be rolled back to any previous commit
-    /// using [`MerkleTree::rollback`]
+    /// using [`MerkleTree::rollback`] or [`MerkleTree::rollback_to`].
+    ///
+    /// Returns the [`CommitId`] of the new commit, or [`None`] if there were no changes
+    /// to commit.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the store fails to write the changes, which never happens with the default
+    /// [`MemoryStore`]. Use [`MerkleTree::try_commit`] with stores that can fail, such as
+    /// [`FileStore`].
+    ///
+    /// [`MemoryStore`]: crate::node_stores::MemoryStore
+    /// [`FileStore`]: crate::node_stores::FileStore
     ///
     /// ## Examples
     ///
@@ -308,17 +1079,66 @@ impl<T: Hasher> MerkleTree<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn commit(&mut self) {
-        if let Some(diff) = self.uncommitted_diff() {
-            self.history.push(diff.clone());
-            self.current_working_tree.merge_unverified(diff);
-            self.uncommitted_leaves.clear();
+    pub fn commit(&mut self) -> Option<CommitId<T::Hash>> {
+        match self.try_commit() {
+            Ok(commit_id) => commit_id,
+            Err(error) => panic!("failed to commit the changes: {}", error),
         }
     }
 
+    /// Same as [`MerkleTree::commit`], but returns the error of the store instead of
+    /// panicking. All nodes changed by the commit are written to the store with a single
+    /// [`NodeStore::write_batch`] call.
+    ///
+    /// For examples, please check [`FileStore`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the store fails to write the changes. The changes stay uncommitted
+    /// in that case.
+    ///
+    /// [`FileStore`]: crate::node_stores::FileStore
+    pub fn try_commit(&mut self) -> Result<Option<CommitId<T::Hash>>, Error> {
+        let diff = match self.uncommitted_diff() {
+            Some(diff) => diff,
+            None => return Ok(None),
+        };
+        let id = CommitId {
+            sequence: self.history.len(),
+            root: diff.root().cloned(),
+        };
+        let previous_leaves_count = self.leaves_len();
+        let leaves_count = self.uncommitted_leaves_len();
+        let overwritten = self.overwritten_nodes(&diff, leaves_count)?;
+
+        self.store.write_batch(NodeBatch::new(
+            indices::layer_lens(leaves_count),
+            node_triples(&diff),
+        ))?;
+
+        self.history.push(Commit {
+            id,
+            overwritten,
+            leaves_count,
+            previous_leaves_count,
+        });
+        self.abort_uncommitted();
+
+        Ok(Some(id))
+    }
+
     /// Rolls back one commit and reverts the tree to the previous state.
     /// Removes the most recent commit from the history.
     ///
+    /// ## Panics
+    ///
+    /// Panics if the store fails to revert the changes, which never happens with the default
+    /// [`MemoryStore`]. Use [`MerkleTree::try_rollback`] with stores that can fail, such as
+    /// [`FileStore`].
+    ///
+    /// [`MemoryStore`]: crate::node_stores::MemoryStore
+    /// [`FileStore`]: crate::node_stores::FileStore
+    ///
     /// ## Examples
     ///
     /// ```
@@ -348,16 +1168,213 @@ impl<T: Hasher> MerkleTree<T> {
     /// # }
     /// ```
     pub fn rollback(&mut self) {
-        // Remove the most recent commit
-        self.history.pop();
-        // Clear working tree
-        self.current_working_tree.clear();
-        // Applying all the commits up to the removed one. This is not an
-        // efficient way of doing things, but the diff subtraction is not implemented yet on
-        // PartialMerkleTree
-        for commit in &self.history {
-            self.current_working_tree.merge_unverified(commit.clone());
+        if let Err(error) = self.try_rollback() {
+            panic!("failed to roll back the commit: {}", error);
+        }
+    }
+
+    /// Same as [`MerkleTree::rollback`], but returns the error of the store instead of
+    /// panicking. Does nothing if there are no commits.
+    ///
+    /// For examples, please check [`FileStore`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the store fails to revert the changes. The commit stays in the
+    /// history in that case.
+    ///
+    /// [`FileStore`]: crate::node_stores::FileStore
+    pub fn try_rollback(&mut self) -> Result<(), Error> {
+        if let Some(commit) = self.history.pop() {
+            if let Err(error) = self.revert_commit(&commit) {
+                self.history.push(commit);
+                return Err(error);
+            }
         }
+
+        Ok(())
+    }
+
+    /// Rolls back all commits made after the given one, reverting the tree to the state it had
+    /// right after that commit. Takes time proportional to the size of the reverted changes.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, Error, utils};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut merkle_tree = MerkleTree::<Sha256>::new();
+    ///
+    /// let first_commit = merkle_tree
+    ///     .insert(Sha256::hash("a".as_bytes()))
+    ///     .commit()
+    ///     .ok_or("nothing to commit")?;
+    /// merkle_tree.insert(Sha256::hash("b".as_bytes())).commit();
+    /// merkle_tree.insert(Sha256::hash("c".as_bytes())).commit();
+    /// assert_eq!(merkle_tree.commits().len(), 3);
+    ///
+    /// merkle_tree.rollback_to(&first_commit)?;
+    /// assert_eq!(merkle_tree.commits(), vec![first_commit]);
+    /// assert_eq!(
+    ///     merkle_tree.root_hex(),
+    ///     Some("ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb".to_string())
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the commit isn't present in the tree history, for example, if it
+    /// was already rolled back, or if the store fails to revert the changes.
+    pub fn rollback_to(&mut self, commit_id: &CommitId<T::Hash>) -> Result<(), Error> {
+        let sequence = self
+            .find_commit(commit_id)
+            .ok_or_else(Error::commit_not_found)?
+            .id
+            .sequence;
+
+        while self.history.len() > sequence + 1 {
+            self.try_rollback()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns identifiers of all commits in the tree history, from the oldest to the newest.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, Error, utils};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut merkle_tree = MerkleTree::<Sha256>::new();
+    /// merkle_tree.insert(Sha256::hash("a".as_bytes())).commit();
+    /// merkle_tree.insert(Sha256::hash("b".as_bytes())).commit();
+    ///
+    /// let commits = merkle_tree.commits();
+    /// assert_eq!(commits.len(), 2);
+    /// assert_eq!(commits[1].sequence(), 1);
+    /// assert_eq!(commits[1].root(), merkle_tree.root());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn commits(&self) -> Vec<CommitId<T::Hash>> {
+        self.history.iter().map(|commit| commit.id).collect()
+    }
+
+    /// Returns the root the tree had right after the given commit, or [`None`] if the commit
+    /// isn't present in the tree history or the tree was empty after it.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, Error, utils};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut merkle_tree = MerkleTree::<Sha256>::new();
+    /// let first_commit = merkle_tree
+    ///     .insert(Sha256::hash("a".as_bytes()))
+    ///     .commit()
+    ///     .ok_or("nothing to commit")?;
+    /// merkle_tree.insert(Sha256::hash("b".as_bytes())).commit();
+    ///
+    /// assert_eq!(
+    ///     merkle_tree.root_at(&first_commit),
+    ///     Some(Sha256::hash("a".as_bytes()))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn root_at(&self, commit_id: &CommitId<T::Hash>) -> Option<T::Hash> {
+        self.find_commit(commit_id)?.id.root
+    }
+
+    /// Returns the number of leaves the tree had right after the given commit, or [`None`] if
+    /// the commit isn't present in the tree history. Used together with [`MerkleTree::root_at`]
+    /// to verify proofs produced by [`MerkleTree::proof_at`].
+    pub fn leaves_len_at(&self, commit_id: &CommitId<T::Hash>) -> Option<usize> {
+        Some(self.find_commit(commit_id)?.leaves_count)
+    }
+
+    /// Returns the Merkle proof for the given leaves as they were in the tree right after the
+    /// given commit. The proof verifies against [`MerkleTree::root_at`] with
+    /// [`MerkleTree::leaves_len_at`] leaves.
+    ///
+    /// The tree isn't cloned or rebuilt: the historical nodes are looked up in the nodes
+    /// overwritten by the commits that were made after the given one.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, Error, utils};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let mut merkle_tree = MerkleTree::<Sha256>::new();
+    /// let commit = merkle_tree
+    ///     .append(leaves.clone().as_mut())
+    ///     .commit()
+    ///     .ok_or("nothing to commit")?;
+    ///
+    /// merkle_tree
+    ///     .update(1, Sha256::hash("d".as_bytes()))?
+    ///     .insert(Sha256::hash("e".as_bytes()))
+    ///     .commit();
+    ///
+    /// let proof = merkle_tree.proof_at(&commit, &[1])?;
+    /// let root = merkle_tree.root_at(&commit).ok_or("couldn't get the merkle root")?;
+    /// let leaves_count = merkle_tree.leaves_len_at(&commit).ok_or("commit not found")?;
+    ///
+    /// assert!(proof.verify(root, &[1], &leaves[1..2], leaves_count));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the commit isn't present in the tree history, or if any of the
+    /// indices is out of bounds of the tree at that commit.
+    pub fn proof_at(
+        &self,
+        commit_id: &CommitId<T::Hash>,
+        leaf_indices: &[usize],
+    ) -> Result<MerkleProof<T>, Error> {
+        let commit = self
+            .find_commit(commit_id)
+            .ok_or_else(Error::commit_not_found)?;
+
+        if leaf_indices
+            .iter()
+            .any(|index| *index >= commit.leaves_count)
+        {
+            return Err(Error::leaf_index_out_of_bounds(commit.leaves_count));
+        }
+
+        let mut sorted_indices = leaf_indices.to_vec();
+        sorted_indices.sort_unstable();
+        sorted_indices.dedup();
+
+        let proof_indices =
+            utils::indices::proof_indices_by_layers(&sorted_indices, commit.leaves_count);
+        let mut proof_hashes = Vec::new();
+
+        for (layer_index, layer) in proof_indices.iter().enumerate() {
+            for node_index in layer {
+                let hash = self
+                    .node_at(commit.id.sequence, layer_index, *node_index)?
+                    .ok_or_else(Error::not_enough_helper_nodes)?;
+                proof_hashes.push(hash);
+            }
+        }
+
+        Ok(MerkleProof::with_metadata(
+            proof_hashes,
+            leaf_indices.to_vec(),
+            commit.leaves_count,
+        ))
     }
 
     /// Calculates the root of the uncommitted changes as if they were committed.
@@ -407,8 +1424,9 @@ impl<T: Hasher> MerkleTree<T> {
         Some(utils::collections::to_hex_string(&root))
     }
 
-    /// Clears all uncommitted changes made by [`MerkleTree::insert`] and [`MerkleTree::append`]
-    /// operations without applying them to the tree.
+    /// Clears all uncommitted changes made by [`MerkleTree::insert`], [`MerkleTree::append`],
+    /// [`MerkleTree::update`] and [`MerkleTree::truncate`] operations without applying them
+    /// to the tree.
     ///
     /// ## Examples
     ///
@@ -438,7 +1456,9 @@ impl<T: Hasher> MerkleTree<T> {
     /// # }
     /// ```
     pub fn abort_uncommitted(&mut self) {
-        self.uncommitted_leaves.clear()
+        self.uncommitted_leaves.clear();
+        self.uncommitted_updates.clear();
+        self.uncommitted_truncation = None;
     }
 
     /// Returns the tree depth. A tree depth is how many layers there is between the
@@ -462,7 +1482,7 @@ impl<T: Hasher> MerkleTree<T> {
     /// # }
     /// ```
     pub fn depth(&self) -> usize {
-        self.layer_tuples().len() - 1
+        self.store.layers_len() - 1
     }
 
     /// Returns a copy of the tree leaves - the base level of the tree.
@@ -485,7 +1505,13 @@ impl<T: Hasher> MerkleTree<T> {
     /// # }
     /// ```
     pub fn leaves(&self) -> Option<Vec<T::Hash>> {
-        Some(self.layers().first()?.to_vec())
+        if self.store.layers_len() == 0 {
+            return None;
+        }
+
+        (0..self.leaves_len())
+            .map(|index| self.expect_node(0, index))
+            .collect()
     }
 
     /// Returns the number of leaves in the tree.
@@ -508,59 +1534,234 @@ impl<T: Hasher> MerkleTree<T> {
     /// # }
     /// ```
     pub fn leaves_len(&self) -> usize {
-        if let Some(leaves) = self.leaves_tuples() {
-            return leaves.len();
+        self.store.layer_len(0)
+    }
+
+    /// Returns the committed node with the given index on the given layer
+    pub(crate) fn node(
+        &self,
+        layer_index: usize,
+        node_index: usize,
+    ) -> Result<Option<T::Hash>, Error> {
+        self.store.get(layer_index, node_index)
+    }
+
+    /// Same as [`MerkleTree::node`], for the methods that can't return the error of the store
+    fn expect_node(&self, layer_index: usize, node_index: usize) -> Option<T::Hash> {
+        match self.node(layer_index, node_index) {
+            Ok(node) => node,
+            Err(error) => panic!(
+                "failed to read node {} of layer {}: {}",
+                node_index, layer_index, error
+            ),
+        }
+    }
+
+    fn find_commit(&self, commit_id: &CommitId<T::Hash>) -> Option<&Commit<T>> {
+        self.history
+            .get(commit_id.sequence)
+            .filter(|commit| commit.id.root == commit_id.root)
+    }
+
+    /// Implements `SUBPROOF(m, D[start:end], b)` from RFC 6962, section 2.1.2, where `m` is
+    /// `old_size` and `complete_subtree` is `b`.
+    fn consistency_subproof(
+        &self,
+        old_size: usize,
+        start: usize,
+        end: usize,
+        complete_subtree: bool,
+        proof_hashes: &mut Vec<T::Hash>,
+    ) -> Result<(), Error> {
+        if old_size == end - start {
+            if !complete_subtree {
+                proof_hashes.push(self.subtree_root(start, end)?);
+            }
+            return Ok(());
+        }
+
+        let split = largest_power_of_two_below(end - start);
+        if old_size <= split {
+            self.consistency_subproof(
+                old_size,
+                start,
+                start + split,
+                complete_subtree,
+                proof_hashes,
+            )?;
+            proof_hashes.push(self.subtree_root(start + split, end)?);
+        } else {
+            self.consistency_subproof(old_size - split, start + split, end, false, proof_hashes)?;
+            proof_hashes.push(self.subtree_root(start, start + split)?);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the root of the subtree built from the leaves in the `start..end` range.
+    /// Subtrees that are present in the tree are looked up, the rest are calculated from
+    /// the smaller subtrees in the same way the tree is built.
+    fn subtree_root(&self, start: usize, end: usize) -> Result<T::Hash, Error> {
+        let size = end - start;
+        let layer_index = size.next_power_of_two().trailing_zeros() as usize;
+        let is_aligned = start % (1 << layer_index) == 0;
+
+        if is_aligned && (size == 1 << layer_index || end == self.leaves_len()) {
+            return self
+                .store
+                .get(layer_index, start >> layer_index)?
+                .ok_or_else(Error::not_enough_helper_nodes);
+        }
+
+        let split = largest_power_of_two_below(size);
+        let left = self.subtree_root(start, start + split)?;
+        let right = self.subtree_root(start + split, end)?;
+
+        Ok(T::concat_and_hash(&left, Some(&right)))
+    }
+
+    /// Returns the node the way it was right after the commit with the given sequence number.
+    /// The first commit made after it that touched the node holds its previous value; if no
+    /// commit did, the node hasn't changed since.
+    fn node_at(
+        &self,
+        sequence: usize,
+        layer_index: usize,
+        node_index: usize,
+    ) -> Result<Option<T::Hash>, Error> {
+        let layer_len = self.history.get(sequence).and_then(|commit| {
+            indices::layer_lens(commit.leaves_count)
+                .get(layer_index)
+                .copied()
+        });
+        match layer_len {
+            Some(layer_len) if node_index < layer_len => {}
+            _ => return Ok(None),
+        }
+
+        for commit in &self.history[sequence + 1..] {
+            if let Some(hash) = commit.overwritten.get(layer_index, node_index) {
+                return Ok(Some(*hash));
+            }
         }
 
-        0
+        self.node(layer_index, node_index)
     }
 
-    fn leaves_tuples(&self) -> Option<&[(usize, T::Hash)]> {
-        Some(self.layer_tuples().first()?.as_slice())
+    /// Returns the current values of the nodes that will be replaced by the diff, and of the
+    /// nodes that are out of bounds of a tree with `leaves_count` leaves
+    fn overwritten_nodes(
+        &self,
+        diff: &PartialTree<T>,
+        leaves_count: usize,
+    ) -> Result<PartialTree<T>, Error> {
+        let layer_lens = indices::layer_lens(leaves_count);
+        let mut overwritten_layers = Vec::new();
+
+        for layer_index in 0..self.store.layers_len() {
+            let current_len = self.store.layer_len(layer_index);
+            let retained_len = layer_lens
+                .get(layer_index)
+                .map_or(0, |layer_len| current_len.min(*layer_len));
+
+            let replaced_indices = diff
+                .layers()
+                .get(layer_index)
+                .into_iter()
+                .flatten()
+                .map(|(index, _)| *index)
+                .filter(|index| *index < retained_len);
+            let mut overwritten_layer = Vec::new();
+            for index in replaced_indices.chain(retained_len..current_len) {
+                let hash = self
+                    .store
+                    .get(layer_index, index)?
+                    .ok_or_else(Error::not_enough_helper_nodes)?;
+                overwritten_layer.push((index, hash));
+            }
+            overwritten_layers.push(overwritten_layer);
+        }
+
+        Ok(PartialTree::from_layers(overwritten_layers))
     }
 
-    /// Returns the whole tree, where the first layer is leaves and
-    /// consequent layers are nodes.
-    fn layers(&self) -> Vec<Vec<T::Hash>> {
-        self.current_working_tree.layer_nodes()
+    /// Puts the nodes overwritten by the commit back to the store. Staged updates and
+    /// truncations are dropped, as they refer to the leaves of the reverted tree.
+    fn revert_commit(&mut self, commit: &Commit<T>) -> Result<(), Error> {
+        self.store.write_batch(NodeBatch::new(
+            indices::layer_lens(commit.previous_leaves_count),
+            node_triples(&commit.overwritten),
+        ))?;
+
+        self.uncommitted_updates.clear();
+        self.uncommitted_truncation = None;
+        Ok(())
     }
 
-    fn layer_tuples(&self) -> &[Vec<(usize, T::Hash)>] {
-        self.current_working_tree.layers()
+    /// Amount of committed leaves that are left after the uncommitted truncation
+    fn retained_leaves_len(&self) -> usize {
+        match self.uncommitted_truncation {
+            Some(leaves_count) => leaves_count,
+            None => self.leaves_len(),
+        }
+    }
+
+    /// Amount of leaves the tree will have after the uncommitted changes are committed
+    fn uncommitted_leaves_len(&self) -> usize {
+        self.retained_leaves_len() + self.uncommitted_leaves.len()
     }
 
     /// Creates a diff from a changes that weren't committed to the main tree yet. Can be used
     /// to get uncommitted root or can be merged with the main tree
     fn uncommitted_diff(&self) -> Option<PartialTree<T>> {
-        if self.uncommitted_leaves.is_empty() {
+        if self.uncommitted_leaves.is_empty()
+            && self.uncommitted_updates.is_empty()
+            && self.uncommitted_truncation.is_none()
+        {
             return None;
         }
 
-        let committed_leaves_count = self.leaves_len();
+        let retained_leaves_count = self.retained_leaves_len();
+        // Figuring what tree height would be if we've committed the changes
+        let leaves_in_new_tree = self.uncommitted_leaves_len();
+        let uncommitted_tree_depth = utils::indices::tree_depth(leaves_in_new_tree);
+
+        if leaves_in_new_tree == 0 {
+            return Some(PartialTree::new());
+        }
 
-        let shadow_indices: Vec<usize> = self
-            .uncommitted_leaves
-            .iter()
-            .enumerate()
-            .map(|(index, _)| committed_leaves_count + index)
-            .collect();
         // Tuples (index, hash) needed to construct a partial tree, since partial tree can't
-        // maintain indices otherwise
-        let mut shadow_node_tuples: Vec<(usize, T::Hash)> = shadow_indices
+        // maintain indices otherwise. Updated leaves always precede the appended ones, so the
+        // tuples are sorted by index
+        let mut shadow_node_tuples: Vec<(usize, T::Hash)> = self
+            .uncommitted_updates
             .iter()
-            .cloned()
-            .zip(self.uncommitted_leaves.iter().cloned())
+            .map(|(index, hash)| (*index, *hash))
+            .chain(
+                self.uncommitted_leaves
+                    .iter()
+                    .enumerate()
+                    .map(|(index, hash)| (retained_leaves_count + index, *hash)),
+            )
             .collect();
-        let mut partial_tree_tuples = self.helper_node_tuples(&shadow_indices);
 
-        // Figuring what tree height would be if we've committed the changes
-        let leaves_in_new_tree = self.leaves_len() + self.uncommitted_leaves.len();
-        let uncommitted_tree_depth = utils::indices::tree_depth(leaves_in_new_tree);
+        // If the tree shrinks without new leaves, nodes on the path from the new last leaf
+        // to the root have to be recalculated, as their right siblings are gone
+        if self.uncommitted_truncation.is_some() && self.uncommitted_leaves.is_empty() {
+            let last_index = leaves_in_new_tree - 1;
+            if !self.uncommitted_updates.contains_key(&last_index) {
+                shadow_node_tuples.push((last_index, self.expect_node(0, last_index)?));
+            }
+        }
+
+        let shadow_indices: Vec<usize> =
+            shadow_node_tuples.iter().map(|(index, _)| *index).collect();
+        let mut partial_tree_tuples = self.helper_node_tuples(&shadow_indices, leaves_in_new_tree);
 
         match partial_tree_tuples.first_mut() {
             Some(first_layer) => {
                 first_layer.append(&mut shadow_node_tuples);
-                first_layer.sort_by(|(a, _), (b, _)| a.cmp(b));
+                first_layer.sort_by_key(|(index, _)| *index);
             }
             None => partial_tree_tuples.push(shadow_node_tuples),
         }
@@ -569,3 +1770,121 @@ impl<T: Hasher> MerkleTree<T> {
         PartialTree::<T>::build(partial_tree_tuples, uncommitted_tree_depth).ok()
     }
 }
+
+/// Committed nodes, history and uncommitted changes of a [`MerkleTree`], which are serialized
+/// by [`MerkleTree::to_bytes`] and the `serde` implementation
+pub(crate) struct TreeParts<T: Hasher> {
+    pub(crate) root: Option<T::Hash>,
+    pub(crate) layers: Vec<Vec<T::Hash>>,
+    pub(crate) commits: Vec<CommitParts<T>>,
+    pub(crate) uncommitted_leaves: Vec<T::Hash>,
+    pub(crate) uncommitted_updates: Vec<(usize, T::Hash)>,
+    pub(crate) uncommitted_truncation: Option<usize>,
+}
+
+/// A serialized [`Commit`], whose sequence number is its position in the history
+pub(crate) struct CommitParts<T: Hasher> {
+    pub(crate) root: Option<T::Hash>,
+    pub(crate) leaves_count: usize,
+    pub(crate) previous_leaves_count: usize,
+    pub(crate) overwritten: PartialTree<T>,
+}
+
+/// Returns the `(layer_index, node_index, hash)` tuples of all nodes of the partial tree
+fn node_triples<T: Hasher>(partial_tree: &PartialTree<T>) -> Vec<(usize, usize, T::Hash)> {
+    partial_tree
+        .layers()
+        .iter()
+        .enumerate()
+        .flat_map(|(layer_index, layer)| {
+            layer
+                .iter()
+                .map(move |(node_index, hash)| (layer_index, *node_index, *hash))
+        })
+        .collect()
+}
+
+/// Returns the largest power of two that is smaller than `number`. `number` must be at least 2.
+fn largest_power_of_two_below(number: usize) -> usize {
+    (number - 1).next_power_of_two() >> usize::from(!(number - 1).is_power_of_two())
+}
+
+fn write_number(bytes: &mut Vec<u8>, number: usize) {
+    bytes.extend_from_slice(&(number as u64).to_be_bytes());
+}
+
+fn write_optional_hash<T: Hasher>(bytes: &mut Vec<u8>, hash: Option<T::Hash>) {
+    match hash {
+        Some(hash) => {
+            bytes.push(1);
+            bytes.append(&mut hash.into());
+        }
+        None => bytes.push(0),
+    }
+}
+
+/// Reads the values written by [`MerkleTree::to_bytes`], returning an error instead of
+/// reading past the end of the bytes
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(Error::invalid_serialized_tree(format!(
+                "expected {} more bytes, got {}",
+                len,
+                self.bytes.len()
+            )));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Reads a byte that can't be bigger than `max`
+    fn read_flag_byte(&mut self, max: u8) -> Result<u8, Error> {
+        let byte = self.take(1)?[0];
+        if byte > max {
+            return Err(Error::invalid_serialized_tree(format!(
+                "unexpected byte {}",
+                byte
+            )));
+        }
+        Ok(byte)
+    }
+
+    fn read_number(&mut self) -> Result<usize, Error> {
+        let mut number = [0u8; 8];
+        number.copy_from_slice(self.take(8)?);
+        usize::try_from(u64::from_be_bytes(number)).map_err(|_| {
+            Error::invalid_serialized_tree(String::from("number doesn't fit into usize"))
+        })
+    }
+
+    fn read_hash<T: Hasher>(&mut self) -> Result<T::Hash, Error> {
+        T::Hash::try_from(self.take(T::hash_size())?.to_vec())
+            .map_err(|_| Error::vec_to_hash_conversion_error())
+    }
+
+    fn read_optional_hash<T: Hasher>(&mut self) -> Result<Option<T::Hash>, Error> {
+        match self.read_flag_byte(1)? {
+            1 => Ok(Some(self.read_hash::<T>()?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Reads `count` hashes, checking that the bytes are long enough before allocating them
+    fn read_hashes<T: Hasher>(&mut self, count: usize) -> Result<Vec<T::Hash>, Error> {
+        let len = count.checked_mul(T::hash_size()).ok_or_else(|| {
+            Error::invalid_serialized_tree(format!("{} hashes don't fit into memory", count))
+        })?;
+        self.take(len)?
+            .chunks(T::hash_size())
+            .map(|chunk| {
+                T::Hash::try_from(chunk.to_vec()).map_err(|_| Error::vec_to_hash_conversion_error())
+            })
+            .collect()
+    }
+}
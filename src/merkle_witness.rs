@@ -0,0 +1,193 @@
+use crate::{error::Error, prelude::*, Hasher, MerkleProof, MerkleTree, NodeStore};
+
+/// [`MerkleWitness`] keeps the proof of a single leaf of an append-only [`MerkleTree`] up to date
+/// as new leaves are appended, without storing the tree. This is what wallets do to be able to
+/// spend their notes: they mark the leaves they're interested in and drop the rest of the tree.
+///
+/// The witness stores the siblings of the path from the leaf to the root that are complete
+/// subtrees, and the roots of the complete parts of the sibling that is being filled, so it takes
+/// `O(log n)` memory and each append takes `O(log n)` hashes. Every leaf appended to the tree
+/// after the witness was marked has to be appended to the witness in the same order; updates,
+/// truncations and rollbacks of the tree are not tracked.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, MerkleWitness, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+/// let mut witness = MerkleWitness::mark(&merkle_tree, 1)?;
+///
+/// for leaf in ["d", "e"] {
+///     let leaf = Sha256::hash(leaf.as_bytes());
+///     merkle_tree.insert(leaf).commit();
+///     witness.append(leaf);
+/// }
+///
+/// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+/// let proof = witness.proof();
+/// assert!(proof.verify(root, &[1], &[leaves[1]], witness.leaves_len()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct MerkleWitness<T: Hasher> {
+    leaf_index: usize,
+    leaf: T::Hash,
+    leaves_len: usize,
+    /// Siblings of the path nodes by height. Siblings on the left are always present, siblings
+    /// on the right are present once all of their leaves are appended.
+    siblings: Vec<Option<T::Hash>>,
+    /// Roots of the complete subtrees of the sibling that is being filled, from the highest to
+    /// the lowest
+    cursor: Vec<T::Hash>,
+}
+
+impl<T: Hasher> MerkleWitness<T> {
+    /// Creates a witness for the leaf with the given index from the committed state of the tree
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the index is out of bounds of the tree
+    pub fn mark<S: NodeStore<T>>(
+        tree: &MerkleTree<T, S>,
+        leaf_index: usize,
+    ) -> Result<Self, Error> {
+        let leaves_len = tree.leaves_len();
+        if leaf_index >= leaves_len {
+            return Err(Error::leaf_index_out_of_bounds(leaves_len));
+        }
+
+        let node = |layer_index, node_index| {
+            tree.node(layer_index, node_index)?
+                .ok_or_else(Error::not_enough_helper_nodes)
+        };
+
+        let mut witness = Self {
+            leaf_index,
+            leaf: node(0, leaf_index)?,
+            leaves_len,
+            siblings: Vec::new(),
+            cursor: Vec::new(),
+        };
+
+        for height in (0..).take_while(|height| 1 << height < leaves_len) {
+            let sibling_index = (leaf_index >> height) ^ 1;
+            let sibling_start = sibling_index << height;
+
+            let sibling = if sibling_start + (1 << height) <= leaves_len {
+                Some(node(height, sibling_index)?)
+            } else {
+                if sibling_start < leaves_len {
+                    let cursor_len = leaves_len - sibling_start;
+                    for level in (0..height)
+                        .rev()
+                        .filter(|level| cursor_len >> level & 1 == 1)
+                    {
+                        let start = sibling_start + (cursor_len >> (level + 1) << (level + 1));
+                        witness.cursor.push(node(level, start >> level)?);
+                    }
+                }
+                None
+            };
+            witness.siblings.push(sibling);
+        }
+
+        Ok(witness)
+    }
+
+    /// Appends a leaf the same way it was appended to the tree. Takes `O(log n)` hashes.
+    pub fn append(&mut self, leaf: T::Hash) -> &mut Self {
+        // Leaves are appended to the lowest sibling on the right that isn't complete yet
+        let height = (0..)
+            .find(|height| self.siblings.get(*height).copied().flatten().is_none())
+            .unwrap_or_default();
+        let sibling_start = ((self.leaf_index >> height) + 1) << height;
+        let cursor_len = self.leaves_len - sibling_start;
+
+        let mut node = leaf;
+        let mut complete_subtrees = cursor_len;
+        while complete_subtrees & 1 == 1 {
+            if let Some(left) = self.cursor.pop() {
+                node = T::concat_and_hash(&left, Some(&node));
+            }
+            complete_subtrees >>= 1;
+        }
+        self.cursor.push(node);
+
+        if self.siblings.len() <= height {
+            self.siblings.resize(height + 1, None);
+        }
+        if cursor_len + 1 == 1 << height {
+            self.siblings[height] = Some(node);
+            self.cursor.clear();
+        }
+        self.leaves_len += 1;
+
+        self
+    }
+
+    /// Returns the proof of the leaf for the current state of the tree. The proof verifies
+    /// against [`MerkleTree::root`] with [`MerkleWitness::leaves_len`] leaves.
+    ///
+    /// For examples, please check [`MerkleWitness`]
+    pub fn proof(&self) -> MerkleProof<T> {
+        let proof_hashes = (0..)
+            .take_while(|height| 1 << height < self.leaves_len)
+            .filter_map(|height| {
+                if let Some(sibling) = self.siblings.get(height).copied().flatten() {
+                    return Some(sibling);
+                }
+
+                let sibling_start = ((self.leaf_index >> height) + 1) << height;
+                if sibling_start >= self.leaves_len {
+                    return None;
+                }
+                Some(self.cursor_root(height, self.leaves_len - sibling_start))
+            })
+            .collect();
+
+        MerkleProof::with_metadata(proof_hashes, vec![self.leaf_index], self.leaves_len)
+    }
+
+    /// Returns the index of the leaf
+    pub fn leaf_index(&self) -> usize {
+        self.leaf_index
+    }
+
+    /// Returns the hash of the leaf
+    pub fn leaf(&self) -> T::Hash {
+        self.leaf
+    }
+
+    /// Returns the number of leaves in the tree the witness is up to date with
+    pub fn leaves_len(&self) -> usize {
+        self.leaves_len
+    }
+
+    /// Calculates the root of the sibling that is being filled. As the sibling contains the last
+    /// leaf of the tree, the last node of each of its layers is hashed according to
+    /// [`Hasher::ODD_NODE_POLICY`].
+    fn cursor_root(&self, height: usize, cursor_len: usize) -> T::Hash {
+        let mut complete_subtrees = self.cursor.iter().rev();
+        let mut node: Option<T::Hash> = None;
+
+        for level in 0..height {
+            node = if cursor_len >> level & 1 == 1 {
+                match (complete_subtrees.next(), node) {
+                    (Some(left), node) => Some(T::concat_and_hash(left, node.as_ref())),
+                    (None, node) => node,
+                }
+            } else {
+                node.map(|node| T::concat_and_hash(&node, None))
+            };
+        }
+
+        node.unwrap_or(self.leaf)
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{node_stores::NodeBatch, prelude::*, Error, Hasher, NodeStore};
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+const JOURNAL_FILE_NAME: &str = "journal";
+const JOURNAL_TMP_FILE_NAME: &str = "journal.tmp";
+
+/// Keeps the nodes in a directory on disk, so the tree can be bigger than the available memory
+/// and can be reopened after a restart with [`MerkleTree::with_store`].
+///
+/// Every layer is stored in its own file, `layer_0` being the leaves, as a sequence of hashes,
+/// so a node is read with a single seek. Batches are written to a journal file first, which is
+/// synced to disk and renamed before the layer files are modified. If the process stops while
+/// the layer files are being modified, the journal is replayed the next time the store is
+/// opened, so a batch is either applied completely or not at all. The journal holds the number
+/// of layers followed by their lengths, and the number of nodes followed by the layer index,
+/// the node index and the hash of every node, all numbers being big endian `u64`.
+///
+/// If applying a batch fails after its journal is written, for example because the disk is
+/// full, the layer files may hold a part of the batch. The store then refuses to read and write
+/// nodes until it's reopened, which completes the batch from the journal.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, node_stores::FileStore, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let directory = std::env::temp_dir().join("rs_merkle_file_store_doc");
+/// # let _ = std::fs::remove_dir_all(&directory);
+/// let store = FileStore::<Sha256>::open(&directory)?;
+/// let mut merkle_tree = MerkleTree::with_store(store);
+/// merkle_tree
+///     .insert(Sha256::hash("a".as_bytes()))
+///     .insert(Sha256::hash("b".as_bytes()))
+///     .try_commit()?;
+/// let root = merkle_tree.root();
+/// drop(merkle_tree);
+///
+/// let mut merkle_tree = MerkleTree::with_store(FileStore::<Sha256>::open(&directory)?);
+/// assert_eq!(merkle_tree.root(), root);
+/// assert_eq!(merkle_tree.leaves_len(), 2);
+///
+/// merkle_tree.insert(Sha256::hash("c".as_bytes())).try_commit()?;
+/// merkle_tree.try_rollback()?;
+/// assert_eq!(merkle_tree.root(), root);
+/// # std::fs::remove_dir_all(&directory)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleTree::with_store`]: crate::MerkleTree::with_store
+pub struct FileStore<T: Hasher> {
+    directory: PathBuf,
+    layer_files: Mutex<Vec<File>>,
+    layer_lens: Vec<usize>,
+    /// Set when a batch was partially applied, so the layer files can't be trusted until the
+    /// journal is replayed by reopening the store
+    failed: bool,
+    _hasher: PhantomData<T>,
+}
+
+impl<T: Hasher> FileStore<T> {
+    /// Opens the store in the given directory, creating the directory if it doesn't exist.
+    /// Completes the batch that was being written when the store was closed, if any.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the directory can't be read, or if the stored layers aren't made of
+    /// whole hashes of the hasher
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self, Error> {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory).map_err(io_error)?;
+
+        // A journal that wasn't renamed wasn't complete, so its batch was never applied
+        let journal_tmp_path = directory.join(JOURNAL_TMP_FILE_NAME);
+        if journal_tmp_path.exists() {
+            fs::remove_file(&journal_tmp_path).map_err(io_error)?;
+        }
+
+        let mut layer_files = Vec::new();
+        loop {
+            let path = layer_path(&directory, layer_files.len());
+            if !path.exists() {
+                break;
+            }
+            layer_files.push(open_layer_file(&path).map_err(io_error)?);
+        }
+
+        let mut store = Self {
+            directory,
+            layer_files: Mutex::new(layer_files),
+            layer_lens: Vec::new(),
+            failed: false,
+            _hasher: PhantomData,
+        };
+
+        let journal_path = store.directory.join(JOURNAL_FILE_NAME);
+        if journal_path.exists() {
+            let file_lens = store.file_lens().map_err(io_error)?;
+            let batch = decode_journal::<T>(&fs::read(&journal_path).map_err(io_error)?, |i| {
+                file_lens
+                    .get(i)
+                    .map_or(0, |file_len| file_len / T::hash_size() as u64)
+            })?;
+            store.apply_batch(&batch).map_err(io_error)?;
+            fs::remove_file(&journal_path).map_err(io_error)?;
+        }
+
+        let hash_size = T::hash_size() as u64;
+        let mut layer_lens = Vec::new();
+        for (layer_index, file_len) in store.file_lens().map_err(io_error)?.into_iter().enumerate()
+        {
+            if file_len % hash_size != 0 {
+                return Err(Error::node_store_error(format!(
+                    "layer {} of {} bytes isn't made of {} bytes hashes",
+                    layer_index, file_len, hash_size
+                )));
+            }
+            let layer_len = usize::try_from(file_len / hash_size).map_err(|_| {
+                Error::node_store_error(format!("layer {} is too long", layer_index))
+            })?;
+            layer_lens.push(layer_len);
+        }
+        store.layer_lens = layer_lens;
+
+        Ok(store)
+    }
+
+    /// Returns the directory of the store
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the sizes of the layer files in bytes
+    fn file_lens(&mut self) -> io::Result<Vec<u64>> {
+        self.layer_files
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|file| file.metadata().map(|metadata| metadata.len()))
+            .collect()
+    }
+
+    /// Returns an error if the store holds a partially applied batch
+    fn ensure_not_failed(&self) -> Result<(), Error> {
+        if self.failed {
+            return Err(Error::node_store_error(String::from(
+                "a batch was partially applied, the store has to be reopened to complete it",
+            )));
+        }
+        Ok(())
+    }
+
+    /// Writes the journal to a temporary file and renames it once it's synced to disk, so
+    /// the journal is never seen half-written
+    fn write_journal(&self, batch: &NodeBatch<T>) -> io::Result<()> {
+        let journal_tmp_path = self.directory.join(JOURNAL_TMP_FILE_NAME);
+        let mut journal = File::create(&journal_tmp_path)?;
+        journal.write_all(&encode_journal(batch))?;
+        journal.sync_all()?;
+        fs::rename(&journal_tmp_path, self.directory.join(JOURNAL_FILE_NAME))?;
+        sync_directory(&self.directory)
+    }
+
+    /// Resizes the layer files and writes the batch nodes. Applying the same batch twice gives
+    /// the same result, which allows to replay the journal.
+    fn apply_batch(&mut self, batch: &NodeBatch<T>) -> io::Result<()> {
+        let hash_size = T::hash_size() as u64;
+        let layer_files = self
+            .layer_files
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        while layer_files.len() > batch.layer_lens().len() {
+            // The file is forgotten only once it's removed, so a failed removal is retried when
+            // the journal is replayed
+            match fs::remove_file(layer_path(&self.directory, layer_files.len() - 1)) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                _ => layer_files.pop(),
+            };
+        }
+        for (layer_index, layer_len) in batch.layer_lens().iter().enumerate() {
+            if layer_index == layer_files.len() {
+                let path = layer_path(&self.directory, layer_index);
+                layer_files.push(open_layer_file(&path)?);
+            }
+            layer_files[layer_index].set_len(*layer_len as u64 * hash_size)?;
+        }
+
+        for (layer_index, node_index, hash) in batch.nodes() {
+            let file = &mut layer_files[*layer_index];
+            file.seek(SeekFrom::Start(*node_index as u64 * hash_size))?;
+            let bytes: Vec<u8> = (*hash).into();
+            file.write_all(&bytes)?;
+        }
+        for file in layer_files.iter() {
+            file.sync_all()?;
+        }
+        sync_directory(&self.directory)?;
+
+        self.layer_lens = batch.layer_lens().to_vec();
+        Ok(())
+    }
+}
+
+impl<T: Hasher> NodeStore<T> for FileStore<T> {
+    fn get(&self, layer_index: usize, node_index: usize) -> Result<Option<T::Hash>, Error> {
+        self.ensure_not_failed()?;
+        if node_index >= self.layer_len(layer_index) {
+            return Ok(None);
+        }
+
+        let mut layer_files = self
+            .layer_files
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let file = &mut layer_files[layer_index];
+        let mut bytes = vec![0u8; T::hash_size()];
+        file.seek(SeekFrom::Start(node_index as u64 * T::hash_size() as u64))
+            .and_then(|_| file.read_exact(&mut bytes))
+            .map_err(io_error)?;
+
+        T::Hash::try_from(bytes)
+            .map(Some)
+            .map_err(|_| Error::vec_to_hash_conversion_error())
+    }
+
+    fn put(&mut self, layer_index: usize, node_index: usize, hash: T::Hash) -> Result<(), Error> {
+        self.ensure_not_failed()?;
+        if layer_index > self.layers_len() || node_index > self.layer_len(layer_index) {
+            return Err(Error::node_store_error(format!(
+                "node {} of layer {} would leave a gap in the tree",
+                node_index, layer_index
+            )));
+        }
+
+        let layer_files = self
+            .layer_files
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        if layer_index == layer_files.len() {
+            let path = layer_path(&self.directory, layer_index);
+            layer_files.push(open_layer_file(&path).map_err(io_error)?);
+            self.layer_lens.push(0);
+        }
+
+        let file = &mut layer_files[layer_index];
+        let bytes: Vec<u8> = hash.into();
+        file.seek(SeekFrom::Start(node_index as u64 * T::hash_size() as u64))
+            .and_then(|_| file.write_all(&bytes))
+            .map_err(io_error)?;
+
+        if node_index == self.layer_lens[layer_index] {
+            self.layer_lens[layer_index] += 1;
+        }
+        Ok(())
+    }
+
+    fn layer_len(&self, layer_index: usize) -> usize {
+        self.layer_lens
+            .get(layer_index)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn layers_len(&self) -> usize {
+        self.layer_lens.len()
+    }
+
+    /// Writes the batch to the journal and applies it to the layer files. If the batch may have
+    /// been partially applied, the store refuses to read and write nodes until it's reopened,
+    /// which completes the batch from the journal.
+    fn write_batch(&mut self, batch: NodeBatch<T>) -> Result<(), Error> {
+        self.ensure_not_failed()?;
+        batch.validate(|layer_index| self.layer_len(layer_index))?;
+
+        // Once the journal is renamed, the batch is completed when the store is reopened, so any
+        // failure from then on leaves the store in the failed state
+        if let Err(error) = self
+            .write_journal(&batch)
+            .and_then(|_| self.apply_batch(&batch))
+        {
+            self.failed = true;
+            return Err(io_error(error));
+        }
+        fs::remove_file(self.directory.join(JOURNAL_FILE_NAME)).map_err(io_error)
+    }
+}
+
+fn layer_path(directory: &Path, layer_index: usize) -> PathBuf {
+    directory.join(format!("layer_{}", layer_index))
+}
+
+fn open_layer_file(path: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+}
+
+/// Makes file creations, renames and removals in the directory durable
+fn sync_directory(directory: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(directory)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = directory;
+
+    Ok(())
+}
+
+fn io_error(error: io::Error) -> Error {
+    Error::node_store_error(error.to_string())
+}
+
+/// Encodes the batch as the number of layers followed by their lengths, and the number of nodes
+/// followed by their layer index, node index and hash. Numbers are big endian `u64`.
+fn encode_journal<T: Hasher>(batch: &NodeBatch<T>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    bytes.extend_from_slice(&(batch.layer_lens().len() as u64).to_be_bytes());
+    for layer_len in batch.layer_lens() {
+        bytes.extend_from_slice(&(*layer_len as u64).to_be_bytes());
+    }
+
+    bytes.extend_from_slice(&(batch.nodes().len() as u64).to_be_bytes());
+    for (layer_index, node_index, hash) in batch.nodes() {
+        bytes.extend_from_slice(&(*layer_index as u64).to_be_bytes());
+        bytes.extend_from_slice(&(*node_index as u64).to_be_bytes());
+        bytes.append(&mut (*hash).into());
+    }
+
+    bytes
+}
+
+/// Decodes a journal written by [`encode_journal`]. Rejects the nodes that are out of bounds of
+/// the layer lengths of the batch, and the layers that grow past their stored length, given in
+/// nodes, without the batch writing the added nodes.
+fn decode_journal<T: Hasher>(
+    bytes: &[u8],
+    stored_layer_len: impl Fn(usize) -> u64,
+) -> Result<NodeBatch<T>, Error> {
+    let mut journal = JournalReader { bytes };
+
+    let layers_len = journal.take_number()?;
+    let layer_lens = (0..layers_len)
+        .map(|_| journal.take_number())
+        .collect::<Result<Vec<usize>, Error>>()?;
+
+    let nodes_len = journal.take_number()?;
+    let mut nodes = Vec::new();
+    // Number of nodes of every layer up to the last node written by the batch
+    let mut written_lens = vec![0u64; layer_lens.len()];
+    for _ in 0..nodes_len {
+        let layer_index = journal.take_number()?;
+        let node_index = journal.take_number()?;
+        let hash = T::Hash::try_from(journal.take(T::hash_size())?.to_vec())
+            .map_err(|_| Error::vec_to_hash_conversion_error())?;
+        match layer_lens.get(layer_index) {
+            Some(layer_len) if node_index < *layer_len => {
+                written_lens[layer_index] = written_lens[layer_index].max(node_index as u64 + 1);
+            }
+            _ => return Err(malformed_journal()),
+        }
+        nodes.push((layer_index, node_index, hash));
+    }
+
+    if !journal.bytes.is_empty() {
+        return Err(malformed_journal());
+    }
+
+    let hash_size = T::hash_size() as u64;
+    for (layer_index, (layer_len, written_len)) in layer_lens.iter().zip(written_lens).enumerate() {
+        let layer_len = *layer_len as u64;
+        if layer_len > written_len.max(stored_layer_len(layer_index))
+            || layer_len.checked_mul(hash_size).is_none()
+        {
+            return Err(malformed_journal());
+        }
+    }
+
+    Ok(NodeBatch::new(layer_lens, nodes))
+}
+
+fn malformed_journal() -> Error {
+    Error::node_store_error(String::from("journal is malformed"))
+}
+
+struct JournalReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> JournalReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(malformed_journal());
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn take_number(&mut self) -> Result<usize, Error> {
+        let mut number = [0u8; 8];
+        number.copy_from_slice(self.take(8)?);
+        usize::try_from(u64::from_be_bytes(number)).map_err(|_| malformed_journal())
+    }
+}
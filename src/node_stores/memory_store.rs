@@ -0,0 +1,71 @@
+use crate::{node_stores::NodeBatch, prelude::*, CompleteTree, Error, Hasher, NodeStore};
+
+/// Keeps the nodes in memory, in the contiguous layout of [`CompleteTree`]. This is the default
+/// store of [`MerkleTree`].
+///
+/// [`MerkleTree`]: crate::MerkleTree
+#[derive(Clone)]
+pub struct MemoryStore<T: Hasher> {
+    tree: CompleteTree<T>,
+}
+
+impl<T: Hasher> MemoryStore<T> {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self {
+            tree: CompleteTree::new(),
+        }
+    }
+
+    /// Creates a store that keeps the nodes of the given tree
+    pub(crate) fn from_tree(tree: CompleteTree<T>) -> Self {
+        Self { tree }
+    }
+
+    /// Returns all layers of the store, from the leaves to the root
+    pub fn layers(&self) -> Vec<&[T::Hash]> {
+        self.tree.layers()
+    }
+
+    /// Returns the stored nodes as a [`CompleteTree`]
+    pub fn tree(&self) -> &CompleteTree<T> {
+        &self.tree
+    }
+}
+
+impl<T: Hasher> Default for MemoryStore<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hasher> NodeStore<T> for MemoryStore<T> {
+    fn get(&self, layer_index: usize, node_index: usize) -> Result<Option<T::Hash>, Error> {
+        Ok(self.tree.get(layer_index, node_index).copied())
+    }
+
+    fn put(&mut self, layer_index: usize, node_index: usize, hash: T::Hash) -> Result<(), Error> {
+        self.tree.set(layer_index, node_index, hash)
+    }
+
+    fn layer_len(&self, layer_index: usize) -> usize {
+        self.tree.layer_len(layer_index)
+    }
+
+    fn layers_len(&self) -> usize {
+        self.tree.layers_len()
+    }
+
+    fn write_batch(&mut self, batch: NodeBatch<T>) -> Result<(), Error> {
+        batch.validate(|layer_index| self.layer_len(layer_index))?;
+
+        // The batch fills all positions added to the layers, so they can be resized before
+        // the nodes are written
+        self.tree.resize_layers(batch.layer_lens());
+        for (layer_index, node_index, hash) in batch.nodes() {
+            self.tree.set(*layer_index, *node_index, *hash)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+//! This module contains the [`NodeStore`] trait and its built-in implementations. Stores keep
+//! the committed nodes of a [`MerkleTree`], see [`MerkleTree::with_store`]
+//!
+//! [`NodeStore`]: crate::NodeStore
+//! [`MerkleTree`]: crate::MerkleTree
+//! [`MerkleTree::with_store`]: crate::MerkleTree::with_store
+
+#[cfg(feature = "std")]
+mod file_store;
+mod memory_store;
+mod node_store;
+
+#[cfg(feature = "std")]
+pub use file_store::FileStore;
+pub use memory_store::MemoryStore;
+pub use node_store::{NodeBatch, NodeStore};
@@ -0,0 +1,108 @@
+use crate::{prelude::*, Error, Hasher};
+
+/// Trait representing a storage of the committed nodes of a [`MerkleTree`]. Nodes are keyed by
+/// the layer index, starting from the leaves, and the node index inside of the layer.
+///
+/// The layers of a tree are always contiguous: a layer of `n` nodes contains the nodes with
+/// indices from `0` to `n - 1`. The tree reads single nodes with [`NodeStore::get`] and writes
+/// all changes of a commit or a rollback with a single [`NodeStore::write_batch`] call.
+///
+/// The library provides some built-in implementations of this trait - check
+/// [`node_stores`] module.
+///
+/// [`MerkleTree`]: crate::MerkleTree
+/// [`node_stores`]: crate::node_stores
+pub trait NodeStore<T: Hasher> {
+    /// Returns the node with the given index on the given layer, or [`None`] if it's out of
+    /// bounds of the layer
+    fn get(&self, layer_index: usize, node_index: usize) -> Result<Option<T::Hash>, Error>;
+
+    /// Writes a single node, replacing the existing one or appending it to the end of the
+    /// layer. The node index can't be bigger than the layer length.
+    fn put(&mut self, layer_index: usize, node_index: usize, hash: T::Hash) -> Result<(), Error>;
+
+    /// Returns the number of nodes on the given layer
+    fn layer_len(&self, layer_index: usize) -> usize;
+
+    /// Returns the number of layers, including the root layer
+    fn layers_len(&self) -> usize;
+
+    /// Applies all changes of the batch atomically, so the store never ends up with a part of
+    /// the batch applied, even if the process stops in the middle of the write
+    fn write_batch(&mut self, batch: NodeBatch<T>) -> Result<(), Error>;
+}
+
+/// A set of changes applied to a [`NodeStore`] at once. Every layer of the store is resized to
+/// the length from [`NodeBatch::layer_lens`], and the layers above them are removed; then the
+/// [`NodeBatch::nodes`] are written. The nodes have to fill all positions added to the layers,
+/// so that the layers stay contiguous.
+#[derive(Clone)]
+pub struct NodeBatch<T: Hasher> {
+    layer_lens: Vec<usize>,
+    nodes: Vec<(usize, usize, T::Hash)>,
+}
+
+impl<T: Hasher> NodeBatch<T> {
+    /// Creates a batch from the lengths of the layers after the batch is applied and the
+    /// `(layer_index, node_index, hash)` tuples of the nodes to write
+    pub fn new(layer_lens: Vec<usize>, nodes: Vec<(usize, usize, T::Hash)>) -> Self {
+        Self { layer_lens, nodes }
+    }
+
+    /// Returns the lengths of the layers after the batch is applied
+    pub fn layer_lens(&self) -> &[usize] {
+        &self.layer_lens
+    }
+
+    /// Returns the `(layer_index, node_index, hash)` tuples of the nodes to write
+    pub fn nodes(&self) -> &[(usize, usize, T::Hash)] {
+        &self.nodes
+    }
+
+    /// Checks that the nodes are in bounds of the new layers and fill all positions that aren't
+    /// retained from the current layers, whose lengths are returned by `current_layer_len`
+    pub(crate) fn validate<F>(&self, current_layer_len: F) -> Result<(), Error>
+    where
+        F: Fn(usize) -> usize,
+    {
+        let mut added_nodes: Vec<Vec<usize>> = vec![Vec::new(); self.layer_lens.len()];
+
+        for (layer_index, node_index, _) in &self.nodes {
+            let layer_len = match self.layer_lens.get(*layer_index) {
+                Some(layer_len) if node_index < layer_len => *layer_len,
+                _ => {
+                    return Err(Error::node_store_error(format!(
+                        "node {} of layer {} is out of bounds of the batch layers",
+                        node_index, layer_index
+                    )))
+                }
+            };
+            if *node_index >= current_layer_len(*layer_index).min(layer_len) {
+                added_nodes[*layer_index].push(*node_index);
+            }
+        }
+
+        for (layer_index, (layer_len, added_nodes)) in self
+            .layer_lens
+            .iter()
+            .zip(added_nodes.iter_mut())
+            .enumerate()
+        {
+            added_nodes.sort_unstable();
+            added_nodes.dedup();
+            let retained_len = current_layer_len(layer_index).min(*layer_len);
+            if retained_len + added_nodes.len() != *layer_len {
+                return Err(Error::node_store_error(format!(
+                    "batch leaves a gap in layer {}: {} nodes are retained and {} are added to \
+                     a layer of {} nodes",
+                    layer_index,
+                    retained_len,
+                    added_nodes.len(),
+                    layer_len
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -30,6 +30,28 @@ impl<T: Hasher> PartialTree<T> {
         Self { layers: Vec::new() }
     }
 
+    /// Creates a partial tree from the `(index, hash)` tuples of every layer without
+    /// calculating any hashes. The tuples of each layer have to be sorted by index.
+    pub(crate) fn from_layers(layers: Vec<Vec<(usize, T::Hash)>>) -> Self {
+        Self { layers }
+    }
+
+    /// Same as [`PartialTree::from_layers`], but returns an error if the tuples of a layer
+    /// aren't sorted by index or contain the same index twice. Used to restore deserialized
+    /// trees.
+    pub(crate) fn try_from_layers(layers: Vec<Vec<(usize, T::Hash)>>) -> Result<Self, Error> {
+        for (layer_index, layer) in layers.iter().enumerate() {
+            if layer.windows(2).any(|nodes| nodes[0].0 >= nodes[1].0) {
+                return Err(Error::invalid_serialized_tree(format!(
+                    "nodes of layer {} aren't sorted by index",
+                    layer_index
+                )));
+            }
+        }
+
+        Ok(Self::from_layers(layers))
+    }
+
     /// This is a helper function to build a full tree from a full set of leaves without any
     /// helper indices
     pub fn from_leaves(leaves: &[T::Hash]) -> Result<Self, Error> {
@@ -68,25 +90,26 @@ impl<T: Hasher> PartialTree<T> {
             if let Some(mut nodes) = reversed_layers.pop() {
                 current_layer.append(&mut nodes);
             }
-            current_layer.sort_by(|(a, _), (b, _)| a.cmp(b));
+            current_layer.sort_by_key(|(index, _)| *index);
 
             // Adding partial layer to the tree
             partial_tree.push(current_layer.clone());
 
             // This empties `current` layer and prepares it to be reused for the next iteration
             let (indices, nodes): (Vec<usize>, Vec<T::Hash>) = current_layer.drain(..).unzip();
-            let parent_layer_indices = utils::indices::parent_indices(&indices);
 
-            for (i, parent_node_index) in parent_layer_indices.iter().enumerate() {
-                match nodes.get(i * 2) {
-                    // Populate `current_layer` back for the next iteration
-                    Some(left_node) => current_layer.push((
-                        *parent_node_index,
-                        T::concat_and_hash(left_node, nodes.get(i * 2 + 1)),
-                    )),
-                    None => return Err(Error::not_enough_helper_nodes()),
-                }
+            // A layer consisting of a single node with index 0 can only be the root layer. The
+            // root is carried over unchanged, so it doesn't get rehashed by the odd node policy
+            // of the hasher.
+            if indices == [0] {
+                current_layer.push((0, nodes[0]));
+                continue;
             }
+
+            let parent_layer_indices = utils::indices::parent_indices(&indices);
+
+            // Populate `current_layer` back for the next iteration
+            current_layer = Self::hash_parents(&parent_layer_indices, &nodes)?;
         }
 
         partial_tree.push(current_layer.clone());
@@ -94,6 +117,21 @@ impl<T: Hasher> PartialTree<T> {
         Ok(partial_tree)
     }
 
+    /// Hashes the known nodes of a layer in pairs into their parents with
+    /// [`Hasher::hash_layer`]. Every parent needs at least its left child.
+    fn hash_parents(
+        parent_indices: &[usize],
+        nodes: &[T::Hash],
+    ) -> Result<Vec<(usize, T::Hash)>, Error> {
+        let children_len = nodes.len().min(parent_indices.len() * 2);
+        if children_len < parent_indices.len() * 2 - 1 {
+            return Err(Error::not_enough_helper_nodes());
+        }
+
+        let parents = T::hash_layer(&nodes[..children_len]);
+        Ok(parent_indices.iter().copied().zip(parents).collect())
+    }
+
     /// Returns how many layers there is between leaves and the root
     pub fn depth(&self) -> usize {
         self.layers.len() - 1
@@ -104,11 +142,18 @@ impl<T: Hasher> PartialTree<T> {
         Some(&self.layers.last()?.first()?.1)
     }
 
+    /// Returns the hash of the node at the given position, if the node is present in the tree
+    pub fn get(&self, layer_index: usize, node_index: usize) -> Option<&T::Hash> {
+        let layer = self.layers().get(layer_index)?;
+        let position = layer
+            .binary_search_by_key(&node_index, |(index, _)| *index)
+            .ok()?;
+
+        Some(&layer[position].1)
+    }
+
     pub fn contains(&self, layer_index: usize, node_index: usize) -> bool {
-        match self.layers().get(layer_index) {
-            Some(layer) => layer.iter().any(|(index, _)| *index == node_index),
-            None => false,
-        }
+        self.get(layer_index, node_index).is_some()
     }
 
     /// Consumes other partial tree into itself, replacing any conflicting nodes with nodes from
@@ -119,12 +164,7 @@ impl<T: Hasher> PartialTree<T> {
     /// no need to verify integrity of the result.
     pub fn merge_unverified(&mut self, other: Self) {
         // Figure out new tree depth after merge
-        let depth_difference = other.layers().len() - self.layers().len();
-        let combined_tree_size = if depth_difference > 0 {
-            other.layers().len()
-        } else {
-            self.layers().len()
-        };
+        let combined_tree_size = core::cmp::max(other.layers().len(), self.layers().len());
 
         for layer_index in 0..combined_tree_size {
             let mut combined_layer: Vec<(usize, T::Hash)> = Vec::new();
@@ -144,11 +184,110 @@ impl<T: Hasher> PartialTree<T> {
                 combined_layer.append(&mut cloned_other_layer);
             }
 
-            combined_layer.sort_by(|(a, _), (b, _)| a.cmp(b));
+            combined_layer.sort_by_key(|(index, _)| *index);
             self.upsert_layer(layer_index, combined_layer);
         }
     }
 
+    /// Removes all nodes that are out of bounds of a tree with `leaves_count` leaves, including
+    /// the layers above the root of such tree. Used to shrink the tree when leaves are removed
+    /// from it. Returns a partial tree with the removed nodes.
+    pub fn truncate(&mut self, leaves_count: usize) -> Self {
+        let mut removed = Self::new();
+        let depth = match leaves_count {
+            0 => 0,
+            _ => utils::indices::tree_depth(leaves_count) + 1,
+        };
+
+        let mut layer_width = leaves_count;
+        for (layer_index, layer) in self.layers.iter_mut().enumerate() {
+            let retained_nodes = match layer_index < depth {
+                true => layer.partition_point(|(index, _)| *index < layer_width),
+                false => 0,
+            };
+            removed.upsert_layer(layer_index, layer.split_off(retained_nodes));
+            layer_width = utils::indices::div_ceil(layer_width, 2);
+        }
+        self.layers.truncate(depth);
+
+        removed
+    }
+
+    /// Applies a diff produced for a tree with `leaves_count` leaves: nodes from `diff` replace
+    /// the existing ones, and nodes that are out of bounds of the new tree are removed.
+    ///
+    /// Returns a partial tree with the previous values of all replaced and removed nodes,
+    /// which can be passed to [`PartialTree::revert_diff`] to undo the changes. Both operations
+    /// take time proportional to the size of the diff rather than the size of the tree, as long
+    /// as the nodes are only appended to the end of the layers or replaced in place, which is
+    /// always the case for a complete tree.
+    pub fn apply_diff(&mut self, diff: &Self, leaves_count: usize) -> Self {
+        let mut overwritten = self.truncate(leaves_count);
+
+        for (layer_index, layer) in diff.layers().iter().enumerate() {
+            for (node_index, hash) in layer {
+                if let Some(previous_hash) = self.upsert_node(layer_index, *node_index, *hash) {
+                    overwritten.upsert_node(layer_index, *node_index, previous_hash);
+                }
+            }
+        }
+
+        overwritten
+    }
+
+    /// Undoes [`PartialTree::apply_diff`]: removes all nodes written by `diff` and puts back
+    /// the `overwritten` nodes returned by [`PartialTree::apply_diff`].
+    pub fn revert_diff(&mut self, diff: &Self, overwritten: &Self) {
+        for (layer_index, layer) in diff.layers().iter().enumerate() {
+            for (node_index, _) in layer {
+                self.remove_node(layer_index, *node_index);
+            }
+        }
+
+        for (layer_index, layer) in overwritten.layers().iter().enumerate() {
+            for (node_index, hash) in layer {
+                self.upsert_node(layer_index, *node_index, *hash);
+            }
+        }
+
+        // Layers that were added on top of the tree by the diff are empty now
+        while self.layers.last().is_some_and(|layer| layer.is_empty()) {
+            self.layers.pop();
+        }
+    }
+
+    /// Inserts a node or replaces an existing one, keeping the layer sorted. Returns the
+    /// replaced hash, if any.
+    fn upsert_node(
+        &mut self,
+        layer_index: usize,
+        node_index: usize,
+        hash: T::Hash,
+    ) -> Option<T::Hash> {
+        while self.layers.len() <= layer_index {
+            self.layers.push(Vec::new());
+        }
+        let layer = &mut self.layers[layer_index];
+
+        match layer.binary_search_by_key(&node_index, |(index, _)| *index) {
+            Ok(position) => Some(core::mem::replace(&mut layer[position].1, hash)),
+            Err(position) => {
+                layer.insert(position, (node_index, hash));
+                None
+            }
+        }
+    }
+
+    /// Removes a node from the layer, returning its hash if the node was present
+    fn remove_node(&mut self, layer_index: usize, node_index: usize) -> Option<T::Hash> {
+        let layer = self.layers.get_mut(layer_index)?;
+        let position = layer
+            .binary_search_by_key(&node_index, |(index, _)| *index)
+            .ok()?;
+
+        Some(layer.remove(position).1)
+    }
+
     /// Replace layer at a given index with a new layer. Used during tree merge
     fn upsert_layer(&mut self, layer_index: usize, mut new_layer: Vec<(usize, T::Hash)>) {
         match self.layers.get_mut(layer_index) {
@@ -8,7 +8,9 @@
 mod direct_hashes_order;
 mod merkle_proof_serializer;
 mod reverse_hashes_order;
+mod self_describing;
 
 pub use direct_hashes_order::DirectHashesOrder;
 pub use merkle_proof_serializer::MerkleProofSerializer;
 pub use reverse_hashes_order::ReverseHashesOrder;
+pub use self_describing::SelfDescribing;
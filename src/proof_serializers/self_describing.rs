@@ -0,0 +1,118 @@
+use crate::{prelude::*, Error, Hasher, MerkleProof, MerkleProofSerializer};
+use core::convert::{TryFrom, TryInto};
+
+/// Version of the [`SelfDescribing`] format written by this version of the library
+const FORMAT_VERSION: u8 = 1;
+
+/// Size of the version, the algorithm id, the leaf count and the index count
+const HEADER_SIZE: usize = 1 + 2 + 8 + 8;
+
+/// Serializes the proof together with everything needed to verify it, except the leaves:
+///
+/// - the format version, 1 byte;
+/// - the [`Hasher::ALGORITHM_ID`] of the hasher, 2 bytes;
+/// - the number of leaves in the tree, 8 bytes;
+/// - the number of proven leaves, 8 bytes, followed by their indices, 8 bytes each;
+/// - the proof hashes, in direct order.
+///
+/// All numbers are big-endian. A proof without metadata is written with zero leaves and no
+/// indices, and is read back without metadata. Deserialized proofs can be verified with
+/// [`MerkleProof::verify_with_metadata`].
+///
+/// ## Errors
+///
+/// [`MerkleProof::deserialize`] fails if the format version isn't supported, the proof was made
+/// with a hasher with a different [`Hasher::ALGORITHM_ID`], the input is truncated or a leaf
+/// index is out of bounds of the tree.
+pub struct SelfDescribing {}
+
+impl MerkleProofSerializer for SelfDescribing {
+    fn serialize<T: Hasher>(proof: &MerkleProof<T>) -> Vec<u8> {
+        let leaf_indices = proof.leaf_indices().unwrap_or(&[]);
+        let total_leaves_count = proof.total_leaves_count().unwrap_or(0);
+
+        let mut bytes = Vec::with_capacity(
+            HEADER_SIZE + leaf_indices.len() * 8 + proof.proof_hashes().len() * T::hash_size(),
+        );
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&T::ALGORITHM_ID.to_be_bytes());
+        bytes.extend_from_slice(&(total_leaves_count as u64).to_be_bytes());
+        bytes.extend_from_slice(&(leaf_indices.len() as u64).to_be_bytes());
+        for index in leaf_indices {
+            bytes.extend_from_slice(&(*index as u64).to_be_bytes());
+        }
+        for hash in proof.proof_hashes() {
+            let hash: Vec<u8> = (*hash).into();
+            bytes.extend_from_slice(&hash);
+        }
+
+        bytes
+    }
+
+    fn deserialize<T: Hasher>(bytes: &[u8]) -> Result<MerkleProof<T>, Error> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(Error::serialized_proof_too_short(bytes.len(), HEADER_SIZE));
+        }
+
+        let version = bytes[0];
+        if version != FORMAT_VERSION {
+            return Err(Error::unsupported_format_version(version, FORMAT_VERSION));
+        }
+
+        let algorithm_id = u16::from_be_bytes([bytes[1], bytes[2]]);
+        if algorithm_id != T::ALGORITHM_ID {
+            return Err(Error::hash_algorithm_mismatch(
+                T::ALGORITHM_ID,
+                algorithm_id,
+            ));
+        }
+
+        // Counts that don't fit into usize can't be satisfied by the input either
+        let too_short = || Error::serialized_proof_too_short(bytes.len(), usize::MAX);
+        let total_leaves_count = read_number(&bytes[3..11]).ok_or_else(too_short)?;
+        let indices_end = read_number(&bytes[11..HEADER_SIZE])
+            .and_then(|indices_count| indices_count.checked_mul(8))
+            .and_then(|indices_size| indices_size.checked_add(HEADER_SIZE))
+            .ok_or_else(too_short)?;
+        let indices_bytes = bytes
+            .get(HEADER_SIZE..indices_end)
+            .ok_or_else(|| Error::serialized_proof_too_short(bytes.len(), indices_end))?;
+
+        let mut leaf_indices = Vec::with_capacity(indices_bytes.len() / 8);
+        for chunk in indices_bytes.chunks_exact(8) {
+            match read_number(chunk) {
+                Some(index) if index < total_leaves_count => leaf_indices.push(index),
+                _ => return Err(Error::leaf_index_out_of_bounds(total_leaves_count)),
+            }
+        }
+
+        let hashes_bytes = &bytes[indices_end..];
+        let hash_size = T::hash_size();
+        if hashes_bytes.len() % hash_size != 0 {
+            return Err(Error::wrong_proof_size(hashes_bytes.len(), hash_size));
+        }
+
+        let proof_hashes = hashes_bytes
+            .chunks_exact(hash_size)
+            .map(|chunk| {
+                T::Hash::try_from(chunk.to_vec()).map_err(|_| Error::vec_to_hash_conversion_error())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if total_leaves_count == 0 && leaf_indices.is_empty() {
+            Ok(MerkleProof::new(proof_hashes))
+        } else {
+            Ok(MerkleProof::with_metadata(
+                proof_hashes,
+                leaf_indices,
+                total_leaves_count,
+            ))
+        }
+    }
+}
+
+/// Reads a big-endian u64 from 8 bytes, returns [`None`] if it doesn't fit into usize
+fn read_number(bytes: &[u8]) -> Option<usize> {
+    let bytes: [u8; 8] = bytes.try_into().ok()?;
+    usize::try_from(u64::from_be_bytes(bytes)).ok()
+}
@@ -0,0 +1,102 @@
+//! `Encode`, `Decode` and `TypeInfo` implementations for [`MerkleProof`]. The proof is encoded
+//! as its hashes, the same way as a `Vec` of them, i.e. as the compact number of hashes followed
+//! by their bytes, and then as the metadata of the proof, the same way as an
+//! `Option<(Vec<u64>, u64)>` of the leaf indices and the number of leaves. The number of hashes
+//! is handled as a `Compact<u64>`, which has the same encoding as the `Compact<u32>` length of a
+//! `Vec`, but never wraps.
+
+use crate::{prelude::*, Hasher, MerkleProof};
+use core::convert::TryFrom;
+use parity_scale_codec::{Compact, Decode, Encode, EncodeLike, Error, Input, Output};
+use scale_info::{build::Fields, Path, Type, TypeInfo};
+
+type MetadataRepr = Option<(Vec<u64>, u64)>;
+
+fn metadata_repr<T: Hasher>(proof: &MerkleProof<T>) -> MetadataRepr {
+    let leaf_indices = proof.leaf_indices()?;
+    let total_leaves_count = proof.total_leaves_count()?;
+
+    Some((
+        leaf_indices.iter().map(|index| *index as u64).collect(),
+        total_leaves_count as u64,
+    ))
+}
+
+fn to_usize(number: u64) -> Result<usize, Error> {
+    usize::try_from(number).map_err(|_| Error::from("proof length doesn't fit into usize"))
+}
+
+impl<T: Hasher> Encode for MerkleProof<T> {
+    fn size_hint(&self) -> usize {
+        let hashes_len = self.proof_hashes().len();
+        Compact(hashes_len as u64).size_hint()
+            + hashes_len * T::hash_size()
+            + metadata_repr(self).size_hint()
+    }
+
+    fn encode_to<W: Output + ?Sized>(&self, dest: &mut W) {
+        Compact(self.proof_hashes().len() as u64).encode_to(dest);
+        for hash in self.proof_hashes() {
+            let bytes: Vec<u8> = (*hash).into();
+            dest.write(&bytes);
+        }
+        metadata_repr(self).encode_to(dest);
+    }
+}
+
+impl<T: Hasher> EncodeLike for MerkleProof<T> {}
+
+impl<T: Hasher> Decode for MerkleProof<T> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let hashes_len = to_usize(<Compact<u64>>::decode(input)?.0)?;
+
+        let mut proof_hashes = Vec::new();
+        for _ in 0..hashes_len {
+            let mut bytes = vec![0u8; T::hash_size()];
+            input.read(&mut bytes)?;
+            let hash = T::Hash::try_from(bytes)
+                .map_err(|_| Error::from("proof hash doesn't match the hasher hash type"))?;
+            proof_hashes.push(hash);
+        }
+
+        match MetadataRepr::decode(input)? {
+            Some((leaf_indices, total_leaves_count)) => Ok(MerkleProof::with_metadata(
+                proof_hashes,
+                leaf_indices
+                    .into_iter()
+                    .map(to_usize)
+                    .collect::<Result<_, _>>()?,
+                to_usize(total_leaves_count)?,
+            )),
+            None => Ok(MerkleProof::new(proof_hashes)),
+        }
+    }
+}
+
+impl<T> TypeInfo for MerkleProof<T>
+where
+    T: Hasher + 'static,
+    T::Hash: TypeInfo + 'static,
+{
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::builder()
+            .path(Path::new("MerkleProof", module_path!()))
+            .composite(
+                Fields::named()
+                    .field(|field| {
+                        field
+                            .ty::<Vec<T::Hash>>()
+                            .name("proof_hashes")
+                            .type_name("Vec<T::Hash>")
+                    })
+                    .field(|field| {
+                        field
+                            .ty::<MetadataRepr>()
+                            .name("metadata")
+                            .type_name("Option<(Vec<u64>, u64)>")
+                    }),
+            )
+    }
+}
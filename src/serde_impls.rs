@@ -0,0 +1,287 @@
+//! `Serialize` and `Deserialize` implementations for [`MerkleProof`], [`PartialTree`] and
+//! [`MerkleTree`]. Hashes are written as hex strings in human-readable formats, such as JSON,
+//! and as bytes in binary formats, such as CBOR.
+
+use crate::merkle_tree::{CommitParts, TreeParts};
+use crate::{prelude::*, utils, Hasher, MerkleProof, MerkleTree, PartialTree};
+use core::convert::TryFrom;
+use core::fmt::Formatter;
+use core::marker::PhantomData;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A hash that is serialized as a hex string or as bytes depending on the format
+struct HashRepr<H>(H);
+
+impl<H: Copy + Into<Vec<u8>>> Serialize for HashRepr<H> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&utils::collections::to_hex_string(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0.into())
+        }
+    }
+}
+
+impl<'de, H: TryFrom<Vec<u8>>> Deserialize<'de> for HashRepr<H> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(HashVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_bytes(HashVisitor(PhantomData))
+        }
+    }
+}
+
+struct HashVisitor<H>(PhantomData<H>);
+
+impl<H: TryFrom<Vec<u8>>> HashVisitor<H> {
+    fn hash<E: de::Error>(bytes: Vec<u8>) -> Result<HashRepr<H>, E> {
+        let len = bytes.len();
+        H::try_from(bytes)
+            .map(HashRepr)
+            .map_err(|_| E::invalid_length(len, &"a hash of the hasher size"))
+    }
+}
+
+impl<'de, H: TryFrom<Vec<u8>>> Visitor<'de> for HashVisitor<H> {
+    type Value = HashRepr<H>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("a hash as a hex string or bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        let bytes = utils::collections::from_hex_string(value)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))?;
+        Self::hash(bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Self::hash(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Self::hash(value)
+    }
+
+    /// Some binary formats don't have a bytes type and write bytes as a sequence
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Self::hash(bytes)
+    }
+}
+
+fn to_reprs<H: Copy>(hashes: &[H]) -> Vec<HashRepr<H>> {
+    hashes.iter().map(|hash| HashRepr(*hash)).collect()
+}
+
+fn from_reprs<H>(hashes: Vec<HashRepr<H>>) -> Vec<H> {
+    hashes.into_iter().map(|hash| hash.0).collect()
+}
+
+fn to_indexed_reprs<H: Copy>(nodes: &[(usize, H)]) -> Vec<(usize, HashRepr<H>)> {
+    nodes
+        .iter()
+        .map(|(index, hash)| (*index, HashRepr(*hash)))
+        .collect()
+}
+
+fn from_indexed_reprs<H>(nodes: Vec<(usize, HashRepr<H>)>) -> Vec<(usize, H)> {
+    nodes
+        .into_iter()
+        .map(|(index, hash)| (index, hash.0))
+        .collect()
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(
+    rename = "MerkleProof",
+    bound(
+        serialize = "HashRepr<H>: Serialize",
+        deserialize = "HashRepr<H>: Deserialize<'de>"
+    )
+)]
+struct MerkleProofRepr<H> {
+    proof_hashes: Vec<HashRepr<H>>,
+    /// Missing in the proofs serialized before the metadata was added
+    #[serde(default)]
+    metadata: Option<ProofMetadataRepr>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ProofMetadata")]
+struct ProofMetadataRepr {
+    leaf_indices: Vec<usize>,
+    total_leaves_count: usize,
+}
+
+/// The leaf indices and the number of leaves are written as an optional `metadata` field, so
+/// the proof can still be verified with [`MerkleProof::verify_with_metadata`] after a round
+/// trip.
+impl<T: Hasher> Serialize for MerkleProof<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let metadata = self.leaf_indices().zip(self.total_leaves_count()).map(
+            |(leaf_indices, total_leaves_count)| ProofMetadataRepr {
+                leaf_indices: leaf_indices.to_vec(),
+                total_leaves_count,
+            },
+        );
+
+        MerkleProofRepr {
+            proof_hashes: to_reprs(self.proof_hashes()),
+            metadata,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Hasher> Deserialize<'de> for MerkleProof<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MerkleProofRepr::<T::Hash>::deserialize(deserializer)?;
+        let proof_hashes = from_reprs(repr.proof_hashes);
+
+        Ok(match repr.metadata {
+            Some(metadata) => MerkleProof::with_metadata(
+                proof_hashes,
+                metadata.leaf_indices,
+                metadata.total_leaves_count,
+            ),
+            None => MerkleProof::new(proof_hashes),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(
+    rename = "PartialTree",
+    bound(
+        serialize = "HashRepr<H>: Serialize",
+        deserialize = "HashRepr<H>: Deserialize<'de>"
+    )
+)]
+struct PartialTreeRepr<H> {
+    layers: Vec<Vec<(usize, HashRepr<H>)>>,
+}
+
+impl<H: Copy> PartialTreeRepr<H> {
+    fn new<T: Hasher<Hash = H>>(partial_tree: &PartialTree<T>) -> Self {
+        Self {
+            layers: partial_tree
+                .layers()
+                .iter()
+                .map(|layer| to_indexed_reprs(layer))
+                .collect(),
+        }
+    }
+
+    fn into_partial_tree<T: Hasher<Hash = H>>(self) -> Result<PartialTree<T>, crate::Error> {
+        PartialTree::try_from_layers(self.layers.into_iter().map(from_indexed_reprs).collect())
+    }
+}
+
+impl<T: Hasher> Serialize for PartialTree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PartialTreeRepr::new(self).serialize(serializer)
+    }
+}
+
+impl<'de, T: Hasher> Deserialize<'de> for PartialTree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PartialTreeRepr::<T::Hash>::deserialize(deserializer)?
+            .into_partial_tree()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(
+    rename = "Commit",
+    bound(
+        serialize = "HashRepr<H>: Serialize",
+        deserialize = "HashRepr<H>: Deserialize<'de>"
+    )
+)]
+struct CommitRepr<H> {
+    root: Option<HashRepr<H>>,
+    leaves_count: usize,
+    previous_leaves_count: usize,
+    overwritten: PartialTreeRepr<H>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(
+    rename = "MerkleTree",
+    bound(
+        serialize = "HashRepr<H>: Serialize",
+        deserialize = "HashRepr<H>: Deserialize<'de>"
+    )
+)]
+struct MerkleTreeRepr<H> {
+    root: Option<HashRepr<H>>,
+    layers: Vec<Vec<HashRepr<H>>>,
+    commits: Vec<CommitRepr<H>>,
+    uncommitted_leaves: Vec<HashRepr<H>>,
+    uncommitted_updates: Vec<(usize, HashRepr<H>)>,
+    uncommitted_truncation: Option<usize>,
+}
+
+/// The tree is serialized with its history and uncommitted changes, the same way as with
+/// [`MerkleTree::to_bytes`]. The nodes and the root are checked against the leaves on
+/// deserialization.
+impl<T: Hasher> Serialize for MerkleTree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let parts = self.to_parts();
+
+        MerkleTreeRepr {
+            root: parts.root.map(HashRepr),
+            layers: parts.layers.iter().map(|layer| to_reprs(layer)).collect(),
+            commits: parts
+                .commits
+                .iter()
+                .map(|commit| CommitRepr {
+                    root: commit.root.map(HashRepr),
+                    leaves_count: commit.leaves_count,
+                    previous_leaves_count: commit.previous_leaves_count,
+                    overwritten: PartialTreeRepr::new(&commit.overwritten),
+                })
+                .collect(),
+            uncommitted_leaves: to_reprs(&parts.uncommitted_leaves),
+            uncommitted_updates: to_indexed_reprs(&parts.uncommitted_updates),
+            uncommitted_truncation: parts.uncommitted_truncation,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Hasher> Deserialize<'de> for MerkleTree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MerkleTreeRepr::<T::Hash>::deserialize(deserializer)?;
+
+        let mut commits = Vec::with_capacity(repr.commits.len());
+        for commit in repr.commits {
+            commits.push(CommitParts {
+                root: commit.root.map(|root| root.0),
+                leaves_count: commit.leaves_count,
+                previous_leaves_count: commit.previous_leaves_count,
+                overwritten: commit
+                    .overwritten
+                    .into_partial_tree()
+                    .map_err(de::Error::custom)?,
+            });
+        }
+
+        MerkleTree::from_parts(TreeParts {
+            root: repr.root.map(|root| root.0),
+            layers: repr.layers.into_iter().map(from_reprs).collect(),
+            commits,
+            uncommitted_leaves: from_reprs(repr.uncommitted_leaves),
+            uncommitted_updates: from_indexed_reprs(repr.uncommitted_updates),
+            uncommitted_truncation: repr.uncommitted_truncation,
+        })
+        .map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    algorithms::SortedPair, error::Error, prelude::*, utils, Hasher, MerkleProof, SortedMultiProof,
+};
+use alloc::collections::VecDeque;
+
+/// [`SimpleMerkleTree`] is a Merkle tree with the same layout as OpenZeppelin's
+/// [`SimpleMerkleTree`](https://github.com/OpenZeppelin/merkle-tree), so its roots and proofs
+/// can be verified by OpenZeppelin's `MerkleProof.sol`.
+///
+/// Nodes are hashed with [`SortedPair`], and the tree is stored as a complete binary tree in an
+/// array: the leaves occupy the end of the array in reverse order, and the children of the node
+/// `i` are `2i + 1` and `2i + 2`. Unlike [`MerkleTree`], every internal node has two children,
+/// which is what makes the flag multiproofs of [`SortedMultiProof`] possible for any set of
+/// leaves.
+///
+/// The tree is immutable. Leaf indices used for proofs are the positions of the leaves in
+/// [`SimpleMerkleTree::leaves`].
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{SimpleMerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves(&leaves);
+/// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+///
+/// let proof = merkle_tree.proof(2)?;
+/// assert!(proof.verify_leaf(root, leaves[2]));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SortedPair`]: crate::algorithms::SortedPair
+/// [`MerkleTree`]: crate::MerkleTree
+#[derive(Clone)]
+pub struct SimpleMerkleTree<H: Hasher> {
+    tree: Vec<H::Hash>,
+    leaves_count: usize,
+}
+
+impl<H: Hasher> SimpleMerkleTree<H>
+where
+    H::Hash: AsRef<[u8]>,
+{
+    /// Builds a tree from the leaves, keeping their order.
+    pub fn from_leaves(leaves: &[H::Hash]) -> Self {
+        let leaves_count = leaves.len();
+        if leaves_count == 0 {
+            return Self {
+                tree: Vec::new(),
+                leaves_count,
+            };
+        }
+
+        let mut tree = Vec::with_capacity(2 * leaves_count - 1);
+        tree.resize(leaves_count - 1, leaves[0]);
+        tree.extend(leaves.iter().rev());
+
+        for index in (0..leaves_count - 1).rev() {
+            tree[index] = SortedPair::<H>::concat_and_hash(
+                &tree[left_child_index(index)],
+                Some(&tree[right_child_index(index)]),
+            );
+        }
+
+        Self { tree, leaves_count }
+    }
+
+    /// Builds a tree from the leaves sorted in the ascending byte order. This is the default
+    /// behavior of OpenZeppelin's `SimpleMerkleTree.of`, and makes the root independent of the
+    /// order of the leaves.
+    pub fn from_leaves_sorted(leaves: &[H::Hash]) -> Self {
+        let mut sorted_leaves = leaves.to_vec();
+        sorted_leaves.sort_by_cached_key(|leaf| -> Vec<u8> { (*leaf).into() });
+
+        Self::from_leaves(&sorted_leaves)
+    }
+
+    /// Returns the tree root, or `None` if the tree is empty
+    pub fn root(&self) -> Option<H::Hash> {
+        self.tree.first().copied()
+    }
+
+    /// Same as [`root`](SimpleMerkleTree::root), but serialized as a hex string
+    pub fn root_hex(&self) -> Option<String> {
+        let root = self.root()?;
+        Some(utils::collections::to_hex_string(&root))
+    }
+
+    /// Returns the leaves in the order they're indexed in
+    pub fn leaves(&self) -> Vec<H::Hash> {
+        self.tree[self.tree.len() - self.leaves_count..]
+            .iter()
+            .rev()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the number of leaves in the tree
+    pub fn leaves_len(&self) -> usize {
+        self.leaves_count
+    }
+
+    /// Returns the proof for a single leaf, compatible with OpenZeppelin's `MerkleProof.verify`.
+    /// The proof can be verified with [`MerkleProof::verify_leaf`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the leaf index is out of bounds
+    pub fn proof(&self, leaf_index: usize) -> Result<MerkleProof<SortedPair<H>>, Error> {
+        let mut index = self.tree_index(leaf_index)?;
+        let mut proof_hashes = Vec::new();
+
+        while index > 0 {
+            proof_hashes.push(self.tree[sibling_index(index)]);
+            index = parent_index(index);
+        }
+
+        Ok(MerkleProof::new(proof_hashes))
+    }
+
+    /// Returns the flag multiproof for the given leaves, compatible with OpenZeppelin's
+    /// `MerkleProof.multiProofVerify`. The leaves have to be passed to the verifier in the
+    /// order of their indices, regardless of the order of `leaf_indices`.
+    ///
+    /// For examples, please check [`SortedMultiProof`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if any of the leaf indices is out of bounds or duplicated
+    pub fn multi_proof(&self, leaf_indices: &[usize]) -> Result<SortedMultiProof<H>, Error> {
+        let mut tree_indices = leaf_indices
+            .iter()
+            .map(|leaf_index| self.tree_index(*leaf_index))
+            .collect::<Result<Vec<usize>, Error>>()?;
+        tree_indices.sort_unstable_by(|a, b| b.cmp(a));
+        if tree_indices.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(Error::duplicate_leaf_index());
+        }
+
+        let mut proof_hashes = Vec::new();
+        let mut proof_flags = Vec::new();
+
+        let mut queue: VecDeque<usize> = tree_indices.into();
+        while let Some(index) = queue.pop_front() {
+            if index == 0 {
+                break;
+            }
+
+            let sibling = sibling_index(index);
+            if queue.front() == Some(&sibling) {
+                proof_flags.push(true);
+                queue.pop_front();
+            } else {
+                proof_flags.push(false);
+                proof_hashes.push(self.tree[sibling]);
+            }
+            queue.push_back(parent_index(index));
+        }
+
+        if leaf_indices.is_empty() {
+            proof_hashes.extend(self.root());
+        }
+
+        Ok(SortedMultiProof::new(proof_hashes, proof_flags))
+    }
+
+    /// Returns all nodes of the tree in the array layout, starting from the root
+    #[cfg(feature = "json")]
+    pub(crate) fn nodes(&self) -> &[H::Hash] {
+        &self.tree
+    }
+
+    pub(crate) fn tree_index(&self, leaf_index: usize) -> Result<usize, Error> {
+        if leaf_index >= self.leaves_count {
+            return Err(Error::leaf_index_out_of_bounds(self.leaves_count));
+        }
+
+        Ok(self.tree.len() - 1 - leaf_index)
+    }
+}
+
+fn left_child_index(index: usize) -> usize {
+    2 * index + 1
+}
+
+fn right_child_index(index: usize) -> usize {
+    2 * index + 2
+}
+
+fn parent_index(index: usize) -> usize {
+    (index - 1) / 2
+}
+
+fn sibling_index(index: usize) -> usize {
+    if index % 2 == 1 {
+        index + 1
+    } else {
+        index - 1
+    }
+}
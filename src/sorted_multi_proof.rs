@@ -0,0 +1,137 @@
+use crate::{algorithms::SortedPair, error::Error, prelude::*, utils, Hasher};
+
+/// [`SortedMultiProof`] proves that several leaves belong to a tree built with the
+/// [`SortedPair`] hashing, using a vector of flags instead of the leaf indices. The format
+/// is the one used by OpenZeppelin's `MerkleProof.multiProofVerify`: every flag tells whether
+/// the next node is combined with another known node or with the next hash from the proof.
+///
+/// The leaves need to be passed in the order of their indices in the tree. Proofs are produced
+/// by [`SimpleMerkleTree::multi_proof`].
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{SimpleMerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves(&leaves);
+/// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+///
+/// let proof = merkle_tree.multi_proof(&[4, 1])?;
+/// assert!(proof.verify(root, &[leaves[1], leaves[4]]));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SortedPair`]: crate::algorithms::SortedPair
+/// [`SimpleMerkleTree::multi_proof`]: crate::SimpleMerkleTree::multi_proof
+pub struct SortedMultiProof<H: Hasher> {
+    proof_hashes: Vec<H::Hash>,
+    proof_flags: Vec<bool>,
+}
+
+impl<H: Hasher> SortedMultiProof<H>
+where
+    H::Hash: AsRef<[u8]>,
+{
+    pub fn new(proof_hashes: Vec<H::Hash>, proof_flags: Vec<bool>) -> Self {
+        SortedMultiProof {
+            proof_hashes,
+            proof_flags,
+        }
+    }
+
+    /// Calculates the root from the leaves, the same way OpenZeppelin's
+    /// `MerkleProof.processMultiProof` does. The leaves must be sorted by their indices.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the number of flags doesn't match the number of leaves and proof
+    /// hashes, or if the flags reference more nodes than there are.
+    pub fn root(&self, leaves: &[H::Hash]) -> Result<H::Hash, Error> {
+        let total_hashes = self.proof_flags.len();
+        let invalid_proof_flags =
+            || Error::invalid_proof_flags(total_hashes, leaves.len(), self.proof_hashes.len());
+        if leaves.len() + self.proof_hashes.len() != total_hashes + 1 {
+            return Err(invalid_proof_flags());
+        }
+
+        let mut leaves_iter = leaves.iter();
+        let mut proof_hashes = self.proof_hashes.iter();
+        let mut hashes: Vec<H::Hash> = Vec::with_capacity(total_hashes);
+        let mut hash_position = 0;
+
+        // Known nodes are consumed as a queue: the leaves go first, then the hashes
+        // calculated on the previous iterations
+        let mut next_known_node = |hashes: &Vec<H::Hash>| match leaves_iter.next() {
+            Some(leaf) => Some(*leaf),
+            None => {
+                let hash = hashes.get(hash_position).copied();
+                hash_position += 1;
+                hash
+            }
+        };
+
+        for flag in &self.proof_flags {
+            let left = next_known_node(&hashes);
+            let right = if *flag {
+                next_known_node(&hashes)
+            } else {
+                proof_hashes.next().copied()
+            };
+
+            match (left, right) {
+                (Some(left), Some(right)) => {
+                    hashes.push(SortedPair::<H>::concat_and_hash(&left, Some(&right)))
+                }
+                _ => return Err(Error::not_enough_hashes_to_calculate_root()),
+            }
+        }
+
+        if let Some(root) = hashes.last() {
+            if proof_hashes.next().is_some() {
+                return Err(invalid_proof_flags());
+            }
+            return Ok(*root);
+        }
+
+        next_known_node(&hashes)
+            .or_else(|| self.proof_hashes.first().copied())
+            .ok_or_else(Error::not_enough_hashes_to_calculate_root)
+    }
+
+    /// Checks that the leaves belong to the tree with the given root. Compatible with
+    /// OpenZeppelin's `MerkleProof.multiProofVerify`.
+    ///
+    /// For examples, please check [`SortedMultiProof`]
+    pub fn verify(&self, root: H::Hash, leaves: &[H::Hash]) -> bool {
+        match self.root(leaves) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the proof hashes in the order they are consumed by the verifier
+    pub fn proof_hashes(&self) -> &[H::Hash] {
+        &self.proof_hashes
+    }
+
+    /// Returns all hashes from the proof as a vector of lower hex strings.
+    /// For a slice of [`Hasher::Hash`], see [`SortedMultiProof::proof_hashes`]
+    pub fn proof_hashes_hex(&self) -> Vec<String> {
+        self.proof_hashes
+            .iter()
+            .map(utils::collections::to_hex_string)
+            .collect()
+    }
+
+    /// Returns the proof flags. `true` means that the next known node is combined with
+    /// another known node, `false` means it's combined with the next proof hash.
+    pub fn proof_flags(&self) -> &[bool] {
+        &self.proof_flags
+    }
+}
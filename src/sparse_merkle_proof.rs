@@ -0,0 +1,232 @@
+use crate::{
+    error::Error,
+    hasher,
+    prelude::*,
+    proof_serializers::{DirectHashesOrder, MerkleProofSerializer},
+    utils::{
+        self,
+        keys::{Key, KEY_BITS},
+    },
+    Hasher, MerkleProof,
+};
+
+/// Size of the bitmap of non-empty siblings in bytes
+const BITMAP_SIZE: usize = KEY_BITS / 8;
+
+/// [`SparseMerkleProof`] proves that a key is present in a [`SparseMerkleTree`] with a given
+/// value, or that the key is absent from the tree.
+///
+/// The proof is compressed: siblings that are roots of empty subtrees are not included.
+/// Instead, the proof contains a bitmap with a bit set for every layer that has a non-empty
+/// sibling, so a proof for a tree with `n` keys carries about `log2(n)` hashes instead of 256.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{SparseMerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut tree = SparseMerkleTree::<Sha256>::new();
+/// let key = Sha256::hash("alice".as_bytes());
+/// let value = Sha256::hash("100".as_bytes());
+/// tree.insert(key, value);
+///
+/// let proof = tree.proof(&key);
+/// assert!(proof.verify(tree.root(), &key, Some(value)));
+///
+/// let missing_key = Sha256::hash("bob".as_bytes());
+/// let proof = tree.proof(&missing_key);
+/// assert!(proof.verify(tree.root(), &missing_key, None));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SparseMerkleTree`]: crate::SparseMerkleTree
+pub struct SparseMerkleProof<T: Hasher> {
+    bitmap: [u8; BITMAP_SIZE],
+    proof_hashes: Vec<T::Hash>,
+}
+
+impl<T: Hasher> SparseMerkleProof<T> {
+    /// Creates a proof from the bitmap of non-empty siblings and their hashes, ordered from
+    /// the leaf to the root. Bit `i` of the bitmap, i.e. `bitmap[i / 8] >> (i % 8) & 1`, is set
+    /// if the sibling on layer `i` is not empty.
+    pub fn new(bitmap: [u8; BITMAP_SIZE], proof_hashes: Vec<T::Hash>) -> Self {
+        SparseMerkleProof {
+            bitmap,
+            proof_hashes,
+        }
+    }
+
+    /// Returns the roots of the empty subtrees of every height, from the empty leaf to the root
+    /// of an empty tree. [`SparseMerkleProof::root`] calculates them on every call, so verifiers
+    /// that check many proofs can calculate them once and pass them to
+    /// [`SparseMerkleProof::verify_with_empty_hashes`] instead. A [`SparseMerkleTree`] keeps
+    /// the same hashes, see [`SparseMerkleTree::empty_hashes`].
+    ///
+    /// [`SparseMerkleTree`]: crate::SparseMerkleTree
+    /// [`SparseMerkleTree::empty_hashes`]: crate::SparseMerkleTree::empty_hashes
+    pub fn empty_hashes() -> Vec<T::Hash> {
+        hasher::empty_hashes::<T>(KEY_BITS)
+    }
+
+    /// Calculates the root of the tree in which the key has the given value, or is absent
+    /// if the value is `None`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the number of hashes doesn't match the bitmap
+    pub fn root(&self, key: &Key, value: Option<T::Hash>) -> Result<T::Hash, Error> {
+        self.root_with_empty_hashes(key, value, &Self::empty_hashes())
+    }
+
+    /// Same as [`SparseMerkleProof::root`], but takes the roots of the empty subtrees returned
+    /// by [`SparseMerkleProof::empty_hashes`] instead of calculating them.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the number of hashes doesn't match the bitmap, or if there is no
+    /// empty subtree root for some of the heights
+    pub fn root_with_empty_hashes(
+        &self,
+        key: &Key,
+        value: Option<T::Hash>,
+        empty_hashes: &[T::Hash],
+    ) -> Result<T::Hash, Error> {
+        let non_empty_siblings = self
+            .bitmap
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum::<usize>();
+        if non_empty_siblings != self.proof_hashes.len() || empty_hashes.len() < KEY_BITS {
+            return Err(Error::not_enough_hashes_to_calculate_root());
+        }
+
+        let mut proof_hashes = self.proof_hashes.iter();
+        let mut node = value.unwrap_or(empty_hashes[0]);
+
+        for (layer, empty_hash) in empty_hashes.iter().take(KEY_BITS).enumerate() {
+            let sibling = if self.has_sibling(layer) {
+                proof_hashes.next().unwrap_or(empty_hash)
+            } else {
+                empty_hash
+            };
+
+            node = if utils::keys::bit(key, layer) {
+                T::concat_and_hash(sibling, Some(&node))
+            } else {
+                T::concat_and_hash(&node, Some(sibling))
+            };
+        }
+
+        Ok(node)
+    }
+
+    /// Verifies that the key has the given value in the tree with the given root. Pass `None`
+    /// as the value to verify that the key is absent from the tree.
+    ///
+    /// For examples, please check [`SparseMerkleProof`]
+    pub fn verify(&self, root: T::Hash, key: &Key, value: Option<T::Hash>) -> bool {
+        match self.root(key, value) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Same as [`SparseMerkleProof::verify`], but takes the roots of the empty subtrees returned
+    /// by [`SparseMerkleProof::empty_hashes`] instead of calculating them for every proof.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{SparseMerkleProof, SparseMerkleTree, algorithms::Sha256, Hasher};
+    /// let mut tree = SparseMerkleTree::<Sha256>::new();
+    /// let keys: Vec<[u8; 32]> = (0..10u8).map(|i| Sha256::hash(&[i])).collect();
+    /// for key in &keys {
+    ///     tree.insert(*key, Sha256::hash(key));
+    /// }
+    ///
+    /// let empty_hashes = SparseMerkleProof::<Sha256>::empty_hashes();
+    /// for key in &keys {
+    ///     let proof = tree.proof(key);
+    ///     let value = Some(Sha256::hash(key));
+    ///     assert!(proof.verify_with_empty_hashes(tree.root(), key, value, &empty_hashes));
+    /// }
+    /// ```
+    pub fn verify_with_empty_hashes(
+        &self,
+        root: T::Hash,
+        key: &Key,
+        value: Option<T::Hash>,
+        empty_hashes: &[T::Hash],
+    ) -> bool {
+        match self.root_with_empty_hashes(key, value, empty_hashes) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the bitmap of the non-empty siblings
+    pub fn bitmap(&self) -> &[u8; BITMAP_SIZE] {
+        &self.bitmap
+    }
+
+    /// Returns the hashes of the non-empty siblings, from the leaf to the root
+    pub fn proof_hashes(&self) -> &[T::Hash] {
+        &self.proof_hashes
+    }
+
+    /// Returns all hashes from the proof as a vector of lower hex strings.
+    /// For a slice of [`Hasher::Hash`], see [`SparseMerkleProof::proof_hashes`]
+    pub fn proof_hashes_hex(&self) -> Vec<String> {
+        self.proof_hashes
+            .iter()
+            .map(utils::collections::to_hex_string)
+            .collect()
+    }
+
+    /// Serializes the proof to bytes: the 32 bytes bitmap followed by the proof hashes in
+    /// direct order. To use a different hashes serializer, see [`SparseMerkleProof::serialize`]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.serialize::<DirectHashesOrder>()
+    }
+
+    /// Parses a proof serialized with [`SparseMerkleProof::to_bytes`]
+    ///
+    /// ## Errors
+    ///
+    /// In case of a parsing error result will contain [`Error`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::deserialize::<DirectHashesOrder>(bytes)
+    }
+
+    /// Serializes the proof to bytes: the 32 bytes bitmap followed by the proof hashes
+    /// serialized with the given [`MerkleProofSerializer`]
+    pub fn serialize<S: MerkleProofSerializer>(&self) -> Vec<u8> {
+        let mut bytes = self.bitmap.to_vec();
+        bytes.append(&mut S::serialize(&MerkleProof::<T>::new(
+            self.proof_hashes.clone(),
+        )));
+        bytes
+    }
+
+    /// Parses a proof serialized with [`SparseMerkleProof::serialize`]
+    ///
+    /// ## Errors
+    ///
+    /// In case of a parsing error result will contain [`Error`]
+    pub fn deserialize<S: MerkleProofSerializer>(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < BITMAP_SIZE {
+            return Err(Error::wrong_proof_size(bytes.len(), BITMAP_SIZE));
+        }
+
+        let mut bitmap = [0u8; BITMAP_SIZE];
+        bitmap.copy_from_slice(&bytes[..BITMAP_SIZE]);
+        let proof = S::deserialize::<T>(&bytes[BITMAP_SIZE..])?;
+
+        Ok(Self::new(bitmap, proof.proof_hashes().to_vec()))
+    }
+
+    fn has_sibling(&self, layer: usize) -> bool {
+        self.bitmap[layer / 8] >> (layer % 8) & 1 == 1
+    }
+}
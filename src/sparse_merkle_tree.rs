@@ -0,0 +1,179 @@
+use crate::{
+    error::Error,
+    hasher,
+    prelude::*,
+    utils::{
+        self,
+        keys::{Key, KEY_BITS},
+    },
+    Hasher, SparseMerkleProof,
+};
+use alloc::collections::BTreeMap;
+
+/// [`SparseMerkleTree`] is a Merkle tree with a leaf for every possible 256-bit key, most of
+/// which are empty. It's used to map keys to values, and to prove both that a key has some
+/// value and that a key is absent from the map.
+///
+/// The tree has 256 layers, and the position of a leaf is its key read as a big endian integer.
+/// Only non-empty nodes are stored; empty subtrees are represented by precomputed hashes, so
+/// every update takes 256 hashes regardless of the number of keys. The value of a key is used
+/// as its leaf hash, and the empty leaf is a hash made of zero bytes.
+/// Setting a key to the empty leaf hash is the same as removing it.
+///
+/// Every key stores the full path from its leaf to the root: a node on each of the 256 layers,
+/// kept in a `BTreeMap` per layer along with its 32 bytes index. Only the top `log2(n)` layers
+/// of a tree with `n` keys are shared between the keys, so the tree takes about
+/// `(256 - log2(n)) * n` nodes. With 32 bytes hashes, that's up to 16 KiB of indices and hashes
+/// per key, plus the overhead of the maps.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{SparseMerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut tree = SparseMerkleTree::<Sha256>::new();
+/// let empty_root = tree.root();
+///
+/// let key = Sha256::hash("alice".as_bytes());
+/// tree.insert(key, Sha256::hash("100".as_bytes()));
+/// tree.update(key, Sha256::hash("150".as_bytes()))?;
+/// assert_eq!(tree.get(&key), Some(Sha256::hash("150".as_bytes())));
+///
+/// tree.remove(&key);
+/// assert_eq!(tree.root(), empty_root);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SparseMerkleTree<T: Hasher> {
+    /// Non-empty nodes of every layer, from the leaves to the children of the root, keyed by
+    /// their indices on the layer
+    layers: Vec<BTreeMap<Key, T::Hash>>,
+    /// Roots of the empty subtrees of every height, from the empty leaf to the empty tree root
+    empty_hashes: Vec<T::Hash>,
+    root: T::Hash,
+}
+
+impl<T: Hasher> Default for SparseMerkleTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hasher> SparseMerkleTree<T> {
+    /// Creates an empty tree
+    pub fn new() -> Self {
+        let empty_hashes = hasher::empty_hashes::<T>(KEY_BITS);
+
+        Self {
+            layers: vec![BTreeMap::new(); KEY_BITS],
+            root: empty_hashes[KEY_BITS],
+            empty_hashes,
+        }
+    }
+
+    /// Returns the tree root. The root of an empty tree is the root of an empty subtree of
+    /// height 256.
+    pub fn root(&self) -> T::Hash {
+        self.root
+    }
+
+    /// Same as [`root`](SparseMerkleTree::root), but serialized as a hex string
+    pub fn root_hex(&self) -> String {
+        utils::collections::to_hex_string(&self.root)
+    }
+
+    /// Returns the roots of the empty subtrees of every height, from the empty leaf to the root
+    /// of an empty tree. They can be passed to [`SparseMerkleProof::verify_with_empty_hashes`]
+    /// to verify many proofs without calculating them again.
+    pub fn empty_hashes(&self) -> &[T::Hash] {
+        &self.empty_hashes
+    }
+
+    /// Returns the value of the key, or `None` if the key is absent
+    pub fn get(&self, key: &Key) -> Option<T::Hash> {
+        self.layers[0].get(key).copied()
+    }
+
+    /// Returns the number of keys in the tree
+    pub fn len(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    /// Returns `true` if the tree has no keys
+    pub fn is_empty(&self) -> bool {
+        self.layers[0].is_empty()
+    }
+
+    /// Sets the value of the key, inserting the key if it's absent. Returns the previous value.
+    pub fn insert(&mut self, key: Key, value: T::Hash) -> Option<T::Hash> {
+        let previous_value = self.get(&key);
+        self.set_leaf(&key, value);
+        previous_value
+    }
+
+    /// Sets the value of a key that is already present in the tree. Returns the previous value.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the key is absent
+    pub fn update(&mut self, key: Key, value: T::Hash) -> Result<T::Hash, Error> {
+        let previous_value = self.get(&key).ok_or_else(Error::key_not_found)?;
+        self.set_leaf(&key, value);
+        Ok(previous_value)
+    }
+
+    /// Removes the key from the tree. Returns the removed value, or `None` if the key was absent.
+    pub fn remove(&mut self, key: &Key) -> Option<T::Hash> {
+        let previous_value = self.get(key)?;
+        self.set_leaf(key, self.empty_hashes[0]);
+        Some(previous_value)
+    }
+
+    /// Returns the proof for the key. If the key is present in the tree, the proof shows its
+    /// value, otherwise it shows that the key is absent.
+    ///
+    /// For examples, please check [`SparseMerkleProof`]
+    pub fn proof(&self, key: &Key) -> SparseMerkleProof<T> {
+        let mut bitmap = [0u8; KEY_BITS / 8];
+        let mut proof_hashes = Vec::new();
+
+        for (layer, nodes) in self.layers.iter().enumerate() {
+            let index = utils::keys::shift_right(key, layer);
+            if let Some(sibling) = nodes.get(&utils::keys::sibling(&index)) {
+                bitmap[layer / 8] |= 1 << (layer % 8);
+                proof_hashes.push(*sibling);
+            }
+        }
+
+        SparseMerkleProof::new(bitmap, proof_hashes)
+    }
+
+    /// Sets the leaf and recalculates the path from it to the root, dropping the nodes that
+    /// became empty
+    fn set_leaf(&mut self, key: &Key, leaf: T::Hash) {
+        let mut node = leaf;
+
+        for (layer, nodes) in self.layers.iter_mut().enumerate() {
+            let empty_hash = &self.empty_hashes[layer];
+            let index = utils::keys::shift_right(key, layer);
+
+            if node == *empty_hash {
+                nodes.remove(&index);
+            } else {
+                nodes.insert(index, node);
+            }
+
+            let sibling = nodes
+                .get(&utils::keys::sibling(&index))
+                .unwrap_or(empty_hash);
+            node = if utils::keys::bit(key, layer) {
+                T::concat_and_hash(sibling, Some(&node))
+            } else {
+                T::concat_and_hash(&node, Some(sibling))
+            };
+        }
+
+        self.root = node;
+    }
+}
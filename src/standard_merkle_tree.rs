@@ -0,0 +1,339 @@
+use crate::{
+    algorithms::{Keccak256, SortedPair},
+    error::Error,
+    prelude::*,
+    utils, Hasher, MerkleProof, SimpleMerkleTree, SortedMultiProof,
+};
+
+/// The format identifier used by OpenZeppelin's `StandardMerkleTree.dump`
+#[cfg(feature = "json")]
+const DUMP_FORMAT: &str = "standard-v1";
+
+/// [`StandardMerkleTree`] is a Rust equivalent of OpenZeppelin's
+/// [`StandardMerkleTree`](https://github.com/OpenZeppelin/merkle-tree#standardmerkletree),
+/// commonly used for airdrops and allowlists.
+///
+/// Every value is a list of fields that is ABI encoded according to the leaf encoding, e.g.
+/// `["address", "uint256"]`, and then hashed twice with [`Keccak256`]: the leaf hash is
+/// `keccak256(keccak256(abi.encode(...)))`. Leaves are sorted by their hashes and put into a
+/// [`SimpleMerkleTree`], so the root and the proofs are the same as the ones produced by the
+/// JavaScript library, and can be verified on chain with OpenZeppelin's `MerkleProof.sol`.
+///
+/// With the `json` feature, trees can be dumped to and loaded from the JSON format used by
+/// `StandardMerkleTree.dump` and `StandardMerkleTree.load`.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::StandardMerkleTree;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let values = [
+///     ["0x1111111111111111111111111111111111111111", "5000000000000000000"],
+///     ["0x2222222222222222222222222222222222222222", "2500000000000000000"],
+/// ];
+///
+/// let merkle_tree = StandardMerkleTree::of(&values, &["address", "uint256"])?;
+/// assert_eq!(
+///     merkle_tree.root_hex(),
+///     Some("d4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77".to_string())
+/// );
+///
+/// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+/// let proof = merkle_tree.proof(1)?;
+/// assert!(proof.verify_leaf(root, merkle_tree.leaf_hash(&values[1])?));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Keccak256`]: crate::algorithms::Keccak256
+#[derive(Clone)]
+pub struct StandardMerkleTree {
+    tree: SimpleMerkleTree<Keccak256>,
+    leaf_encoding: Vec<String>,
+    values: Vec<Vec<String>>,
+    /// Position of the leaf of each value in the tree leaves
+    leaf_indices: Vec<usize>,
+}
+
+impl StandardMerkleTree {
+    /// Builds a tree from the values encoded with the given leaf encoding. Values are ABI
+    /// encoded with the supported elementary types: `address`, `bool`, `uint<N>`, `int<N>`,
+    /// `bytes<N>`, `bytes` and `string`. Integers can be decimal or `0x` prefixed hex strings,
+    /// addresses and bytes are `0x` prefixed hex strings.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if any value doesn't match the leaf encoding
+    pub fn of<V: AsRef<[S]>, S: AsRef<str>, E: AsRef<str>>(
+        values: &[V],
+        leaf_encoding: &[E],
+    ) -> Result<Self, Error> {
+        let leaf_encoding: Vec<String> = leaf_encoding
+            .iter()
+            .map(|abi_type| abi_type.as_ref().to_string())
+            .collect();
+        let values: Vec<Vec<String>> = values
+            .iter()
+            .map(|value| {
+                value
+                    .as_ref()
+                    .iter()
+                    .map(|field| field.as_ref().to_string())
+                    .collect()
+            })
+            .collect();
+
+        let mut hashed_values = values
+            .iter()
+            .enumerate()
+            .map(|(value_index, value)| Ok((hash_leaf(&leaf_encoding, value)?, value_index)))
+            .collect::<Result<Vec<([u8; 32], usize)>, Error>>()?;
+        hashed_values.sort();
+
+        let mut leaf_indices = vec![0; values.len()];
+        for (leaf_index, (_, value_index)) in hashed_values.iter().enumerate() {
+            leaf_indices[*value_index] = leaf_index;
+        }
+
+        let leaves: Vec<[u8; 32]> = hashed_values.iter().map(|(hash, _)| *hash).collect();
+
+        Ok(Self {
+            tree: SimpleMerkleTree::from_leaves(&leaves),
+            leaf_encoding,
+            values,
+            leaf_indices,
+        })
+    }
+
+    /// Returns the tree root, or `None` if the tree is empty
+    pub fn root(&self) -> Option<[u8; 32]> {
+        self.tree.root()
+    }
+
+    /// Same as [`root`](StandardMerkleTree::root), but serialized as a hex string
+    pub fn root_hex(&self) -> Option<String> {
+        self.tree.root_hex()
+    }
+
+    /// Returns the leaf encoding the tree was built with
+    pub fn leaf_encoding(&self) -> &[String] {
+        &self.leaf_encoding
+    }
+
+    /// Returns the values in the order they were passed to the tree
+    pub fn values(&self) -> &[Vec<String>] {
+        &self.values
+    }
+
+    /// Returns the leaf hash of the value, `keccak256(keccak256(abi.encode(value)))`
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the value doesn't match the leaf encoding of the tree
+    pub fn leaf_hash<S: AsRef<str>>(&self, value: &[S]) -> Result<[u8; 32], Error> {
+        hash_leaf(&self.leaf_encoding, value)
+    }
+
+    /// Returns the proof for the value with the given index, compatible with OpenZeppelin's
+    /// `MerkleProof.verify`. The proof can be verified with [`MerkleProof::verify_leaf`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the value index is out of bounds
+    pub fn proof(&self, value_index: usize) -> Result<MerkleProof<SortedPair<Keccak256>>, Error> {
+        self.tree.proof(self.leaf_index(value_index)?)
+    }
+
+    /// Returns the flag multiproof for the values with the given indices, compatible with
+    /// OpenZeppelin's `MerkleProof.multiProofVerify`, along with the value indices in the order
+    /// their leaf hashes have to be passed to the verifier.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if any of the value indices is out of bounds or duplicated
+    pub fn multi_proof(
+        &self,
+        value_indices: &[usize],
+    ) -> Result<(SortedMultiProof<Keccak256>, Vec<usize>), Error> {
+        let mut indices = value_indices
+            .iter()
+            .map(|value_index| Ok((self.leaf_index(*value_index)?, *value_index)))
+            .collect::<Result<Vec<(usize, usize)>, Error>>()?;
+        indices.sort_unstable();
+
+        let leaf_indices: Vec<usize> = indices.iter().map(|(leaf_index, _)| *leaf_index).collect();
+        let proof = self.tree.multi_proof(&leaf_indices)?;
+        let ordered_value_indices = indices
+            .iter()
+            .map(|(_, value_index)| *value_index)
+            .collect();
+
+        Ok((proof, ordered_value_indices))
+    }
+
+    /// Serializes the tree to the JSON format of OpenZeppelin's `StandardMerkleTree.dump`
+    #[cfg(feature = "json")]
+    pub fn dump(&self) -> String {
+        use serde_json::{json, Value};
+
+        let tree: Vec<Value> = self
+            .tree
+            .nodes()
+            .iter()
+            .map(|node| Value::from(to_prefixed_hex(node)))
+            .collect();
+        let values: Vec<Value> = self
+            .values
+            .iter()
+            .zip(&self.leaf_indices)
+            .map(|(value, leaf_index)| {
+                let fields: Vec<Value> = value
+                    .iter()
+                    .zip(&self.leaf_encoding)
+                    .map(
+                        |(field, abi_type)| match (abi_type.as_str(), field.as_str()) {
+                            ("bool", "true") => Value::Bool(true),
+                            ("bool", "false") => Value::Bool(false),
+                            _ => Value::from(field.as_str()),
+                        },
+                    )
+                    .collect();
+                json!({
+                    "value": fields,
+                    "treeIndex": self.tree.tree_index(*leaf_index).unwrap_or_default(),
+                })
+            })
+            .collect();
+
+        json!({
+            "format": DUMP_FORMAT,
+            "tree": tree,
+            "values": values,
+            "leafEncoding": self.leaf_encoding,
+        })
+        .to_string()
+    }
+
+    /// Loads the tree from the JSON format of OpenZeppelin's `StandardMerkleTree.dump`.
+    /// The tree is rebuilt from the values, so the dump is rejected if any of its nodes doesn't
+    /// match the values.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`Error`] if the JSON is malformed, has an unknown format or is inconsistent
+    #[cfg(feature = "json")]
+    pub fn load(json: &str) -> Result<Self, Error> {
+        use core::convert::TryFrom;
+        use serde_json::Value;
+
+        let invalid_dump = |message: &str| Error::invalid_tree_dump(message.to_string());
+
+        let dump: Value = serde_json::from_str(json)
+            .map_err(|error| Error::invalid_tree_dump(error.to_string()))?;
+        if dump["format"] != DUMP_FORMAT {
+            return Err(invalid_dump("unknown tree dump format"));
+        }
+
+        let leaf_encoding = dump["leafEncoding"]
+            .as_array()
+            .ok_or_else(|| invalid_dump("leaf encoding is missing"))?
+            .iter()
+            .map(|abi_type| abi_type.as_str().map(String::from))
+            .collect::<Option<Vec<String>>>()
+            .ok_or_else(|| invalid_dump("leaf encoding must be a list of types"))?;
+
+        let tree = dump["tree"]
+            .as_array()
+            .ok_or_else(|| invalid_dump("tree is missing"))?
+            .iter()
+            .map(|node| {
+                let node = utils::abi::parse_hex(node.as_str().unwrap_or_default()).ok()?;
+                <[u8; 32]>::try_from(node).ok()
+            })
+            .collect::<Option<Vec<[u8; 32]>>>()
+            .ok_or_else(|| invalid_dump("tree nodes must be 32 bytes hex strings"))?;
+
+        let mut values = Vec::new();
+        let mut tree_indices = Vec::new();
+        for value in dump["values"]
+            .as_array()
+            .ok_or_else(|| invalid_dump("values are missing"))?
+        {
+            let fields = value["value"]
+                .as_array()
+                .ok_or_else(|| invalid_dump("value must be a list"))?
+                .iter()
+                .map(|field| match field {
+                    Value::String(field) => Some(field.clone()),
+                    Value::Bool(_) | Value::Number(_) => Some(field.to_string()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<String>>>()
+                .ok_or_else(|| invalid_dump("value fields must be strings, numbers or bools"))?;
+            let tree_index = value["treeIndex"]
+                .as_u64()
+                .ok_or_else(|| invalid_dump("tree index is missing"))?;
+            let tree_index = usize::try_from(tree_index)
+                .map_err(|_| invalid_dump("tree index doesn't point to a leaf"))?;
+
+            values.push(fields);
+            tree_indices.push(tree_index);
+        }
+
+        if tree.len() != (2 * values.len()).saturating_sub(1) {
+            return Err(invalid_dump("tree size doesn't match the number of values"));
+        }
+
+        let mut leaves = vec![None; values.len()];
+        let mut leaf_indices = Vec::with_capacity(values.len());
+        for (value, tree_index) in values.iter().zip(&tree_indices) {
+            let leaf_index = tree_index
+                .checked_add(1)
+                .and_then(|offset| tree.len().checked_sub(offset))
+                .filter(|leaf_index| *leaf_index < values.len())
+                .ok_or_else(|| invalid_dump("tree index doesn't point to a leaf"))?;
+            if leaves[leaf_index].is_some() {
+                return Err(invalid_dump("tree indices contain duplicates"));
+            }
+            if hash_leaf(&leaf_encoding, value)? != tree[*tree_index] {
+                return Err(invalid_dump("leaf hash doesn't match the value"));
+            }
+
+            leaves[leaf_index] = Some(tree[*tree_index]);
+            leaf_indices.push(leaf_index);
+        }
+
+        let leaves: Vec<[u8; 32]> = leaves.into_iter().flatten().collect();
+        let merkle_tree = SimpleMerkleTree::from_leaves(&leaves);
+        if merkle_tree.nodes() != tree.as_slice() {
+            return Err(invalid_dump("tree nodes don't match the values"));
+        }
+
+        Ok(Self {
+            tree: merkle_tree,
+            leaf_encoding,
+            values,
+            leaf_indices,
+        })
+    }
+
+    fn leaf_index(&self, value_index: usize) -> Result<usize, Error> {
+        self.leaf_indices
+            .get(value_index)
+            .copied()
+            .ok_or_else(|| Error::leaf_index_out_of_bounds(self.values.len()))
+    }
+}
+
+fn hash_leaf<E: AsRef<str>, S: AsRef<str>>(
+    leaf_encoding: &[E],
+    value: &[S],
+) -> Result<[u8; 32], Error> {
+    let encoded = utils::abi::encode(leaf_encoding, value)?;
+    Ok(Keccak256::hash(&Keccak256::hash(&encoded)))
+}
+
+#[cfg(feature = "json")]
+fn to_prefixed_hex(node: &[u8; 32]) -> String {
+    format!("0x{}", utils::collections::to_hex_string(node))
+}
@@ -0,0 +1,226 @@
+//! Solidity ABI encoding of the elementary types, as done by `abi.encode`
+use crate::{error::Error, prelude::*};
+
+const WORD_SIZE: usize = 32;
+
+type Word = [u8; WORD_SIZE];
+
+/// Encodes the values as a tuple of the given types, the same way Solidity's `abi.encode` does.
+/// Supports `address`, `bool`, `uint<N>`, `int<N>`, `bytes<N>`, `bytes` and `string`.
+/// Integers can be passed as decimal or `0x` prefixed hex strings, bytes and addresses as hex.
+pub fn encode<T: AsRef<str>, V: AsRef<str>>(types: &[T], values: &[V]) -> Result<Vec<u8>, Error> {
+    if types.len() != values.len() {
+        return Err(Error::invalid_leaf_value(format!(
+            "expected {} values, got {}",
+            types.len(),
+            values.len()
+        )));
+    }
+
+    let mut head = Vec::with_capacity(types.len() * WORD_SIZE);
+    let mut tail = Vec::new();
+
+    for (abi_type, value) in types.iter().zip(values) {
+        let (abi_type, value) = (abi_type.as_ref(), value.as_ref());
+        match abi_type {
+            "bytes" => {
+                append_offset(&mut head, types.len(), tail.len());
+                append_dynamic(&mut tail, &parse_hex(value)?);
+            }
+            "string" => {
+                append_offset(&mut head, types.len(), tail.len());
+                append_dynamic(&mut tail, value.as_bytes());
+            }
+            _ => head.extend_from_slice(&encode_static(abi_type, value)?),
+        }
+    }
+
+    head.append(&mut tail);
+    Ok(head)
+}
+
+fn encode_static(abi_type: &str, value: &str) -> Result<Word, Error> {
+    if abi_type == "address" {
+        let address = parse_hex(value)?;
+        if address.len() != 20 {
+            return Err(invalid_value(abi_type, value));
+        }
+        let mut word = [0u8; WORD_SIZE];
+        word[WORD_SIZE - 20..].copy_from_slice(&address);
+        return Ok(word);
+    }
+
+    if abi_type == "bool" {
+        let mut word = [0u8; WORD_SIZE];
+        word[WORD_SIZE - 1] = match value {
+            "true" => 1,
+            "false" => 0,
+            _ => return Err(invalid_value(abi_type, value)),
+        };
+        return Ok(word);
+    }
+
+    if let Some(bits) = abi_type.strip_prefix("uint") {
+        let bits = parse_size(bits, 256, 8).ok_or_else(|| unsupported_type(abi_type))?;
+        let word = parse_uint(value).ok_or_else(|| invalid_value(abi_type, value))?;
+        if !fits(&word, bits) {
+            return Err(invalid_value(abi_type, value));
+        }
+        return Ok(word);
+    }
+
+    if let Some(bits) = abi_type.strip_prefix("int") {
+        let bits = parse_size(bits, 256, 8).ok_or_else(|| unsupported_type(abi_type))?;
+        let (negative, magnitude) = match value.strip_prefix('-') {
+            Some(magnitude) => (true, magnitude),
+            None => (false, value),
+        };
+        let word = parse_uint(magnitude).ok_or_else(|| invalid_value(abi_type, value))?;
+        if word == [0u8; WORD_SIZE] {
+            return Ok(word);
+        }
+        // The magnitude of a negative number can be one more than of a positive one
+        let limit = if negative { subtract_one(&word) } else { word };
+        if !fits(&limit, bits - 1) {
+            return Err(invalid_value(abi_type, value));
+        }
+        return Ok(if negative { negate(&word) } else { word });
+    }
+
+    if let Some(size) = abi_type.strip_prefix("bytes") {
+        let size = parse_size(size, 32, 1).ok_or_else(|| unsupported_type(abi_type))?;
+        let bytes = parse_hex(value)?;
+        if bytes.len() != size {
+            return Err(invalid_value(abi_type, value));
+        }
+        let mut word = [0u8; WORD_SIZE];
+        word[..size].copy_from_slice(&bytes);
+        return Ok(word);
+    }
+
+    Err(unsupported_type(abi_type))
+}
+
+fn append_offset(head: &mut Vec<u8>, params_count: usize, tail_len: usize) {
+    head.extend_from_slice(&usize_to_word(params_count * WORD_SIZE + tail_len));
+}
+
+fn append_dynamic(tail: &mut Vec<u8>, data: &[u8]) {
+    tail.extend_from_slice(&usize_to_word(data.len()));
+    tail.extend_from_slice(data);
+    let padding = (WORD_SIZE - data.len() % WORD_SIZE) % WORD_SIZE;
+    tail.resize(tail.len() + padding, 0);
+}
+
+fn usize_to_word(value: usize) -> Word {
+    let mut word = [0u8; WORD_SIZE];
+    let bytes = (value as u64).to_be_bytes();
+    word[WORD_SIZE - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+/// Parses the size suffix of types like `uint64` or `bytes4`. An empty suffix means `max`.
+fn parse_size(size: &str, max: usize, step: usize) -> Option<usize> {
+    if size.is_empty() {
+        return Some(max);
+    }
+    if size.starts_with('0') {
+        return None;
+    }
+
+    let size: usize = size.parse().ok()?;
+    if size == 0 || size > max || size % step != 0 {
+        return None;
+    }
+    Some(size)
+}
+
+/// Parses a decimal or `0x` prefixed hex string into a big endian 256 bit word
+fn parse_uint(value: &str) -> Option<Word> {
+    let (digits, radix) = match value.strip_prefix("0x") {
+        Some(hex) => (hex, 16),
+        None => (value, 10),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut word = [0u8; WORD_SIZE];
+    for digit in digits.chars() {
+        let mut carry = digit.to_digit(radix)?;
+        for byte in word.iter_mut().rev() {
+            let product = u32::from(*byte) * radix + carry;
+            *byte = product as u8;
+            carry = product >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(word)
+}
+
+/// Checks that the word doesn't have any bits set above the lowest `bits` bits
+fn fits(word: &Word, bits: usize) -> bool {
+    let full_bytes = bits / 8;
+    let high_bytes = WORD_SIZE - full_bytes;
+    let partial_bits = bits % 8;
+
+    word.iter().take(high_bytes).enumerate().all(|(i, byte)| {
+        if i + 1 == high_bytes && partial_bits > 0 {
+            *byte >> partial_bits == 0
+        } else {
+            *byte == 0
+        }
+    })
+}
+
+fn subtract_one(word: &Word) -> Word {
+    let mut result = *word;
+    for byte in result.iter_mut().rev() {
+        let (value, borrow) = byte.overflowing_sub(1);
+        *byte = value;
+        if !borrow {
+            break;
+        }
+    }
+    result
+}
+
+/// Returns the two's complement of the word
+fn negate(word: &Word) -> Word {
+    let mut result = [0u8; WORD_SIZE];
+    let mut carry = true;
+    for (result_byte, byte) in result.iter_mut().zip(word.iter()).rev() {
+        let (value, overflow) = (!byte).overflowing_add(u8::from(carry));
+        *result_byte = value;
+        carry = overflow;
+    }
+    result
+}
+
+/// Parses a `0x` prefixed hex string into bytes
+pub fn parse_hex(value: &str) -> Result<Vec<u8>, Error> {
+    let invalid_hex = || Error::invalid_leaf_value(format!("{} is not a 0x prefixed hex", value));
+    let hex = value.strip_prefix("0x").ok_or_else(invalid_hex)?;
+    if hex.len() % 2 != 0 {
+        return Err(invalid_hex());
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(invalid_hex)
+        })
+        .collect()
+}
+
+fn invalid_value(abi_type: &str, value: &str) -> Error {
+    Error::invalid_leaf_value(format!("{} is not a valid {}", value, abi_type))
+}
+
+fn unsupported_type(abi_type: &str) -> Error {
+    Error::invalid_leaf_value(format!("{} type is not supported", abi_type))
+}
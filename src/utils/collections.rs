@@ -11,6 +11,18 @@ pub fn to_hex_string<T: Clone + Into<Vec<u8>>>(bytes: &T) -> String {
     hex_vec.join("")
 }
 
+/// Parses a hex string produced by [`to_hex_string`] back into bytes
+pub fn from_hex_string(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
 /// Find a difference between two vectors and return a third vector
 /// containing the difference. This function preserves the first
 /// vector order.
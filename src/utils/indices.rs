@@ -1,4 +1,4 @@
-use crate::{prelude::*, utils};
+use crate::prelude::*;
 use alloc::collections::BTreeMap;
 
 pub fn is_left_index(index: usize) -> bool {
@@ -31,10 +31,49 @@ pub fn parent_indices(indices: &[usize]) -> Vec<usize> {
     parents
 }
 
+/// Returns the siblings of the nodes with the given sorted and unique indices on a layer of
+/// `layer_len` nodes that are needed to calculate the parents of the nodes, i.e. the siblings
+/// that aren't among the nodes and are in bounds of the layer. Takes linear time, since
+/// the sibling of a node can only be the previous or the next node in the sorted indices.
+pub fn helper_indices(sorted_indices: &[usize], layer_len: usize) -> Vec<usize> {
+    let mut helper_indices = Vec::with_capacity(sorted_indices.len());
+    let mut position = 0;
+
+    while let Some(index) = sorted_indices.get(position) {
+        let sibling = get_sibling_index(*index);
+        if is_left_index(*index) && sorted_indices.get(position + 1) == Some(&sibling) {
+            position += 2;
+            continue;
+        }
+        if sibling < layer_len {
+            helper_indices.push(sibling);
+        }
+        position += 1;
+    }
+
+    helper_indices
+}
+
 pub fn tree_depth(leaves_count: usize) -> usize {
     8 * core::mem::size_of::<usize>() - leaves_count.leading_zeros() as usize
 }
 
+/// Returns the number of nodes on every layer of a tree with `leaves_count` leaves, from the
+/// leaves to the root
+pub fn layer_lens(leaves_count: usize) -> Vec<usize> {
+    if leaves_count == 0 {
+        return Vec::new();
+    }
+
+    let mut layer_lens = vec![leaves_count];
+    for _ in 0..tree_depth(leaves_count) {
+        let layer_len = layer_lens[layer_lens.len() - 1];
+        layer_lens.push(div_ceil(layer_len, 2));
+    }
+
+    layer_lens
+}
+
 pub fn uneven_layers(tree_leaves_count: usize) -> BTreeMap<usize, usize> {
     let mut leaves_count = tree_leaves_count;
     let depth = tree_depth(tree_leaves_count);
@@ -52,36 +91,25 @@ pub fn uneven_layers(tree_leaves_count: usize) -> BTreeMap<usize, usize> {
     uneven_layers
 }
 
-/// Returns layered proof indices
+/// Returns layered proof indices. The last node of an odd layer never needs a proof node,
+/// so the indices are the same for every [`OddNodePolicy`](crate::OddNodePolicy)
 pub fn proof_indices_by_layers(
     sorted_leaf_indices: &[usize],
     leaves_count: usize,
 ) -> Vec<Vec<usize>> {
     let depth = tree_depth(leaves_count);
-    let uneven_layers = uneven_layers(leaves_count);
 
     let mut layer_nodes = sorted_leaf_indices.to_vec();
+    let mut layer_len = leaves_count;
     let mut proof_indices: Vec<Vec<usize>> = Vec::new();
 
-    for layer_index in 0..depth {
-        let mut sibling_indices = sibling_indices(&layer_nodes);
-        // The last node of that layer doesn't have another hash to the right, so no need to include
-        // that index
-        if let Some(leaves_count) = uneven_layers.get(&layer_index) {
-            if let Some(layer_last_node_index) = layer_nodes.last() {
-                if *layer_last_node_index == leaves_count - 1 {
-                    sibling_indices.pop();
-                }
-            }
-        }
-
-        // Figuring out indices that are already siblings and do not require additional hash
-        // to calculate the parent
-        let proof_nodes_indices = utils::collections::difference(&sibling_indices, &layer_nodes);
-
-        proof_indices.push(proof_nodes_indices);
+    for _ in 0..depth {
+        // The last node of an odd layer doesn't have another hash to the right, and the nodes
+        // that are siblings of each other don't require an additional hash
+        proof_indices.push(helper_indices(&layer_nodes, layer_len));
         // Passing parent nodes indices to the next iteration cycle
         layer_nodes = parent_indices(&layer_nodes);
+        layer_len = div_ceil(layer_len, 2);
     }
 
     proof_indices
@@ -0,0 +1,36 @@
+//! Bit manipulations of the 256-bit keys of the sparse Merkle tree. Keys are big endian
+//! integers, so bit 0 is the lowest bit of the last byte.
+pub type Key = [u8; 32];
+
+/// Number of bits in a key, which is also the depth of the sparse Merkle tree
+pub const KEY_BITS: usize = 256;
+
+/// Returns the bit of the key with the given index
+pub fn bit(key: &Key, index: usize) -> bool {
+    key[key.len() - 1 - index / 8] >> (index % 8) & 1 == 1
+}
+
+/// Returns the key shifted right by the given number of bits, i.e. the index of the
+/// ancestor node of the key on that layer
+pub fn shift_right(key: &Key, bits: usize) -> Key {
+    let mut shifted = [0u8; 32];
+    let bytes = bits / 8;
+    let remainder = bits % 8;
+
+    for i in (bytes..key.len()).rev() {
+        let mut byte = key[i - bytes] >> remainder;
+        if remainder > 0 && i > bytes {
+            byte |= key[i - bytes - 1] << (8 - remainder);
+        }
+        shifted[i] = byte;
+    }
+
+    shifted
+}
+
+/// Returns the index of the sibling of the node
+pub fn sibling(index: &Key) -> Key {
+    let mut sibling = *index;
+    sibling[sibling.len() - 1] ^= 1;
+    sibling
+}
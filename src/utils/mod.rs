@@ -1,3 +1,5 @@
 //! Utilities used internally to manipulate tree indices
+pub mod abi;
 pub mod collections;
 pub mod indices;
+pub mod keys;
@@ -0,0 +1,147 @@
+#[cfg(any(feature = "parity-scale-codec", feature = "borsh"))]
+use rs_merkle::{algorithms::Sha256, Hasher, MerkleProof, MerkleTree};
+
+#[cfg(any(feature = "parity-scale-codec", feature = "borsh"))]
+fn proof_of_the_first_leaf() -> (MerkleProof<Sha256>, [u8; 32], [u8; 32]) {
+    let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|x| Sha256::hash(x.as_bytes()))
+        .collect();
+    let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+    (
+        merkle_tree.proof(&[0]),
+        merkle_tree.root().unwrap(),
+        leaves[0],
+    )
+}
+
+#[cfg(feature = "parity-scale-codec")]
+pub mod scale_codec {
+    use crate::proof_of_the_first_leaf;
+    use parity_scale_codec::{Compact, Decode, Encode};
+    use rs_merkle::{algorithms::Sha256, MerkleProof};
+    use scale_info::{MetaType, Registry, TypeDefComposite};
+
+    #[test]
+    pub fn should_encode_the_proof_as_hashes_and_metadata() {
+        let (proof, root, leaf) = proof_of_the_first_leaf();
+
+        let bytes = proof.encode();
+        assert_eq!(
+            bytes,
+            (proof.proof_hashes().to_vec(), Some((vec![0u64], 5u64))).encode()
+        );
+        assert!(bytes.len() <= proof.size_hint());
+
+        let decoded_proof = MerkleProof::<Sha256>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(decoded_proof.leaf_indices(), Some(&[0][..]));
+        assert_eq!(decoded_proof.total_leaves_count(), Some(5));
+        assert!(decoded_proof.verify_with_metadata(root, &[leaf]));
+    }
+
+    #[test]
+    pub fn should_encode_proofs_without_metadata() {
+        let (proof, root, leaf) = proof_of_the_first_leaf();
+        let proof = MerkleProof::<Sha256>::new(proof.proof_hashes().to_vec());
+
+        let bytes = proof.encode();
+        assert_eq!(
+            bytes,
+            (proof.proof_hashes().to_vec(), None::<(Vec<u64>, u64)>).encode()
+        );
+
+        let decoded_proof = MerkleProof::<Sha256>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(decoded_proof.leaf_indices(), None);
+        assert!(decoded_proof.verify(root, &[0], &[leaf], 5));
+    }
+
+    #[test]
+    pub fn should_not_decode_truncated_proofs() {
+        let (proof, _, _) = proof_of_the_first_leaf();
+        let bytes = proof.encode();
+
+        assert!(MerkleProof::<Sha256>::decode(&mut &bytes[..bytes.len() - 1]).is_err());
+
+        // A number of hashes above u32::MAX is read as is rather than wrapped
+        let bytes = Compact(u64::from(u32::MAX) + 2).encode();
+        assert!(MerkleProof::<Sha256>::decode(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    pub fn should_describe_the_proof_type() {
+        let mut registry = Registry::new();
+        registry.register_type(&MetaType::new::<MerkleProof<Sha256>>());
+        let registry: scale_info::PortableRegistry = registry.into();
+
+        let proof_type = registry
+            .types
+            .iter()
+            .find(|ty| {
+                ty.ty.path.ident().map(|ident| ident.to_string()) == Some("MerkleProof".into())
+            })
+            .unwrap();
+        match &proof_type.ty.type_def {
+            scale_info::TypeDef::Composite(TypeDefComposite { fields }) => {
+                let names: Vec<Option<String>> = fields
+                    .iter()
+                    .map(|field| field.name.as_ref().map(|name| name.to_string()))
+                    .collect();
+                assert_eq!(
+                    names,
+                    vec![Some("proof_hashes".into()), Some("metadata".into())]
+                );
+            }
+            _ => panic!("proof should be a composite type"),
+        }
+    }
+}
+
+#[cfg(feature = "borsh")]
+pub mod borsh {
+    use crate::proof_of_the_first_leaf;
+    use rs_merkle::{algorithms::Sha256, MerkleProof};
+
+    #[test]
+    pub fn should_serialize_the_proof_as_hashes_and_metadata() {
+        let (proof, root, leaf) = proof_of_the_first_leaf();
+
+        let bytes = borsh::to_vec(&proof).unwrap();
+        assert_eq!(
+            bytes,
+            borsh::to_vec(&(proof.proof_hashes().to_vec(), Some((vec![0u64], 5u64)))).unwrap()
+        );
+
+        let parsed_proof = borsh::from_slice::<MerkleProof<Sha256>>(&bytes).unwrap();
+        assert_eq!(parsed_proof.leaf_indices(), Some(&[0][..]));
+        assert_eq!(parsed_proof.total_leaves_count(), Some(5));
+        assert!(parsed_proof.verify_with_metadata(root, &[leaf]));
+    }
+
+    #[test]
+    pub fn should_serialize_proofs_without_metadata() {
+        let (proof, root, leaf) = proof_of_the_first_leaf();
+        let proof = MerkleProof::<Sha256>::new(proof.proof_hashes().to_vec());
+
+        let bytes = borsh::to_vec(&proof).unwrap();
+        assert_eq!(
+            bytes,
+            borsh::to_vec(&(proof.proof_hashes().to_vec(), None::<(Vec<u64>, u64)>)).unwrap()
+        );
+
+        let parsed_proof = borsh::from_slice::<MerkleProof<Sha256>>(&bytes).unwrap();
+        assert_eq!(parsed_proof.leaf_indices(), None);
+        assert!(parsed_proof.verify(root, &[0], &[leaf], 5));
+    }
+
+    #[test]
+    pub fn should_not_deserialize_truncated_proofs() {
+        let (proof, _, _) = proof_of_the_first_leaf();
+        let bytes = borsh::to_vec(&proof).unwrap();
+
+        assert!(borsh::from_slice::<MerkleProof<Sha256>>(&bytes[..bytes.len() - 1]).is_err());
+        assert!(
+            borsh::from_slice::<MerkleProof<Sha256>>(&[bytes.as_slice(), &[0]].concat()).is_err()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use rayon::prelude::*;
 #[cfg(feature = "keccak256")]
 use rs_merkle::algorithms::Keccak256;
@@ -25,7 +27,7 @@ pub struct TestDataKeccak256 {
 }
 
 fn combine<T: Clone>(active: Vec<T>, rest: Vec<T>, mut combinations: Vec<Vec<T>>) -> Vec<Vec<T>> {
-    return if rest.is_empty() {
+    if rest.is_empty() {
         if active.is_empty() {
             combinations
         } else {
@@ -42,7 +44,7 @@ fn combine<T: Clone>(active: Vec<T>, rest: Vec<T>, mut combinations: Vec<Vec<T>>
         combinations = combine(next, rest.clone().drain(1..).collect(), combinations);
         combinations = combine(active, rest.clone().drain(1..).collect(), combinations);
         combinations
-    };
+    }
 }
 
 /// Create all possible combinations of elements inside a vector without duplicates
@@ -0,0 +1,205 @@
+pub mod extract_matches {
+    use rs_merkle::{
+        algorithms::{Bitcoin, Sha256},
+        CompactMultiProof, ErrorKind, Hasher, MerkleTree,
+    };
+
+    fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| Sha256::hash(i.to_string().as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    pub fn should_extract_root_and_indices_for_all_subsets() {
+        for leaves_count in 1..=7 {
+            let leaves = leaf_hashes(leaves_count);
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+            let root = merkle_tree.root().unwrap();
+
+            for subset in 0..(1usize << leaves_count) {
+                let indices: Vec<usize> = (0..leaves_count)
+                    .filter(|index| subset & (1 << index) != 0)
+                    .collect();
+                let proven_leaves: Vec<[u8; 32]> =
+                    indices.iter().map(|index| leaves[*index]).collect();
+
+                let proof = merkle_tree.compact_multi_proof(&indices).unwrap();
+                let proof = CompactMultiProof::<Sha256>::from_bytes(&proof.to_bytes()).unwrap();
+
+                assert_eq!(
+                    proof.extract_matches(&proven_leaves).unwrap(),
+                    (root, indices.clone()),
+                    "proof for the leaves {:?} of {} should be valid",
+                    indices,
+                    leaves_count
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_follow_odd_node_policy() {
+        let leaves = leaf_hashes(11);
+        let merkle_tree = MerkleTree::<Bitcoin>::from_leaves(&leaves);
+        let root = merkle_tree.root().unwrap();
+
+        let proof = merkle_tree.compact_multi_proof(&[2, 10]).unwrap();
+
+        assert!(proof.verify(root, &[2, 10], &[leaves[2], leaves[10]]));
+    }
+
+    #[test]
+    pub fn should_not_verify_wrong_leaves() {
+        let leaves = leaf_hashes(9);
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let root = merkle_tree.root().unwrap();
+        let proof = merkle_tree.compact_multi_proof(&[3, 8]).unwrap();
+
+        assert!(proof.verify(root, &[3, 8], &[leaves[3], leaves[8]]));
+        assert!(!proof.verify(root, &[3, 8], &[leaves[8], leaves[3]]));
+        assert!(!proof.verify(root, &[3, 7], &[leaves[3], leaves[8]]));
+        assert_eq!(
+            proof
+                .extract_matches(&[leaves[3]])
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::NotEnoughHashesToCalculateRoot)
+        );
+        assert_eq!(
+            proof
+                .extract_matches(&[leaves[3], leaves[8], leaves[0]])
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::InvalidProofFlags)
+        );
+    }
+
+    #[test]
+    pub fn should_reject_invalid_indices() {
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaf_hashes(5));
+
+        assert_eq!(
+            merkle_tree
+                .compact_multi_proof(&[1, 5])
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::LeafIndexOutOfBounds)
+        );
+        assert_eq!(
+            merkle_tree
+                .compact_multi_proof(&[1, 1])
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::DuplicateLeafIndex)
+        );
+    }
+}
+
+pub mod to_bytes {
+    use rs_merkle::{
+        algorithms::Sha256,
+        proof_serializers::{DirectHashesOrder, SelfDescribing},
+        utils, CompactMultiProof, ErrorKind, Hasher, MerkleTree,
+    };
+
+    #[test]
+    pub fn should_add_only_the_leaves_count_and_the_flags_to_the_proof_hashes() {
+        let leaves: Vec<[u8; 32]> = (0..1024)
+            .map(|i: usize| Sha256::hash(i.to_string().as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let indices: Vec<usize> = (0..1024).step_by(3).collect();
+
+        let compact_proof = merkle_tree.compact_multi_proof(&indices).unwrap();
+        let proof = merkle_tree.proof(&indices).serialize::<DirectHashesOrder>();
+
+        assert_eq!(
+            compact_proof.to_bytes().len(),
+            proof.len() + 2 + utils::indices::div_ceil(compact_proof.proof_flags().len(), 8)
+        );
+    }
+
+    #[test]
+    pub fn should_derive_the_counts_from_the_tree_shape() {
+        let leaves: Vec<[u8; 32]> = (0..5)
+            .map(|i: usize| Sha256::hash(i.to_string().as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+        // 5 leaves, the flags 1110100 and the hashes of the leaf 0, of the leaves 2 and 3,
+        // and of the leaf 4
+        let compact_proof = merkle_tree.compact_multi_proof(&[1]).unwrap();
+        let bytes = compact_proof.to_bytes();
+
+        assert_eq!(bytes[0], 5);
+        assert_eq!(bytes.len(), 1 + 1 + 3 * 32);
+        assert_eq!(
+            CompactMultiProof::<Sha256>::from_bytes(&bytes)
+                .unwrap()
+                .proof_flags(),
+            compact_proof.proof_flags()
+        );
+
+        let empty_tree = MerkleTree::<Sha256>::new();
+        let empty_proof = empty_tree.compact_multi_proof(&[]).unwrap().to_bytes();
+        assert_eq!(empty_proof, vec![0]);
+        assert!(CompactMultiProof::<Sha256>::from_bytes(&empty_proof)
+            .unwrap()
+            .proof_flags()
+            .is_empty());
+    }
+
+    #[test]
+    pub fn should_be_smaller_than_self_describing_proof_for_large_batches() {
+        let leaves: Vec<[u8; 32]> = (0..1024)
+            .map(|i: usize| Sha256::hash(i.to_string().as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+        for indices in [
+            (0..1024).step_by(3).collect::<Vec<usize>>(),
+            (100..900).collect::<Vec<usize>>(),
+        ] {
+            let compact_proof = merkle_tree
+                .compact_multi_proof(&indices)
+                .unwrap()
+                .to_bytes();
+            let proof = merkle_tree.proof(&indices).serialize::<SelfDescribing>();
+
+            assert!(compact_proof.len() < proof.len() * 9 / 10);
+        }
+    }
+
+    #[test]
+    pub fn should_reject_malformed_input() {
+        let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|x| Sha256::hash(x.as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let mut bytes = merkle_tree.compact_multi_proof(&[1]).unwrap().to_bytes();
+
+        let missing_leaves_count = CompactMultiProof::<Sha256>::from_bytes(&[]);
+        let truncated_leaves_count = CompactMultiProof::<Sha256>::from_bytes(&[0x85]);
+        let overflowing_leaves_count = CompactMultiProof::<Sha256>::from_bytes(&[0xff; 11]);
+        let truncated_flags = CompactMultiProof::<Sha256>::from_bytes(&bytes[..1]);
+        let truncated_hashes = CompactMultiProof::<Sha256>::from_bytes(&bytes[..bytes.len() - 1]);
+        bytes.push(0);
+        let trailing_byte = CompactMultiProof::<Sha256>::from_bytes(&bytes);
+
+        for result in [
+            missing_leaves_count,
+            truncated_leaves_count,
+            overflowing_leaves_count,
+            truncated_flags,
+            truncated_hashes,
+            trailing_byte,
+        ] {
+            assert_eq!(
+                result.err().map(|error| error.kind()),
+                Some(ErrorKind::SerializedProofSizeIsIncorrect)
+            );
+        }
+    }
+}
@@ -0,0 +1,110 @@
+pub mod from_leaves {
+    use rs_merkle::{
+        algorithms::{Bitcoin, Sha256},
+        CompleteTree, Hasher, MerkleTree,
+    };
+
+    fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| Sha256::hash(i.to_string().as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    pub fn should_build_the_same_tree_as_merkle_tree() {
+        for leaves_count in 0..=33 {
+            let leaves = leaf_hashes(leaves_count);
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+            let tree = CompleteTree::<Sha256>::from_leaves(&leaves);
+
+            assert_eq!(tree.root().copied(), merkle_tree.root());
+            assert_eq!(tree.leaves_len(), leaves_count);
+            assert_eq!(tree.layers(), merkle_tree.store().layers());
+            assert_eq!(
+                CompleteTree::<Bitcoin>::from_leaves(&leaves)
+                    .root()
+                    .copied(),
+                MerkleTree::<Bitcoin>::from_leaves(&leaves).root()
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_look_up_nodes_by_position() {
+        let leaves = leaf_hashes(5);
+        let tree = CompleteTree::<Sha256>::from_leaves(&leaves);
+
+        assert_eq!(tree.layers_len(), 4);
+        assert_eq!(tree.layer_len(1), 3);
+        assert_eq!(tree.get(0, 4), Some(&leaves[4]));
+        assert_eq!(tree.get(1, 2), Some(&leaves[4]));
+        assert_eq!(
+            tree.get(1, 0),
+            Some(&Sha256::concat_and_hash(&leaves[0], Some(&leaves[1])))
+        );
+        assert_eq!(tree.get(0, 5), None);
+        assert_eq!(tree.get(4, 0), None);
+    }
+}
+
+pub mod proof {
+    use rs_merkle::{algorithms::Sha256, CompleteTree, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_return_the_same_proof_as_merkle_tree() {
+        let leaves: Vec<[u8; 32]> = (0..37)
+            .map(|i: usize| Sha256::hash(i.to_string().as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let tree = CompleteTree::<Sha256>::from_leaves(&leaves);
+        let root = *tree.root().unwrap();
+
+        for indices in [
+            vec![0],
+            vec![36],
+            vec![35, 2, 17],
+            (0..37).step_by(4).collect(),
+        ] {
+            let proven_leaves: Vec<[u8; 32]> = indices.iter().map(|index| leaves[*index]).collect();
+            let proof = tree.proof(&indices);
+
+            assert_eq!(
+                proof.proof_hashes(),
+                merkle_tree.proof(&indices).proof_hashes()
+            );
+            assert!(proof.verify(root, &indices, &proven_leaves, leaves.len()));
+        }
+    }
+}
+
+pub mod memory_store {
+    use rs_merkle::{algorithms::Sha256, CompleteTree, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_keep_the_layout_consistent_when_growing_and_shrinking() {
+        let leaves: Vec<[u8; 32]> = (0..70)
+            .map(|i: usize| Sha256::hash(i.to_string().as_bytes()))
+            .collect();
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            merkle_tree.insert(*leaf).commit();
+            let expected_tree = CompleteTree::<Sha256>::from_leaves(&leaves[..=index]);
+
+            assert_eq!(merkle_tree.store().layers(), expected_tree.layers());
+        }
+
+        for leaves_count in (0..70).rev() {
+            merkle_tree.rollback();
+            let expected_tree = CompleteTree::<Sha256>::from_leaves(&leaves[..leaves_count]);
+
+            assert_eq!(merkle_tree.store().layers(), expected_tree.layers());
+        }
+
+        merkle_tree.append(leaves.clone().as_mut()).commit();
+        assert_eq!(
+            merkle_tree.store().tree().root().copied(),
+            merkle_tree.root()
+        );
+    }
+}
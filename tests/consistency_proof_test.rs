@@ -0,0 +1,194 @@
+pub mod verify {
+    use rs_merkle::{algorithms::Sha256, ConsistencyProof, Hasher, MerkleTree};
+
+    fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| Sha256::hash(i.to_string().as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    pub fn should_verify_all_tree_size_pairs() {
+        let leaves = leaf_hashes(17);
+        let new_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+        for new_size in 1..=leaves.len() {
+            let new_root = MerkleTree::<Sha256>::from_leaves(&leaves[..new_size])
+                .root()
+                .unwrap();
+
+            for old_size in 1..=new_size {
+                let old_root = MerkleTree::<Sha256>::from_leaves(&leaves[..old_size])
+                    .root()
+                    .unwrap();
+                let proof = new_tree.consistency_proof(old_size, new_size).unwrap();
+
+                assert!(
+                    proof.verify(old_root, new_root, old_size, new_size),
+                    "proof for sizes {} and {} should be valid",
+                    old_size,
+                    new_size
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_match_the_proof_length_from_the_rfc() {
+        let leaves = leaf_hashes(7);
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+        // Examples from RFC 6962, section 2.1.3
+        let expected = [(3, 4), (4, 1), (6, 3)];
+        for (old_size, proof_len) in expected {
+            let proof = merkle_tree.consistency_proof(old_size, 7).unwrap();
+            assert_eq!(proof.proof_hashes().len(), proof_len);
+        }
+    }
+
+    #[test]
+    pub fn should_not_verify_a_tampered_proof() {
+        let leaves = leaf_hashes(11);
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let old_root = MerkleTree::<Sha256>::from_leaves(&leaves[..5])
+            .root()
+            .unwrap();
+        let new_root = merkle_tree.root().unwrap();
+
+        let proof = merkle_tree.consistency_proof(5, 11).unwrap();
+        assert!(proof.verify(old_root, new_root, 5, 11));
+
+        for i in 0..proof.proof_hashes().len() {
+            let mut proof_hashes = proof.proof_hashes().to_vec();
+            proof_hashes[i][0] ^= 1;
+            let tampered_proof = ConsistencyProof::<Sha256>::new(proof_hashes);
+            assert!(!tampered_proof.verify(old_root, new_root, 5, 11));
+        }
+
+        assert!(!proof.verify(new_root, new_root, 5, 11));
+        assert!(!proof.verify(old_root, old_root, 5, 11));
+        assert!(!proof.verify(old_root, new_root, 4, 11));
+        assert!(!ConsistencyProof::<Sha256>::new(vec![]).verify(old_root, new_root, 5, 11));
+    }
+
+    #[test]
+    pub fn should_verify_equal_sizes_only_with_an_empty_proof() {
+        let leaves = leaf_hashes(5);
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let root = merkle_tree.root().unwrap();
+
+        let proof = merkle_tree.consistency_proof(5, 5).unwrap();
+        assert!(proof.proof_hashes().is_empty());
+        assert!(proof.verify(root, root, 5, 5));
+        assert!(!proof.verify(root, leaves[0], 5, 5));
+    }
+}
+
+pub mod consistency_proof {
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_prove_consistency_between_commits() {
+        let leaves: Vec<[u8; 32]> = (0..10)
+            .map(|i: usize| Sha256::hash(i.to_string().as_bytes()))
+            .collect();
+
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+        let first_commit = merkle_tree
+            .append(&mut leaves[..3].to_vec())
+            .commit()
+            .unwrap();
+        let second_commit = merkle_tree
+            .append(&mut leaves[3..6].to_vec())
+            .commit()
+            .unwrap();
+        merkle_tree.append(&mut leaves[6..].to_vec()).commit();
+
+        let proof = merkle_tree.consistency_proof(3, 6).unwrap();
+        assert!(proof.verify(
+            first_commit.root().unwrap(),
+            second_commit.root().unwrap(),
+            3,
+            6
+        ));
+
+        let proof = merkle_tree.consistency_proof(3, 10).unwrap();
+        assert!(proof.verify(
+            first_commit.root().unwrap(),
+            merkle_tree.root().unwrap(),
+            3,
+            10
+        ));
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_invalid_tree_sizes() {
+        let leaves: Vec<[u8; 32]> = (0..4)
+            .map(|i: usize| Sha256::hash(i.to_string().as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+        for (old_size, new_size) in [(0, 2), (3, 2), (2, 5)] {
+            let kind = merkle_tree
+                .consistency_proof(old_size, new_size)
+                .err()
+                .map(|error| error.kind());
+            assert_eq!(kind, Some(ErrorKind::InvalidTreeSize));
+        }
+    }
+}
+
+pub mod serialization {
+    use rs_merkle::{
+        algorithms::Sha256, proof_serializers::ReverseHashesOrder, ConsistencyProof, Hasher,
+        MerkleTree,
+    };
+
+    fn leaf_hashes() -> Vec<[u8; 32]> {
+        (0..11)
+            .map(|i: usize| Sha256::hash(i.to_string().as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    pub fn should_serialize_and_deserialize_proofs() {
+        let leaves = leaf_hashes();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let new_root = merkle_tree.root().unwrap();
+        let old_root = MerkleTree::<Sha256>::from_leaves(&leaves[..4])
+            .root()
+            .unwrap();
+        let proof = merkle_tree.consistency_proof(4, 11).unwrap();
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), proof.proof_hashes().len() * 32);
+        let parsed_proof = ConsistencyProof::<Sha256>::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed_proof.proof_hashes(), proof.proof_hashes());
+        assert!(parsed_proof.verify(old_root, new_root, 4, 11));
+
+        let bytes = proof.serialize::<ReverseHashesOrder>();
+        let parsed_proof =
+            ConsistencyProof::<Sha256>::deserialize::<ReverseHashesOrder>(&bytes).unwrap();
+        assert!(parsed_proof.verify(old_root, new_root, 4, 11));
+
+        assert!(ConsistencyProof::<Sha256>::from_bytes(&bytes[..40]).is_err());
+    }
+
+    #[test]
+    pub fn should_clone_and_format_proofs() {
+        let proof = MerkleTree::<Sha256>::from_leaves(&leaf_hashes())
+            .consistency_proof(4, 11)
+            .unwrap();
+        let cloned_proof = proof.clone();
+
+        assert_eq!(cloned_proof.proof_hashes(), proof.proof_hashes());
+        assert_eq!(
+            format!("{:?}", proof),
+            format!(
+                "ConsistencyProof {{ proof_hashes: {:?} }}",
+                proof.proof_hashes()
+            )
+        );
+        assert_eq!(proof.proof_hashes_hex().len(), proof.proof_hashes().len());
+    }
+}
@@ -1,4 +1,4 @@
-use rs_merkle::{algorithms::Sha256, Hasher, MerkleProof, MerkleTree};
+use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree};
 
 #[test]
 fn duplicate_indices_should_not_verify() {
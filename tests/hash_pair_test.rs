@@ -0,0 +1,98 @@
+use rs_merkle::Hasher;
+
+/// Checks that both functions hash a pair of nodes the same way
+fn assert_hash_pair_matches_concat_and_hash<T: Hasher>() {
+    let left = T::hash(b"left");
+    let right = T::hash(b"right");
+
+    assert!(T::hash_pair(&left, &right) == T::concat_and_hash(&left, Some(&right)));
+    assert!(T::hash_pair(&right, &left) == T::concat_and_hash(&right, Some(&left)));
+}
+
+/// Hashes the data into a 96 byte hash, too long to be prefixed in the stack buffer of
+/// [`Rfc6962`](rs_merkle::algorithms::Rfc6962)
+#[derive(Clone)]
+pub struct WideSha256 {}
+
+impl Hasher for WideSha256 {
+    type Hash = [u8; 96];
+
+    fn hash(data: &[u8]) -> [u8; 96] {
+        let mut hash = [0u8; 96];
+        for (i, chunk) in hash.chunks_mut(32).enumerate() {
+            let mut prefixed = vec![i as u8];
+            prefixed.extend_from_slice(data);
+            chunk.copy_from_slice(&rs_merkle::algorithms::Sha256::hash(&prefixed));
+        }
+        hash
+    }
+}
+
+/// Checks that the RFC 6962 node hash is the hash of the prefixed concatenation of the nodes
+fn assert_rfc6962_node_is_prefixed<T: Hasher>()
+where
+    T::Hash: AsRef<[u8]>,
+{
+    let left = T::hash(b"left");
+    let right = T::hash(b"right");
+    let mut prefixed = vec![0x01];
+    prefixed.extend_from_slice(left.as_ref());
+    prefixed.extend_from_slice(right.as_ref());
+
+    assert!(rs_merkle::algorithms::Rfc6962::<T>::hash_pair(&left, &right) == T::hash(&prefixed));
+}
+
+pub mod hash_pair {
+    use crate::{
+        assert_hash_pair_matches_concat_and_hash, assert_rfc6962_node_is_prefixed, WideSha256,
+    };
+    #[cfg(feature = "keccak256")]
+    use rs_merkle::algorithms::Keccak256;
+    use rs_merkle::{
+        algorithms::{Bitcoin, Rfc6962, Sha256, Sha384, SortedPair},
+        Hasher,
+    };
+
+    #[test]
+    pub fn should_match_concat_and_hash_for_all_hashers() {
+        assert_hash_pair_matches_concat_and_hash::<Sha256>();
+        assert_hash_pair_matches_concat_and_hash::<Sha384>();
+        assert_hash_pair_matches_concat_and_hash::<Bitcoin>();
+        assert_hash_pair_matches_concat_and_hash::<Rfc6962<Sha256>>();
+        assert_hash_pair_matches_concat_and_hash::<SortedPair<Sha256>>();
+        assert_hash_pair_matches_concat_and_hash::<SortedPair<Bitcoin>>();
+        #[cfg(feature = "keccak256")]
+        {
+            assert_hash_pair_matches_concat_and_hash::<Keccak256>();
+            assert_hash_pair_matches_concat_and_hash::<SortedPair<Keccak256>>();
+        }
+    }
+
+    #[test]
+    pub fn should_prefix_rfc6962_nodes() {
+        assert_rfc6962_node_is_prefixed::<Sha256>();
+        assert_rfc6962_node_is_prefixed::<Sha384>();
+        assert_rfc6962_node_is_prefixed::<WideSha256>();
+        assert_hash_pair_matches_concat_and_hash::<Rfc6962<WideSha256>>();
+    }
+
+    #[test]
+    pub fn should_sort_the_nodes_of_a_sorted_pair() {
+        let left = Sha256::hash(b"left");
+        let right = Sha256::hash(b"right");
+        let (smaller, bigger) = if left < right {
+            (left, right)
+        } else {
+            (right, left)
+        };
+
+        assert_eq!(
+            SortedPair::<Sha256>::hash_pair(&left, &right),
+            Sha256::hash_pair(&smaller, &bigger)
+        );
+        assert_eq!(
+            SortedPair::<Sha256>::hash_pair(&right, &left),
+            Sha256::hash_pair(&smaller, &bigger)
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use rs_merkle::{algorithms::Sha256, Hasher};
+
+fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+    (0..count)
+        .map(|i| Sha256::hash(i.to_string().as_bytes()))
+        .collect()
+}
+
+pub mod root {
+    use crate::leaf_hashes;
+    use rs_merkle::{
+        algorithms::Sha256, utils, ErrorKind, Hasher, IncrementalMerkleTree, MerkleTree,
+    };
+
+    #[test]
+    pub fn should_match_the_empty_ethereum_deposit_contract_root() {
+        let tree = IncrementalMerkleTree::<Sha256>::new(2).unwrap();
+        assert_eq!(
+            tree.root_hex(),
+            "db56114e00fdd4c1f85c892bf35ac9a89289aaecb1ebd0a96cde606a748b5d71"
+        );
+
+        // The deposit contract mixes the little endian number of deposits into the root
+        let tree = IncrementalMerkleTree::<Sha256>::new(32).unwrap();
+        let mut deposit_root = tree.root().to_vec();
+        deposit_root.extend_from_slice(&[0u8; 32]);
+        assert_eq!(
+            utils::collections::to_hex_string(&Sha256::hash(&deposit_root)),
+            "d70a234731285c6804c2a4f56711ddb8c82c99740f207854891028af34e27e5e"
+        );
+    }
+
+    #[test]
+    pub fn should_match_the_root_of_a_padded_merkle_tree() {
+        let leaves = leaf_hashes(16);
+        let mut tree = IncrementalMerkleTree::<Sha256>::new(4).unwrap();
+
+        for count in 0..=leaves.len() {
+            let mut padded_leaves = leaves[..count].to_vec();
+            padded_leaves.resize(16, [0u8; 32]);
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&padded_leaves);
+
+            assert_eq!(Some(tree.root()), merkle_tree.root());
+            assert_eq!(tree.leaves_len(), count);
+            assert_eq!(tree.frontier().len(), count.count_ones() as usize);
+
+            if count < leaves.len() {
+                tree.append(leaves[count]).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_not_append_to_a_full_tree() {
+        let mut tree = IncrementalMerkleTree::<Sha256>::new(2).unwrap();
+        for leaf in leaf_hashes(4) {
+            tree.append(leaf).unwrap();
+        }
+        let root = tree.root();
+
+        let kind = tree
+            .append(Sha256::hash("e".as_bytes()))
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::TreeIsFull));
+        assert_eq!(tree.root(), root);
+        assert_eq!(tree.capacity(), 4);
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_invalid_depths() {
+        for depth in [0, IncrementalMerkleTree::<Sha256>::MAX_DEPTH + 1] {
+            let kind = IncrementalMerkleTree::<Sha256>::new(depth)
+                .err()
+                .map(|error| error.kind());
+            assert_eq!(kind, Some(ErrorKind::InvalidTreeDepth));
+        }
+    }
+}
+
+pub mod checkpoint {
+    use crate::leaf_hashes;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, IncrementalMerkleTree};
+
+    #[test]
+    pub fn should_restore_the_tree_from_a_checkpoint() {
+        let leaves = leaf_hashes(32);
+        let mut tree = IncrementalMerkleTree::<Sha256>::new(5).unwrap();
+
+        for (count, leaf) in leaves.iter().enumerate() {
+            let mut restored_tree =
+                IncrementalMerkleTree::<Sha256>::from_bytes(&tree.to_bytes()).unwrap();
+            assert_eq!(restored_tree.leaves_len(), count);
+            assert_eq!(restored_tree.root(), tree.root());
+
+            tree.append(*leaf).unwrap();
+            restored_tree.append(*leaf).unwrap();
+            assert_eq!(restored_tree.root(), tree.root());
+        }
+
+        let restored_tree = IncrementalMerkleTree::<Sha256>::from_bytes(&tree.to_bytes()).unwrap();
+        assert_eq!(restored_tree.root(), tree.root());
+    }
+
+    #[test]
+    pub fn should_not_restore_malformed_checkpoints() {
+        let mut tree = IncrementalMerkleTree::<Sha256>::new(8).unwrap();
+        for leaf in leaf_hashes(5) {
+            tree.append(leaf).unwrap();
+        }
+        let checkpoint = tree.to_bytes();
+
+        let mut too_many_leaves = checkpoint.clone();
+        too_many_leaves[7] = 1;
+        let mut missing_hash = checkpoint.clone();
+        missing_hash[8] = 7;
+
+        for bytes in [
+            checkpoint[..5].to_vec(),
+            checkpoint[..checkpoint.len() - 1].to_vec(),
+            too_many_leaves,
+            missing_hash,
+        ] {
+            let kind = IncrementalMerkleTree::<Sha256>::from_bytes(&bytes)
+                .err()
+                .map(|error| error.kind());
+            assert_eq!(kind, Some(ErrorKind::InvalidSerializedTree));
+        }
+
+        let mut invalid_depth = checkpoint;
+        invalid_depth[0] = 0;
+        let kind = IncrementalMerkleTree::<Sha256>::from_bytes(&invalid_depth)
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::InvalidTreeDepth));
+    }
+}
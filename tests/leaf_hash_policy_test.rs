@@ -0,0 +1,252 @@
+use rs_merkle::{algorithms::Sha256, Hasher, LeafHashPolicy};
+
+#[derive(Clone)]
+pub struct DoubleSha256 {}
+
+impl Hasher for DoubleSha256 {
+    type Hash = [u8; 32];
+
+    const LEAF_HASH_POLICY: LeafHashPolicy = LeafHashPolicy::DoubleHash;
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        Sha256::hash(data)
+    }
+}
+
+#[derive(Clone)]
+pub struct PrefixedSha256 {}
+
+impl Hasher for PrefixedSha256 {
+    type Hash = [u8; 32];
+
+    const LEAF_HASH_POLICY: LeafHashPolicy = LeafHashPolicy::Prefixed(0x00);
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        Sha256::hash(data)
+    }
+}
+
+const DATA: [&str; 5] = ["a", "b", "c", "d", "e"];
+
+/// Returns the data of a fake leaf that has the same hash as the parent of the first two leaves
+/// hashed with the policy, if the leaves were hashed the same way as the internal nodes
+fn first_parent_as_data(policy: LeafHashPolicy) -> Vec<u8> {
+    let mut data = policy.hash_leaf::<Sha256>(DATA[0].as_bytes()).to_vec();
+    data.extend_from_slice(&policy.hash_leaf::<Sha256>(DATA[1].as_bytes()));
+    data
+}
+
+pub mod default_policy {
+    use crate::{first_parent_as_data, DATA};
+    use rs_merkle::{
+        algorithms::{Bitcoin, Rfc6962, Sha256, Sha384, SortedPair},
+        Hasher, LeafHashPolicy, MerkleProof, MerkleTree,
+    };
+
+    #[test]
+    pub fn should_separate_the_leaves_of_the_built_in_hashers() {
+        assert_eq!(Sha256::LEAF_HASH_POLICY, LeafHashPolicy::DoubleHash);
+        assert_eq!(Sha384::LEAF_HASH_POLICY, LeafHashPolicy::DoubleHash);
+        assert_eq!(Bitcoin::LEAF_HASH_POLICY, LeafHashPolicy::DoubleHash);
+        #[cfg(feature = "keccak256")]
+        assert_eq!(
+            rs_merkle::algorithms::Keccak256::LEAF_HASH_POLICY,
+            LeafHashPolicy::DoubleHash
+        );
+        assert_eq!(
+            SortedPair::<Sha256>::LEAF_HASH_POLICY,
+            LeafHashPolicy::DoubleHash
+        );
+        assert_eq!(Rfc6962::<Sha256>::LEAF_HASH_POLICY, LeafHashPolicy::Plain);
+        assert_eq!(Rfc6962::<Sha256>::hash_leaf(b"a"), Sha256::hash(b"\x00a"));
+    }
+
+    #[test]
+    pub fn should_reject_internal_nodes_as_leaves() {
+        let merkle_tree = MerkleTree::<Sha256>::from_data(&DATA);
+        let root = merkle_tree.root().unwrap();
+
+        let proof_hashes = merkle_tree.proof(&[0, 1]).proof_hashes().to_vec();
+        let forged_proof = MerkleProof::<Sha256>::new(proof_hashes);
+
+        for policy in [LeafHashPolicy::Plain, LeafHashPolicy::DoubleHash] {
+            assert!(!forged_proof.verify_data(root, &[0], &[first_parent_as_data(policy)], 3));
+        }
+    }
+}
+
+pub mod plain {
+    use crate::{first_parent_as_data, DATA};
+    use rs_merkle::{algorithms::Sha256, Hasher, LeafHashPolicy, MerkleProof, MerkleTree};
+
+    #[test]
+    pub fn should_build_the_same_tree_as_from_leaves() {
+        let leaves: Vec<[u8; 32]> = DATA.iter().map(|x| Sha256::hash(x.as_bytes())).collect();
+
+        let merkle_tree = MerkleTree::<Sha256>::from_data_with_policy(&DATA, LeafHashPolicy::Plain);
+
+        assert_eq!(
+            merkle_tree.root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves).root()
+        );
+    }
+
+    #[test]
+    pub fn should_not_tell_leaves_from_internal_nodes() {
+        let policy = LeafHashPolicy::Plain;
+        let merkle_tree = MerkleTree::<Sha256>::from_data_with_policy(&DATA, policy);
+        let root = merkle_tree.root().unwrap();
+
+        // The parent of the first two leaves is proven as the first leaf of a tree made of
+        // the second layer
+        let proof_hashes = merkle_tree.proof(&[0, 1]).proof_hashes().to_vec();
+        let forged_proof = MerkleProof::<Sha256>::new(proof_hashes);
+
+        assert!(forged_proof.verify_data_with_policy(
+            root,
+            &[0],
+            &[first_parent_as_data(policy)],
+            3,
+            policy
+        ));
+    }
+}
+
+pub mod double_hash {
+    use crate::{first_parent_as_data, DoubleSha256, DATA};
+    use rs_merkle::{algorithms::Sha256, Hasher, LeafHashPolicy, MerkleProof, MerkleTree};
+
+    #[test]
+    pub fn should_hash_the_leaf_data_twice() {
+        let leaves: Vec<[u8; 32]> = DATA
+            .iter()
+            .map(|x| Sha256::hash(&Sha256::hash(x.as_bytes())))
+            .collect();
+
+        let merkle_tree = MerkleTree::<DoubleSha256>::from_data(&DATA);
+        let root = merkle_tree.root().unwrap();
+
+        assert_eq!(
+            Some(root),
+            MerkleTree::<Sha256>::from_leaves(&leaves).root()
+        );
+        assert!(merkle_tree
+            .proof(&[1, 4])
+            .verify_data(root, &[1, 4], &["b", "e"], DATA.len()));
+        assert!(!merkle_tree.proof(&[1, 4]).verify(
+            root,
+            &[1, 4],
+            &[Sha256::hash(b"b"), Sha256::hash(b"e")],
+            DATA.len()
+        ));
+    }
+
+    #[test]
+    pub fn should_reject_internal_nodes_as_leaves() {
+        let merkle_tree = MerkleTree::<DoubleSha256>::from_data(&DATA);
+        let root = merkle_tree.root().unwrap();
+
+        let proof_hashes = merkle_tree.proof(&[0, 1]).proof_hashes().to_vec();
+        let forged_proof = MerkleProof::<DoubleSha256>::new(proof_hashes);
+
+        assert!(!forged_proof.verify_data(
+            root,
+            &[0],
+            &[first_parent_as_data(LeafHashPolicy::DoubleHash)],
+            3
+        ));
+    }
+}
+
+pub mod prefixed {
+    use crate::{PrefixedSha256, DATA};
+    use rs_merkle::{
+        algorithms::{Sha256, SortedPair},
+        Hasher, LeafHashPolicy, MerkleTree,
+    };
+
+    #[test]
+    pub fn should_hash_the_prefixed_leaf_data() {
+        assert_eq!(PrefixedSha256::hash_leaf(b"a"), Sha256::hash(b"\x00a"));
+        assert_eq!(
+            SortedPair::<PrefixedSha256>::LEAF_HASH_POLICY,
+            LeafHashPolicy::Prefixed(0x00)
+        );
+    }
+
+    #[test]
+    pub fn should_insert_the_same_leaves_as_from_data() {
+        let mut merkle_tree = MerkleTree::<PrefixedSha256>::new();
+        for data in DATA {
+            merkle_tree.insert_data(data);
+        }
+        merkle_tree.commit();
+
+        assert_eq!(
+            merkle_tree.root(),
+            MerkleTree::<PrefixedSha256>::from_data(&DATA).root()
+        );
+        assert_ne!(
+            merkle_tree.root(),
+            MerkleTree::<Sha256>::from_data_with_policy(&DATA, LeafHashPolicy::Plain).root()
+        );
+    }
+}
+
+pub mod explicit_policy {
+    use crate::{first_parent_as_data, DoubleSha256, PrefixedSha256, DATA};
+    use rs_merkle::{algorithms::Sha256, Hasher, LeafHashPolicy, MerkleProof, MerkleTree};
+
+    #[test]
+    pub fn should_hash_the_leaves_like_the_hasher_policy() {
+        assert_eq!(
+            LeafHashPolicy::DoubleHash.hash_leaf::<Sha256>(b"a"),
+            DoubleSha256::hash_leaf(b"a")
+        );
+        assert_eq!(
+            LeafHashPolicy::Prefixed(0x00).hash_leaf::<Sha256>(b"a"),
+            PrefixedSha256::hash_leaf(b"a")
+        );
+        assert_eq!(
+            MerkleTree::<Sha256>::from_data_with_policy(&DATA, LeafHashPolicy::DoubleHash).root(),
+            MerkleTree::<DoubleSha256>::from_data(&DATA).root()
+        );
+    }
+
+    #[test]
+    pub fn should_insert_the_same_leaves_as_from_data_with_policy() {
+        let policy = LeafHashPolicy::Prefixed(0x00);
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+        for data in DATA {
+            merkle_tree.insert_data_with_policy(data, policy);
+        }
+        merkle_tree.commit();
+
+        assert_eq!(
+            merkle_tree.root(),
+            MerkleTree::<Sha256>::from_data_with_policy(&DATA, policy).root()
+        );
+    }
+
+    #[test]
+    pub fn should_reject_internal_nodes_as_leaves() {
+        let policy = LeafHashPolicy::Prefixed(0x00);
+        let merkle_tree = MerkleTree::<Sha256>::from_data_with_policy(&DATA, policy);
+        let root = merkle_tree.root().unwrap();
+        let proof = merkle_tree.proof(&[1, 4]);
+
+        assert!(proof.verify_data_with_policy(root, &[1, 4], &["b", "e"], DATA.len(), policy));
+        assert!(!proof.verify_data(root, &[1, 4], &["b", "e"], DATA.len()));
+
+        let proof_hashes = merkle_tree.proof(&[0, 1]).proof_hashes().to_vec();
+        let forged_proof = MerkleProof::<Sha256>::new(proof_hashes);
+
+        assert!(!forged_proof.verify_data_with_policy(
+            root,
+            &[0],
+            &[first_parent_as_data(policy)],
+            3,
+            policy
+        ));
+    }
+}
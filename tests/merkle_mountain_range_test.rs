@@ -0,0 +1,258 @@
+mod common;
+
+use rs_merkle::{algorithms::Sha256, Hasher};
+
+fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+    (0..count)
+        .map(|i| Sha256::hash(i.to_string().as_bytes()))
+        .collect()
+}
+
+pub mod root {
+    use crate::leaf_hashes;
+    use rs_merkle::{algorithms::Sha256, Hasher, MerkleMountainRange, MerkleTree};
+
+    #[test]
+    pub fn should_bag_the_peaks_from_the_right() {
+        let leaves = leaf_hashes(7);
+        let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+
+        let first_peak = MerkleTree::<Sha256>::from_leaves(&leaves[..4])
+            .root()
+            .unwrap();
+        let second_peak = Sha256::concat_and_hash(&leaves[4], Some(&leaves[5]));
+        let third_peak = leaves[6];
+        assert_eq!(mmr.peaks(), vec![first_peak, second_peak, third_peak]);
+
+        let bagged_right = Sha256::concat_and_hash(&second_peak, Some(&third_peak));
+        assert_eq!(
+            mmr.root(),
+            Some(Sha256::concat_and_hash(&first_peak, Some(&bagged_right)))
+        );
+    }
+
+    #[test]
+    pub fn should_match_the_merkle_tree_root_for_powers_of_two() {
+        for count in [1, 2, 4, 8, 16] {
+            let leaves = leaf_hashes(count);
+            let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+            assert_eq!(mmr.peaks().len(), 1);
+            assert_eq!(mmr.root(), merkle_tree.root());
+        }
+    }
+
+    #[test]
+    pub fn should_return_none_for_an_empty_range() {
+        let mmr = MerkleMountainRange::<Sha256>::new();
+
+        assert_eq!(mmr.root(), None);
+        assert_eq!(mmr.root_hex(), None);
+        assert!(mmr.peaks().is_empty());
+    }
+
+    #[test]
+    pub fn should_have_a_peak_for_every_bit_of_the_leaves_count() {
+        let mut mmr = MerkleMountainRange::<Sha256>::new();
+
+        for (count, leaf) in leaf_hashes(40).into_iter().enumerate() {
+            mmr.push(leaf);
+            assert_eq!(mmr.leaves_len(), count + 1);
+            assert_eq!(mmr.peaks().len(), (count + 1).count_ones() as usize);
+        }
+    }
+}
+
+pub mod proof {
+    use crate::{common, leaf_hashes};
+    use rs_merkle::{
+        algorithms::Sha256, proof_serializers::ReverseHashesOrder, ErrorKind, MerkleMountainRange,
+        MerkleMountainRangeProof,
+    };
+
+    #[test]
+    pub fn should_verify_single_leaf_proofs() {
+        for count in 1..=20 {
+            let leaves = leaf_hashes(count);
+            let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+            let root = mmr.root().unwrap();
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = mmr.proof(&[index]).unwrap();
+                assert!(proof.verify(root, &[index], &[*leaf], count));
+                assert!(
+                    !proof.verify(root, &[index], &[leaves[(index + 1) % count]], count)
+                        || count == 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_verify_multi_leaf_proofs() {
+        let leaves = leaf_hashes(11);
+        let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+        let root = mmr.root().unwrap();
+
+        for indices in common::combinations((0..leaves.len()).collect::<Vec<usize>>()) {
+            let proof = mmr.proof(&indices).unwrap();
+            let leaves_to_prove: Vec<[u8; 32]> = indices.iter().map(|i| leaves[*i]).collect();
+
+            assert!(proof.verify(root, &indices, &leaves_to_prove, leaves.len()));
+        }
+    }
+
+    #[test]
+    pub fn should_share_hashes_between_leaves() {
+        let leaves = leaf_hashes(8);
+        let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+
+        assert_eq!(mmr.proof(&[0]).unwrap().proof_hashes().len(), 3);
+        assert_eq!(mmr.proof(&[0, 1]).unwrap().proof_hashes().len(), 2);
+        assert_eq!(mmr.proof(&[0, 1, 2, 3]).unwrap().proof_hashes().len(), 1);
+    }
+
+    #[test]
+    pub fn should_stay_valid_after_appending_leaves() {
+        let leaves = leaf_hashes(20);
+        let mut mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves[..6]);
+        let old_root = mmr.root().unwrap();
+        let proof = mmr.proof(&[2]).unwrap();
+
+        for leaf in &leaves[6..] {
+            mmr.push(*leaf);
+        }
+        let new_root = mmr.root().unwrap();
+
+        assert!(proof.verify(old_root, &[2], &[leaves[2]], 6));
+        assert!(mmr
+            .ancestry_proof(6)
+            .unwrap()
+            .verify(old_root, new_root, 6, 20));
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_invalid_indices() {
+        let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaf_hashes(5));
+
+        let kind = mmr.proof(&[5]).err().map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::LeafIndexOutOfBounds));
+
+        let kind = mmr.proof(&[1, 1]).err().map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::DuplicateLeafIndex));
+    }
+
+    #[test]
+    pub fn should_not_verify_tampered_proofs() {
+        let leaves = leaf_hashes(13);
+        let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+        let root = mmr.root().unwrap();
+        let proof = mmr.proof(&[3, 9]).unwrap();
+
+        let mut proof_hashes = proof.proof_hashes().to_vec();
+        proof_hashes[1][0] ^= 1;
+        let tampered_proof = MerkleMountainRangeProof::<Sha256>::new(proof_hashes);
+        assert!(!tampered_proof.verify(root, &[3, 9], &[leaves[3], leaves[9]], 13));
+
+        let mut proof_hashes = proof.proof_hashes().to_vec();
+        proof_hashes.pop();
+        let short_proof = MerkleMountainRangeProof::<Sha256>::new(proof_hashes);
+        let kind = short_proof
+            .root(&[3, 9], &[leaves[3], leaves[9]], 13)
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::NotEnoughHashesToCalculateRoot));
+
+        let mut proof_hashes = proof.proof_hashes().to_vec();
+        proof_hashes.push(leaves[0]);
+        let long_proof = MerkleMountainRangeProof::<Sha256>::new(proof_hashes);
+        let kind = long_proof
+            .root(&[3, 9], &[leaves[3], leaves[9]], 13)
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::TooManyProofHashes));
+        assert!(!long_proof.verify(root, &[3, 9], &[leaves[3], leaves[9]], 13));
+
+        assert!(!proof.verify(root, &[3, 9], &[leaves[3], leaves[9]], 16));
+        assert!(!proof.verify(root, &[9, 3], &[leaves[3], leaves[9]], 13));
+    }
+
+    #[test]
+    pub fn should_serialize_and_deserialize_proofs() {
+        let leaves = leaf_hashes(13);
+        let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+        let root = mmr.root().unwrap();
+        let proof = mmr.proof(&[3, 9]).unwrap();
+
+        let parsed_proof =
+            MerkleMountainRangeProof::<Sha256>::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(parsed_proof.proof_hashes(), proof.proof_hashes());
+
+        let bytes = proof.serialize::<ReverseHashesOrder>();
+        let parsed_proof =
+            MerkleMountainRangeProof::<Sha256>::deserialize::<ReverseHashesOrder>(&bytes).unwrap();
+        assert!(parsed_proof.verify(root, &[3, 9], &[leaves[3], leaves[9]], 13));
+    }
+}
+
+pub mod ancestry_proof {
+    use crate::leaf_hashes;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, MerkleMountainRange};
+
+    #[test]
+    pub fn should_verify_every_old_size() {
+        let leaves = leaf_hashes(24);
+        let roots: Vec<[u8; 32]> = (1..=leaves.len())
+            .map(|count| {
+                MerkleMountainRange::<Sha256>::from_leaves(&leaves[..count])
+                    .root()
+                    .unwrap()
+            })
+            .collect();
+
+        for new_size in 1..=leaves.len() {
+            let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves[..new_size]);
+            for old_size in 1..=new_size {
+                let proof = mmr.ancestry_proof(old_size).unwrap();
+                assert!(proof.verify(roots[old_size - 1], roots[new_size - 1], old_size, new_size));
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_not_verify_with_wrong_roots_or_sizes() {
+        let leaves = leaf_hashes(10);
+        let old_root = MerkleMountainRange::<Sha256>::from_leaves(&leaves[..5])
+            .root()
+            .unwrap();
+        let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaves);
+        let new_root = mmr.root().unwrap();
+        let proof = mmr.ancestry_proof(5).unwrap();
+
+        assert!(proof.verify(old_root, new_root, 5, 10));
+        assert!(!proof.verify(new_root, new_root, 5, 10));
+        assert!(!proof.verify(old_root, old_root, 5, 10));
+        assert!(!proof.verify(old_root, new_root, 6, 10));
+        assert!(!proof.verify(old_root, new_root, 5, 11));
+        assert!(!proof.verify(old_root, new_root, 10, 5));
+
+        // A different history with the same old range
+        let mut other_leaves = leaves.clone();
+        other_leaves[7] = leaves[8];
+        let other_root = MerkleMountainRange::<Sha256>::from_leaves(&other_leaves)
+            .root()
+            .unwrap();
+        assert!(!proof.verify(old_root, other_root, 5, 10));
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_invalid_sizes() {
+        let mmr = MerkleMountainRange::<Sha256>::from_leaves(&leaf_hashes(5));
+
+        for old_size in [0, 6] {
+            let kind = mmr.ancestry_proof(old_size).err().map(|error| error.kind());
+            assert_eq!(kind, Some(ErrorKind::InvalidTreeSize));
+        }
+    }
+}
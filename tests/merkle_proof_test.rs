@@ -247,3 +247,149 @@ pub mod from_bytes {
         );
     }
 }
+
+pub mod self_describing {
+    use rs_merkle::{
+        algorithms::{Bitcoin, Rfc6962, Sha256, Sha384, SortedPair},
+        proof_serializers::{DirectHashesOrder, SelfDescribing},
+        Error, ErrorKind, Hasher, MerkleProof, MerkleTree,
+    };
+
+    fn leaves() -> Vec<[u8; 32]> {
+        ["a", "b", "c", "d", "e", "f"]
+            .iter()
+            .map(|x| Sha256::hash(x.as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    pub fn should_round_trip_proof_with_metadata() -> Result<(), Error> {
+        let leaves = leaves();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let root = merkle_tree.root().unwrap();
+
+        let bytes = merkle_tree.proof(&[0, 3, 5]).serialize::<SelfDescribing>();
+        let proof = MerkleProof::<Sha256>::deserialize::<SelfDescribing>(&bytes)?;
+
+        assert_eq!(proof.leaf_indices(), Some(&[0, 3, 5][..]));
+        assert_eq!(proof.total_leaves_count(), Some(6));
+        assert!(proof.verify_with_metadata(root, &[leaves[0], leaves[3], leaves[5]]));
+        assert!(!proof.verify_with_metadata(root, &[leaves[1], leaves[3], leaves[5]]));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn should_round_trip_proof_without_metadata() -> Result<(), Error> {
+        let leaves = leaves();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let proof_bytes = merkle_tree.proof(&[2]).serialize::<DirectHashesOrder>();
+
+        let proof = MerkleProof::<Sha256>::deserialize::<DirectHashesOrder>(&proof_bytes)?;
+        let bytes = proof.serialize::<SelfDescribing>();
+        let proof = MerkleProof::<Sha256>::deserialize::<SelfDescribing>(&bytes)?;
+
+        assert_eq!(proof.leaf_indices(), None);
+        assert_eq!(proof.serialize::<DirectHashesOrder>(), proof_bytes);
+        assert!(!proof.verify_with_metadata(merkle_tree.root().unwrap(), &[leaves[2]]));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn should_reject_proof_of_another_algorithm() {
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves());
+        let bytes = merkle_tree.proof(&[1]).serialize::<SelfDescribing>();
+
+        let result = MerkleProof::<SortedPair<Sha256>>::deserialize::<SelfDescribing>(&bytes);
+
+        assert_eq!(
+            result.err().map(|error| error.kind()),
+            Some(ErrorKind::HashAlgorithmMismatch)
+        );
+    }
+
+    /// Returns the identifiers of the hasher and of its wrappers nested up to two levels deep
+    fn wrapped_algorithm_ids<H: Hasher>() -> Vec<u16>
+    where
+        H::Hash: AsRef<[u8]>,
+    {
+        vec![
+            H::ALGORITHM_ID,
+            Rfc6962::<H>::ALGORITHM_ID,
+            SortedPair::<H>::ALGORITHM_ID,
+            Rfc6962::<Rfc6962<H>>::ALGORITHM_ID,
+            Rfc6962::<SortedPair<H>>::ALGORITHM_ID,
+            SortedPair::<Rfc6962<H>>::ALGORITHM_ID,
+            SortedPair::<SortedPair<H>>::ALGORITHM_ID,
+        ]
+    }
+
+    #[test]
+    pub fn should_give_every_hasher_its_own_algorithm_id() {
+        let mut algorithm_ids = wrapped_algorithm_ids::<Sha256>();
+        algorithm_ids.extend(wrapped_algorithm_ids::<Sha384>());
+        algorithm_ids.extend(wrapped_algorithm_ids::<Bitcoin>());
+        #[cfg(feature = "keccak256")]
+        algorithm_ids.extend(wrapped_algorithm_ids::<rs_merkle::algorithms::Keccak256>());
+
+        let mut unique_ids = algorithm_ids.clone();
+        unique_ids.sort_unstable();
+        unique_ids.dedup();
+        assert_eq!(
+            unique_ids.len(),
+            algorithm_ids.len(),
+            "{:x?}",
+            algorithm_ids
+        );
+
+        // Single wrappers keep the identifiers of the proofs serialized so far
+        assert_eq!(Rfc6962::<Sha256>::ALGORITHM_ID, 0x0101);
+        assert_eq!(SortedPair::<Sha256>::ALGORITHM_ID, 0x0201);
+    }
+
+    #[test]
+    pub fn should_reject_unknown_version() {
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves());
+        let mut bytes = merkle_tree.proof(&[1]).serialize::<SelfDescribing>();
+        bytes[0] = 2;
+
+        let result = MerkleProof::<Sha256>::deserialize::<SelfDescribing>(&bytes);
+
+        assert_eq!(
+            result.err().map(|error| error.kind()),
+            Some(ErrorKind::UnsupportedFormatVersion)
+        );
+    }
+
+    #[test]
+    pub fn should_reject_malformed_input() {
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves());
+        let bytes = merkle_tree.proof(&[1, 4]).serialize::<SelfDescribing>();
+
+        let truncated_header = MerkleProof::<Sha256>::deserialize::<SelfDescribing>(&bytes[..10]);
+        let truncated_indices = MerkleProof::<Sha256>::deserialize::<SelfDescribing>(&bytes[..30]);
+        let truncated_hash =
+            MerkleProof::<Sha256>::deserialize::<SelfDescribing>(&bytes[..bytes.len() - 1]);
+        let mut out_of_bounds = bytes.clone();
+        out_of_bounds[34] = 6;
+        let out_of_bounds = MerkleProof::<Sha256>::deserialize::<SelfDescribing>(&out_of_bounds);
+
+        assert_eq!(
+            truncated_header.err().map(|error| error.kind()),
+            Some(ErrorKind::SerializedProofSizeIsIncorrect)
+        );
+        assert_eq!(
+            truncated_indices.err().map(|error| error.kind()),
+            Some(ErrorKind::SerializedProofSizeIsIncorrect)
+        );
+        assert_eq!(
+            truncated_hash.err().map(|error| error.kind()),
+            Some(ErrorKind::SerializedProofSizeIsIncorrect)
+        );
+        assert_eq!(
+            out_of_bounds.err().map(|error| error.kind()),
+            Some(ErrorKind::LeafIndexOutOfBounds)
+        );
+    }
+}
@@ -0,0 +1,96 @@
+pub mod root {
+    use rs_merkle::{
+        algorithms::{Bitcoin, Sha256},
+        Hasher, MerkleRootBuilder, MerkleTree,
+    };
+
+    fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| Sha256::hash(i.to_string().as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    pub fn should_return_the_same_root_as_merkle_tree_after_every_leaf() {
+        let leaves = leaf_hashes(70);
+        let mut builder = MerkleRootBuilder::<Sha256>::new();
+        let mut bitcoin_builder = MerkleRootBuilder::<Bitcoin>::new();
+
+        assert_eq!(builder.root(), None);
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            builder.insert(*leaf);
+            bitcoin_builder.insert(*leaf);
+
+            assert_eq!(builder.leaves_len(), index + 1);
+            assert_eq!(
+                builder.root(),
+                MerkleTree::<Sha256>::from_leaves(&leaves[..=index]).root()
+            );
+            assert_eq!(
+                bitcoin_builder.root(),
+                MerkleTree::<Bitcoin>::from_leaves(&leaves[..=index]).root()
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_collect_leaves_from_an_iterator() {
+        let leaves = leaf_hashes(1000);
+        let builder: MerkleRootBuilder<Sha256> = leaves.iter().copied().collect();
+
+        assert_eq!(
+            builder.root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves).root()
+        );
+    }
+}
+
+#[cfg(feature = "std")]
+pub mod read_leaves {
+    use rs_merkle::{algorithms::Sha256, Hasher, MerkleRootBuilder, MerkleTree};
+    use std::io::{self, Read};
+
+    /// Returns the data in pieces of at most 3 bytes, like a socket would
+    struct SlowReader<'a> {
+        data: &'a [u8],
+    }
+
+    impl Read for SlowReader<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let read_len = self.data.len().min(buffer.len()).min(3);
+            buffer[..read_len].copy_from_slice(&self.data[..read_len]);
+            self.data = &self.data[read_len..];
+            Ok(read_len)
+        }
+    }
+
+    #[test]
+    pub fn should_hash_every_chunk_as_a_leaf() {
+        let data: Vec<u8> = (0..1000).map(|i: usize| i as u8).collect();
+        let leaves: Vec<[u8; 32]> = data.chunks(64).map(Sha256::hash).collect();
+
+        let mut builder = MerkleRootBuilder::<Sha256>::new();
+        let leaves_count = builder.read_leaves(SlowReader { data: &data }, 64).unwrap();
+
+        assert_eq!(leaves_count, 16);
+        assert_eq!(
+            builder.root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves).root()
+        );
+    }
+
+    #[test]
+    pub fn should_reject_empty_chunks() {
+        let mut builder = MerkleRootBuilder::<Sha256>::new();
+
+        let result = builder.read_leaves(&[1u8, 2, 3][..], 0);
+
+        assert_eq!(
+            result.err().map(|error| error.kind()),
+            Some(io::ErrorKind::InvalidInput)
+        );
+        assert_eq!(builder.read_leaves(io::empty(), 16).unwrap(), 0);
+        assert_eq!(builder.root(), None);
+    }
+}
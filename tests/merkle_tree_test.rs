@@ -276,3 +276,678 @@ pub mod rollback {
         );
     }
 }
+
+pub mod update {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_give_the_same_root_as_a_rebuilt_tree() {
+        let leaf_values = ["a", "b", "c", "d", "e", "f", "g"];
+        let mut leaves: Vec<[u8; 32]> = leaf_values
+            .iter()
+            .map(|x| Sha256::hash(x.as_bytes()))
+            .collect();
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+        for index in 0..leaves.len() {
+            let new_leaf = Sha256::hash(format!("updated {}", index).as_bytes());
+            leaves[index] = new_leaf;
+
+            merkle_tree.update(index, new_leaf).unwrap();
+            let expected_root = MerkleTree::<Sha256>::from_leaves(&leaves).root();
+            assert_eq!(merkle_tree.uncommitted_root(), expected_root);
+
+            merkle_tree.commit();
+            assert_eq!(merkle_tree.root(), expected_root);
+            assert_eq!(merkle_tree.leaves(), Some(leaves.clone()));
+        }
+    }
+
+    #[test]
+    pub fn should_update_uncommitted_leaves() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes[..4]);
+        let new_leaf = Sha256::hash("z".as_bytes());
+
+        merkle_tree
+            .append(test_data.leaf_hashes[4..].to_vec().as_mut())
+            .update_batch(&[(1, new_leaf), (5, new_leaf)])
+            .unwrap()
+            .commit();
+
+        let mut expected_leaves = test_data.leaf_hashes.clone();
+        expected_leaves[1] = new_leaf;
+        expected_leaves[5] = new_leaf;
+        let expected_tree = MerkleTree::<Sha256>::from_leaves(&expected_leaves);
+
+        assert_eq!(merkle_tree.root(), expected_tree.root());
+        assert_eq!(merkle_tree.leaves(), Some(expected_leaves));
+    }
+
+    #[test]
+    pub fn should_not_stage_anything_when_index_is_out_of_bounds() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let new_leaf = Sha256::hash("z".as_bytes());
+
+        assert!(merkle_tree
+            .update_batch(&[(0, new_leaf), (test_data.leaf_hashes.len(), new_leaf)])
+            .is_err());
+        assert_eq!(merkle_tree.uncommitted_root(), None);
+    }
+
+    #[test]
+    pub fn should_rollback_an_update() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+
+        merkle_tree
+            .update(2, Sha256::hash("z".as_bytes()))
+            .unwrap()
+            .commit();
+        assert_ne!(
+            merkle_tree.root_hex(),
+            Some(test_data.expected_root_hex.clone())
+        );
+
+        merkle_tree.rollback();
+        assert_eq!(merkle_tree.root_hex(), Some(test_data.expected_root_hex));
+        assert_eq!(merkle_tree.leaves(), Some(test_data.leaf_hashes));
+    }
+}
+
+pub mod truncate {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree};
+
+    fn leaves(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| Sha256::hash(i.to_string().as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    pub fn should_give_the_same_tree_as_a_rebuilt_one() {
+        for leaves_count in 1..=17 {
+            let leaves = leaves(leaves_count);
+
+            for new_leaves_count in 0..leaves_count {
+                let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+                let expected_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..new_leaves_count]);
+
+                merkle_tree.truncate(new_leaves_count);
+                assert_eq!(merkle_tree.uncommitted_root(), expected_tree.root());
+
+                merkle_tree.commit();
+                assert_eq!(merkle_tree.root(), expected_tree.root());
+                assert_eq!(merkle_tree.leaves(), expected_tree.leaves());
+                assert_eq!(merkle_tree.leaves_len(), new_leaves_count);
+
+                if new_leaves_count == 0 {
+                    continue;
+                }
+
+                assert_eq!(merkle_tree.depth(), expected_tree.depth());
+                let root = merkle_tree.root().unwrap();
+                for (index, leaf) in leaves.iter().take(new_leaves_count).enumerate() {
+                    let proof = merkle_tree.proof(&[index]);
+                    assert!(proof.verify(root, &[index], &[*leaf], new_leaves_count));
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_append_after_truncation() {
+        let leaves = leaves(9);
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let new_leaf = Sha256::hash("z".as_bytes());
+
+        merkle_tree
+            .truncate(3)
+            .insert(new_leaf)
+            .update(1, new_leaf)
+            .unwrap()
+            .commit();
+
+        let expected_leaves = vec![leaves[0], new_leaf, leaves[2], new_leaf];
+        let expected_tree = MerkleTree::<Sha256>::from_leaves(&expected_leaves);
+        assert_eq!(merkle_tree.root(), expected_tree.root());
+        assert_eq!(merkle_tree.leaves(), Some(expected_leaves));
+        assert_eq!(merkle_tree.depth(), expected_tree.depth());
+    }
+
+    #[test]
+    pub fn should_pop_uncommitted_and_committed_leaves() {
+        let test_data = common::setup();
+        let leaves = test_data.leaf_hashes;
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..4]);
+        let new_leaf = Sha256::hash("z".as_bytes());
+
+        merkle_tree
+            .append(leaves[4..].to_vec().as_mut())
+            .update(2, new_leaf)
+            .unwrap();
+
+        assert_eq!(merkle_tree.pop(), Some(leaves[5]));
+        assert_eq!(merkle_tree.pop(), Some(leaves[4]));
+        assert_eq!(merkle_tree.pop(), Some(leaves[3]));
+        assert_eq!(merkle_tree.pop(), Some(new_leaf));
+        merkle_tree.commit();
+
+        assert_eq!(merkle_tree.leaves(), Some(leaves[..2].to_vec()));
+
+        assert_eq!(merkle_tree.pop(), Some(leaves[1]));
+        assert_eq!(merkle_tree.pop(), Some(leaves[0]));
+        assert_eq!(merkle_tree.pop(), None);
+        merkle_tree.commit();
+
+        assert_eq!(merkle_tree.root(), None);
+        assert_eq!(merkle_tree.leaves_len(), 0);
+    }
+
+    #[test]
+    pub fn should_rollback_truncation() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+
+        merkle_tree.truncate(2).commit();
+        merkle_tree.truncate(0).commit();
+        assert_eq!(merkle_tree.root(), None);
+
+        merkle_tree.rollback();
+        assert_eq!(
+            merkle_tree.root(),
+            MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes[..2]).root()
+        );
+
+        merkle_tree.rollback();
+        assert_eq!(merkle_tree.root_hex(), Some(test_data.expected_root_hex));
+        assert_eq!(merkle_tree.leaves(), Some(test_data.leaf_hashes));
+    }
+}
+
+pub mod rollback_to {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_revert_mixed_changes() {
+        let leaf = |value: usize| Sha256::hash(value.to_string().as_bytes());
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+        let mut states: Vec<Vec<[u8; 32]>> = Vec::new();
+        let mut commit_ids = Vec::new();
+        let mut leaves: Vec<[u8; 32]> = Vec::new();
+
+        for step in 0..24 {
+            match step % 4 {
+                // Growing across the powers of two
+                0 | 1 => {
+                    let mut new_leaves: Vec<[u8; 32]> = (0..step + 1).map(leaf).collect();
+                    leaves.extend_from_slice(&new_leaves);
+                    merkle_tree.append(&mut new_leaves);
+                }
+                2 => {
+                    let index = (step * 7) % leaves.len();
+                    leaves[index] = leaf(step * 100);
+                    merkle_tree.update(index, leaves[index]).unwrap();
+                }
+                _ => {
+                    leaves.truncate(leaves.len() * 2 / 3);
+                    merkle_tree.truncate(leaves.len());
+                }
+            }
+
+            commit_ids.push(merkle_tree.commit().unwrap());
+            states.push(leaves.clone());
+        }
+
+        for (commit_id, leaves) in commit_ids.iter().zip(states.iter()).rev() {
+            merkle_tree.rollback_to(commit_id).unwrap();
+            let expected_tree = MerkleTree::<Sha256>::from_leaves(leaves);
+
+            assert_eq!(merkle_tree.root(), commit_id.root());
+            assert_eq!(merkle_tree.root(), expected_tree.root());
+            assert_eq!(merkle_tree.leaves(), expected_tree.leaves());
+            assert_eq!(merkle_tree.depth(), expected_tree.depth());
+        }
+
+        merkle_tree.rollback();
+        assert_eq!(merkle_tree.root(), None);
+        assert_eq!(merkle_tree.leaves(), None);
+    }
+
+    #[test]
+    pub fn should_rollback_several_commits_at_once() {
+        let test_data = common::setup();
+        let leaves = test_data.leaf_hashes;
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+
+        let commit_ids: Vec<_> = leaves
+            .iter()
+            .map(|leaf| merkle_tree.insert(*leaf).commit().unwrap())
+            .collect();
+        assert_eq!(merkle_tree.commits(), commit_ids);
+
+        for (index, commit_id) in commit_ids.iter().enumerate() {
+            let expected_root = MerkleTree::<Sha256>::from_leaves(&leaves[..=index]).root();
+            assert_eq!(commit_id.sequence(), index);
+            assert_eq!(commit_id.root(), expected_root);
+            assert_eq!(merkle_tree.root_at(commit_id), expected_root);
+        }
+
+        merkle_tree.rollback_to(&commit_ids[2]).unwrap();
+        assert_eq!(merkle_tree.commits(), commit_ids[..3].to_vec());
+        assert_eq!(merkle_tree.root(), commit_ids[2].root());
+        assert_eq!(merkle_tree.leaves(), Some(leaves[..3].to_vec()));
+
+        // The tree keeps working after the rollback
+        merkle_tree.append(leaves[3..].to_vec().as_mut()).commit();
+        assert_eq!(merkle_tree.root_hex(), Some(test_data.expected_root_hex));
+    }
+
+    #[test]
+    pub fn should_not_rollback_to_a_reverted_commit() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+
+        let first_commit = merkle_tree
+            .insert(test_data.leaf_hashes[0])
+            .commit()
+            .unwrap();
+        let reverted_commit = merkle_tree
+            .insert(test_data.leaf_hashes[1])
+            .commit()
+            .unwrap();
+        merkle_tree.rollback();
+
+        // A new commit at the same position in the history has a different root
+        let new_commit = merkle_tree
+            .insert(Sha256::hash("z".as_bytes()))
+            .commit()
+            .unwrap();
+        assert_eq!(new_commit.sequence(), reverted_commit.sequence());
+        assert_ne!(new_commit, reverted_commit);
+
+        assert_eq!(merkle_tree.root_at(&reverted_commit), None);
+        assert_eq!(
+            merkle_tree
+                .rollback_to(&reverted_commit)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::CommitNotFound
+        );
+        assert_eq!(merkle_tree.root(), new_commit.root());
+
+        merkle_tree.rollback_to(&first_commit).unwrap();
+        assert_eq!(merkle_tree.root(), first_commit.root());
+    }
+
+    #[test]
+    pub fn should_not_create_a_commit_without_changes() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+
+        assert_eq!(merkle_tree.commit(), None);
+        assert_eq!(merkle_tree.commits().len(), 1);
+    }
+}
+
+pub mod proof_at {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_prove_leaves_of_past_commits() {
+        let leaf = |value: usize| Sha256::hash(value.to_string().as_bytes());
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+        let mut states: Vec<Vec<[u8; 32]>> = Vec::new();
+        let mut commit_ids = Vec::new();
+        let mut leaves: Vec<[u8; 32]> = Vec::new();
+
+        for step in 0..12 {
+            match step % 3 {
+                0 => {
+                    let mut new_leaves: Vec<[u8; 32]> = (0..step + 2).map(leaf).collect();
+                    leaves.extend_from_slice(&new_leaves);
+                    merkle_tree.append(&mut new_leaves);
+                }
+                1 => {
+                    let updates: Vec<(usize, [u8; 32])> = (0..leaves.len())
+                        .step_by(3)
+                        .map(|index| (index, leaf(step * 100 + index)))
+                        .collect();
+                    for (index, new_leaf) in &updates {
+                        leaves[*index] = *new_leaf;
+                    }
+                    merkle_tree.update_batch(&updates).unwrap();
+                }
+                _ => {
+                    leaves.truncate(leaves.len() - 1);
+                    merkle_tree.pop();
+                }
+            }
+
+            commit_ids.push(merkle_tree.commit().unwrap());
+            states.push(leaves.clone());
+        }
+
+        for (commit_id, leaves) in commit_ids.iter().zip(states.iter()) {
+            let expected_tree = MerkleTree::<Sha256>::from_leaves(leaves);
+            let root = merkle_tree.root_at(commit_id).unwrap();
+            assert_eq!(merkle_tree.leaves_len_at(commit_id), Some(leaves.len()));
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = merkle_tree.proof_at(commit_id, &[index]).unwrap();
+                assert_eq!(
+                    proof.proof_hashes(),
+                    expected_tree.proof(&[index]).proof_hashes()
+                );
+                assert!(proof.verify(root, &[index], &[*leaf], leaves.len()));
+            }
+
+            let indices: Vec<usize> = (0..leaves.len()).step_by(2).collect();
+            let proven_leaves: Vec<[u8; 32]> = indices.iter().map(|i| leaves[*i]).collect();
+            let proof = merkle_tree.proof_at(commit_id, &indices).unwrap();
+            assert!(proof.verify(root, &indices, &proven_leaves, leaves.len()));
+        }
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_unknown_commits_and_indices() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+
+        let commit_id = merkle_tree
+            .append(test_data.leaf_hashes[..2].to_vec().as_mut())
+            .commit()
+            .unwrap();
+        let reverted_commit_id = merkle_tree
+            .append(test_data.leaf_hashes[2..].to_vec().as_mut())
+            .commit()
+            .unwrap();
+        merkle_tree.rollback();
+
+        assert_eq!(
+            merkle_tree
+                .proof_at(&commit_id, &[2])
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::LeafIndexOutOfBounds)
+        );
+        assert_eq!(
+            merkle_tree
+                .proof_at(&reverted_commit_id, &[2])
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::CommitNotFound)
+        );
+    }
+}
+
+pub mod serialization {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, MerkleTree};
+
+    /// Builds a tree with several commits, including a truncation, and uncommitted changes
+    fn tree_with_history() -> MerkleTree<Sha256> {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+
+        merkle_tree
+            .append(test_data.leaf_hashes[..4].to_vec().as_mut())
+            .commit();
+        merkle_tree.truncate(2).commit();
+        merkle_tree
+            .append(test_data.leaf_hashes[2..].to_vec().as_mut())
+            .update(0, Sha256::hash("x".as_bytes()))
+            .unwrap()
+            .commit();
+        merkle_tree
+            .truncate(5)
+            .update(1, Sha256::hash("y".as_bytes()))
+            .unwrap()
+            .insert(Sha256::hash("z".as_bytes()));
+
+        merkle_tree
+    }
+
+    #[test]
+    pub fn should_restore_the_tree_with_history_and_uncommitted_changes() {
+        let mut merkle_tree = tree_with_history();
+        let mut restored_tree = MerkleTree::<Sha256>::from_bytes(&merkle_tree.to_bytes()).unwrap();
+
+        assert_eq!(restored_tree.root(), merkle_tree.root());
+        assert_eq!(restored_tree.leaves(), merkle_tree.leaves());
+        assert_eq!(restored_tree.commits(), merkle_tree.commits());
+        assert_eq!(restored_tree.to_bytes(), merkle_tree.to_bytes());
+
+        restored_tree.commit();
+        merkle_tree.commit();
+        assert_eq!(restored_tree.root(), merkle_tree.root());
+
+        let commits = merkle_tree.commits();
+        for commit in commits.iter().rev() {
+            restored_tree.rollback_to(commit).unwrap();
+            merkle_tree.rollback_to(commit).unwrap();
+            assert_eq!(restored_tree.root(), merkle_tree.root());
+            assert_eq!(restored_tree.leaves(), merkle_tree.leaves());
+            assert_eq!(
+                restored_tree
+                    .proof_at(&commits[0], &[3])
+                    .unwrap()
+                    .proof_hashes(),
+                merkle_tree
+                    .proof_at(&commits[0], &[3])
+                    .unwrap()
+                    .proof_hashes()
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_restore_an_empty_tree() {
+        let merkle_tree = MerkleTree::<Sha256>::new();
+        let restored_tree = MerkleTree::<Sha256>::from_bytes(&merkle_tree.to_bytes()).unwrap();
+
+        assert_eq!(restored_tree.root(), None);
+        assert!(restored_tree.commits().is_empty());
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_corrupted_input() {
+        let bytes = tree_with_history().to_bytes();
+        let kind = |bytes: &[u8]| {
+            MerkleTree::<Sha256>::from_bytes(bytes)
+                .err()
+                .map(|error| error.kind())
+        };
+
+        let mut unsupported_version = bytes.clone();
+        unsupported_version[0] = 2;
+        assert_eq!(
+            kind(&unsupported_version),
+            Some(ErrorKind::UnsupportedFormatVersion)
+        );
+
+        // The root is located after the version, the hash size and the root flag
+        let mut wrong_root = bytes.clone();
+        wrong_root[10] ^= 1;
+        assert_eq!(kind(&wrong_root), Some(ErrorKind::RootMismatch));
+
+        // The first leaf is located after the root and the number of leaves
+        let mut wrong_leaf = bytes.clone();
+        wrong_leaf[50] ^= 1;
+        assert_eq!(kind(&wrong_leaf), Some(ErrorKind::RootMismatch));
+
+        let mut wrong_hash_size = bytes.clone();
+        wrong_hash_size[8] = 48;
+        let mut trailing_bytes = bytes.clone();
+        trailing_bytes.push(0);
+        for malformed in [
+            &bytes[..bytes.len() - 1],
+            &bytes[..100],
+            &wrong_hash_size,
+            &trailing_bytes,
+        ] {
+            assert_eq!(kind(malformed), Some(ErrorKind::InvalidSerializedTree));
+        }
+    }
+}
+
+pub mod large_trees {
+    use core::convert::TryFrom;
+    use core::marker::PhantomData;
+    use rs_merkle::{
+        algorithms::{Bitcoin, Rfc6962, Sha256, SortedPair},
+        Hasher, MerkleTree,
+    };
+
+    /// A hash that can't be shared between threads, which must not prevent building trees
+    /// with or without the `rayon` feature
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub struct LocalHash([u8; 32], PhantomData<*const u8>);
+
+    impl From<LocalHash> for Vec<u8> {
+        fn from(hash: LocalHash) -> Self {
+            hash.0.to_vec()
+        }
+    }
+
+    impl AsRef<[u8]> for LocalHash {
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    impl TryFrom<Vec<u8>> for LocalHash {
+        type Error = Vec<u8>;
+
+        fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+            Ok(LocalHash(<[u8; 32]>::try_from(bytes)?, PhantomData))
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct LocalSha256 {}
+
+    impl Hasher for LocalSha256 {
+        type Hash = LocalHash;
+
+        fn hash(data: &[u8]) -> LocalHash {
+            LocalHash(Sha256::hash(data), PhantomData)
+        }
+    }
+
+    fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| Sha256::hash(i.to_string().as_bytes()))
+            .collect()
+    }
+
+    /// Calculates the root one node at a time, without any of the tree machinery
+    fn sequential_root<T: Hasher>(leaves: &[T::Hash]) -> T::Hash {
+        let mut layer = leaves.to_vec();
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| T::concat_and_hash(&pair[0], pair.get(1)))
+                .collect();
+        }
+        layer[0]
+    }
+
+    #[test]
+    pub fn should_build_the_same_root_as_sequential_hashing() {
+        let leaves = leaf_hashes(10_007);
+
+        assert_eq!(
+            MerkleTree::<Sha256>::from_leaves(&leaves).root(),
+            Some(sequential_root::<Sha256>(&leaves))
+        );
+        assert_eq!(
+            MerkleTree::<Bitcoin>::from_leaves(&leaves).root(),
+            Some(sequential_root::<Bitcoin>(&leaves))
+        );
+    }
+
+    #[test]
+    pub fn should_build_the_same_root_as_sequential_hashing_with_wrapped_hashers() {
+        let leaves = leaf_hashes(10_007);
+
+        assert_eq!(
+            MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves).root(),
+            Some(sequential_root::<Rfc6962<Sha256>>(&leaves))
+        );
+        assert_eq!(
+            MerkleTree::<SortedPair<Sha256>>::from_leaves(&leaves).root(),
+            Some(sequential_root::<SortedPair<Sha256>>(&leaves))
+        );
+        assert_eq!(
+            MerkleTree::<SortedPair<Rfc6962<Bitcoin>>>::from_leaves(&leaves).root(),
+            Some(sequential_root::<SortedPair<Rfc6962<Bitcoin>>>(&leaves))
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    pub fn should_hash_the_layers_of_wrapped_hashers_in_parallel() {
+        let leaves = leaf_hashes(10_007);
+
+        assert_eq!(
+            Rfc6962::<Sha256>::hash_layer(&leaves),
+            rs_merkle::par_hash_layer::<Rfc6962<Sha256>>(&leaves)
+        );
+        assert_eq!(
+            Sha256::hash_layer_with::<SortedPair<Sha256>>(&leaves),
+            SortedPair::<Sha256>::hash_layer(&leaves)
+        );
+    }
+
+    #[test]
+    pub fn should_build_trees_of_hashes_that_are_not_thread_safe() {
+        let leaves: Vec<LocalHash> = leaf_hashes(10_007)
+            .into_iter()
+            .map(|leaf| LocalHash(leaf, PhantomData))
+            .collect();
+        let merkle_tree = MerkleTree::<LocalSha256>::from_leaves(&leaves);
+        let root = merkle_tree.root().unwrap();
+
+        assert_eq!(root, sequential_root::<LocalSha256>(&leaves));
+        assert_eq!(
+            root.0,
+            MerkleTree::<Sha256>::from_leaves(&leaf_hashes(10_007))
+                .root()
+                .unwrap()
+        );
+        assert!(merkle_tree.proof(&[0, 5_000]).verify(
+            root,
+            &[0, 5_000],
+            &[leaves[0], leaves[5_000]],
+            leaves.len()
+        ));
+        assert_eq!(
+            MerkleTree::<SortedPair<LocalSha256>>::from_leaves(&leaves).root(),
+            Some(sequential_root::<SortedPair<LocalSha256>>(&leaves))
+        );
+    }
+
+    #[test]
+    pub fn should_commit_and_verify_the_same_root_as_sequential_hashing() {
+        let leaves = leaf_hashes(10_007);
+        let mut merkle_tree = MerkleTree::<Sha256>::new();
+
+        for chunk in leaves.chunks(3_000) {
+            merkle_tree.append(chunk.to_vec().as_mut()).commit();
+        }
+        let root = merkle_tree.root().unwrap();
+        let indices: Vec<usize> = (0..leaves.len()).step_by(2).collect();
+        let proven_leaves: Vec<[u8; 32]> = indices.iter().map(|index| leaves[*index]).collect();
+
+        assert_eq!(root, sequential_root::<Sha256>(&leaves));
+        assert!(merkle_tree
+            .proof(&indices)
+            .verify(root, &indices, &proven_leaves, leaves.len()));
+    }
+}
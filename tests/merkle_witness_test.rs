@@ -0,0 +1,114 @@
+use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree, MerkleWitness};
+
+fn leaf_hashes<T: Hasher>(count: usize) -> Vec<T::Hash> {
+    (0..count)
+        .map(|i| T::hash(i.to_string().as_bytes()))
+        .collect()
+}
+
+/// Marks every leaf of trees of different sizes and checks that the witnesses produce the same
+/// proofs as the tree after every append
+fn assert_witnesses_match_the_tree<T: Hasher>() {
+    let leaves = leaf_hashes::<T>(40);
+
+    for marked_len in 1..=17 {
+        let mut merkle_tree = MerkleTree::<T>::from_leaves(&leaves[..marked_len]);
+        let mut witnesses: Vec<MerkleWitness<T>> = (0..marked_len)
+            .map(|index| MerkleWitness::mark(&merkle_tree, index).unwrap())
+            .collect();
+
+        for leaves_len in marked_len..=leaves.len() {
+            let root = merkle_tree.root().unwrap();
+
+            for (index, witness) in witnesses.iter().enumerate() {
+                let proof = witness.proof();
+                assert!(
+                    proof.proof_hashes() == merkle_tree.proof(&[index]).proof_hashes(),
+                    "leaf {} marked at {} leaves, tree of {} leaves",
+                    index,
+                    marked_len,
+                    leaves_len
+                );
+                assert!(proof.verify(root, &[index], &[leaves[index]], leaves_len));
+                assert_eq!(witness.leaves_len(), leaves_len);
+            }
+
+            if let Some(leaf) = leaves.get(leaves_len) {
+                merkle_tree.insert(*leaf).commit();
+                for witness in witnesses.iter_mut() {
+                    witness.append(*leaf);
+                }
+            }
+        }
+    }
+}
+
+pub mod proof {
+    use crate::assert_witnesses_match_the_tree;
+    use rs_merkle::{
+        algorithms::{Bitcoin, Sha256},
+        Hasher, OddNodePolicy,
+    };
+
+    #[derive(Clone)]
+    struct ZeroPaddedSha256 {}
+
+    impl Hasher for ZeroPaddedSha256 {
+        type Hash = [u8; 32];
+
+        const ODD_NODE_POLICY: OddNodePolicy = OddNodePolicy::HashWithZero;
+
+        fn hash(data: &[u8]) -> [u8; 32] {
+            Sha256::hash(data)
+        }
+    }
+
+    #[test]
+    pub fn should_stay_up_to_date_with_the_tree() {
+        assert_witnesses_match_the_tree::<Sha256>();
+    }
+
+    #[test]
+    pub fn should_follow_the_odd_node_policy_of_the_hasher() {
+        assert_witnesses_match_the_tree::<Bitcoin>();
+        assert_witnesses_match_the_tree::<ZeroPaddedSha256>();
+    }
+}
+
+pub mod mark {
+    use crate::leaf_hashes;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, MerkleTree, MerkleWitness};
+
+    #[test]
+    pub fn should_mark_only_committed_leaves() {
+        let leaves = leaf_hashes::<Sha256>(5);
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..3]);
+        merkle_tree.append(leaves[3..].to_vec().as_mut());
+
+        let kind = MerkleWitness::mark(&merkle_tree, 3)
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::LeafIndexOutOfBounds));
+
+        let witness = MerkleWitness::mark(&merkle_tree, 2).unwrap();
+        assert_eq!(witness.leaf_index(), 2);
+        assert_eq!(witness.leaf(), leaves[2]);
+        assert_eq!(witness.leaves_len(), 3);
+    }
+}
+
+#[test]
+pub fn should_keep_logarithmic_state() {
+    let leaves = leaf_hashes::<Sha256>(1000);
+    let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..1]);
+    let mut witness = MerkleWitness::mark(&merkle_tree, 0).unwrap();
+    for leaf in &leaves[1..] {
+        witness.append(*leaf);
+    }
+
+    let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    assert_eq!(
+        witness.proof().proof_hashes(),
+        merkle_tree.proof(&[0]).proof_hashes()
+    );
+}
@@ -0,0 +1,354 @@
+use rs_merkle::{algorithms::Sha256, Hasher};
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
+fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+    (0..count)
+        .map(|i| Sha256::hash(i.to_string().as_bytes()))
+        .collect()
+}
+
+/// Returns an empty directory for the test, removing what's left from the previous runs
+#[cfg(feature = "std")]
+fn test_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!(
+        "rs_merkle_node_store_test_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&directory);
+    directory
+}
+
+pub mod memory_store {
+    use rs_merkle::{
+        algorithms::Sha256,
+        node_stores::{MemoryStore, NodeBatch},
+        ErrorKind, NodeStore,
+    };
+
+    #[test]
+    pub fn should_not_apply_batches_that_leave_gaps() {
+        let leaves = crate::leaf_hashes(3);
+        let mut store = MemoryStore::<Sha256>::new();
+        store
+            .write_batch(NodeBatch::new(vec![1], vec![(0, 0, leaves[0])]))
+            .unwrap();
+
+        for batch in [
+            NodeBatch::new(vec![3], vec![(0, 2, leaves[2])]),
+            NodeBatch::new(vec![2], vec![(0, 2, leaves[2])]),
+            NodeBatch::new(vec![1, 1], vec![(0, 0, leaves[1])]),
+        ] {
+            let kind = store.write_batch(batch).err().map(|error| error.kind());
+            assert_eq!(kind, Some(ErrorKind::NodeStoreError));
+        }
+
+        assert_eq!(store.layers(), &[vec![leaves[0]]]);
+    }
+}
+
+#[cfg(feature = "std")]
+pub mod file_store {
+    use crate::{leaf_hashes, test_directory};
+    use rs_merkle::{
+        algorithms::Sha256,
+        node_stores::{FileStore, NodeBatch},
+        ErrorKind, Hasher, MerkleTree, NodeStore,
+    };
+
+    /// Makes the changes of the given step and commits them
+    fn commit_step<S: NodeStore<Sha256>>(
+        merkle_tree: &mut MerkleTree<Sha256, S>,
+        step: usize,
+        leaves: &[[u8; 32]],
+    ) {
+        match step {
+            0 => merkle_tree.append(leaves[..9].to_vec().as_mut()),
+            1 => merkle_tree.update(4, leaves[11]).unwrap(),
+            2 => merkle_tree.truncate(6),
+            _ => merkle_tree.append(leaves[9..].to_vec().as_mut()),
+        };
+        merkle_tree.try_commit().unwrap();
+    }
+
+    #[test]
+    pub fn should_build_the_same_tree_as_the_memory_store() {
+        let directory = test_directory("same_tree");
+        let leaves = leaf_hashes(13);
+        let mut file_tree = MerkleTree::with_store(FileStore::<Sha256>::open(&directory).unwrap());
+        let mut memory_tree = MerkleTree::<Sha256>::new();
+
+        for step in 0..4 {
+            commit_step(&mut file_tree, step, &leaves);
+            commit_step(&mut memory_tree, step, &leaves);
+
+            assert_eq!(file_tree.root(), memory_tree.root());
+            assert_eq!(file_tree.leaves(), memory_tree.leaves());
+            assert_eq!(
+                file_tree.proof(&[1, 5]).proof_hashes(),
+                memory_tree.proof(&[1, 5]).proof_hashes()
+            );
+        }
+
+        let commits = file_tree.commits();
+        file_tree.rollback_to(&commits[1]).unwrap();
+        memory_tree.rollback_to(&commits[1]).unwrap();
+        assert_eq!(file_tree.root(), memory_tree.root());
+        assert_eq!(file_tree.leaves(), memory_tree.leaves());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    pub fn should_reopen_the_committed_tree() {
+        let directory = test_directory("reopen");
+        let leaves = leaf_hashes(10);
+
+        let mut merkle_tree =
+            MerkleTree::with_store(FileStore::<Sha256>::open(&directory).unwrap());
+        merkle_tree
+            .append(leaves.clone().as_mut())
+            .try_commit()
+            .unwrap();
+        // Uncommitted changes aren't written to the store
+        merkle_tree.insert(leaves[0]);
+        drop(merkle_tree);
+
+        let mut merkle_tree =
+            MerkleTree::with_store(FileStore::<Sha256>::open(&directory).unwrap());
+        assert_eq!(
+            merkle_tree.root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves).root()
+        );
+        assert_eq!(merkle_tree.leaves(), Some(leaves.clone()));
+
+        merkle_tree.truncate(3).try_commit().unwrap();
+        drop(merkle_tree);
+
+        let merkle_tree = MerkleTree::with_store(FileStore::<Sha256>::open(&directory).unwrap());
+        assert_eq!(
+            merkle_tree.root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves[..3]).root()
+        );
+        assert_eq!(merkle_tree.depth(), 2);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    pub fn should_complete_a_journaled_batch_on_open() {
+        let directory = test_directory("journal");
+        let leaves = leaf_hashes(4);
+
+        let mut merkle_tree =
+            MerkleTree::with_store(FileStore::<Sha256>::open(&directory).unwrap());
+        merkle_tree
+            .append(leaves.clone().as_mut())
+            .try_commit()
+            .unwrap();
+        let two_leaves_root = MerkleTree::<Sha256>::from_leaves(&leaves[..2])
+            .root()
+            .unwrap();
+        drop(merkle_tree);
+
+        // The store stopped after journaling the truncation to two leaves
+        let mut journal = Vec::new();
+        for number in [3u64, 2, 1, 1, 1, 2, 0] {
+            journal.extend_from_slice(&number.to_be_bytes());
+        }
+        journal.extend_from_slice(&two_leaves_root);
+        std::fs::write(directory.join("journal"), journal).unwrap();
+        // An incomplete journal is never applied
+        std::fs::write(directory.join("journal.tmp"), [1u8, 2, 3]).unwrap();
+
+        let merkle_tree = MerkleTree::with_store(FileStore::<Sha256>::open(&directory).unwrap());
+        assert_eq!(merkle_tree.root(), Some(two_leaves_root));
+        assert_eq!(merkle_tree.leaves(), Some(leaves[..2].to_vec()));
+        assert!(!directory.join("journal").exists());
+        assert!(!directory.join("journal.tmp").exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    pub fn should_refuse_nodes_until_a_partially_applied_batch_is_completed() {
+        let directory = test_directory("partial_batch");
+        let leaves = leaf_hashes(2);
+        let parent = Sha256::concat_and_hash(&leaves[0], Some(&leaves[1]));
+        let mut store = FileStore::<Sha256>::open(&directory).unwrap();
+        store
+            .write_batch(NodeBatch::new(vec![1], vec![(0, 0, leaves[0])]))
+            .unwrap();
+
+        // The file of the new layer can't be created, so the batch fails after it's journaled
+        // and the leaves layer is resized
+        std::fs::create_dir(directory.join("layer_1")).unwrap();
+        let batch = NodeBatch::new(vec![2, 1], vec![(0, 1, leaves[1]), (1, 0, parent)]);
+        assert!(store.write_batch(batch.clone()).is_err());
+
+        let kind = store.get(0, 0).err().map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::NodeStoreError));
+        assert!(store.put(0, 1, leaves[1]).is_err());
+        assert!(store.write_batch(batch).is_err());
+        drop(store);
+
+        std::fs::remove_dir(directory.join("layer_1")).unwrap();
+        let store = FileStore::<Sha256>::open(&directory).unwrap();
+        assert_eq!(store.layers_len(), 2);
+        assert_eq!(store.get(0, 0).unwrap(), Some(leaves[0]));
+        assert_eq!(store.get(0, 1).unwrap(), Some(leaves[1]));
+        assert_eq!(store.get(1, 0).unwrap(), Some(parent));
+        assert!(!directory.join("journal").exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    pub fn should_reject_malformed_journals() {
+        let directory = test_directory("malformed_journal");
+        let leaves = leaf_hashes(1);
+        FileStore::<Sha256>::open(&directory)
+            .unwrap()
+            .write_batch(NodeBatch::new(vec![1], vec![(0, 0, leaves[0])]))
+            .unwrap();
+
+        let journals: [&[u64]; 3] = [
+            // A node of a layer that isn't in the batch
+            &[1, 2, 1, 5, 1],
+            // A node past the end of its layer
+            &[1, 2, 1, 0, 2],
+            // A layer that grows without its nodes being written
+            &[1, u64::MAX >> 8, 0],
+        ];
+        for numbers in journals {
+            let mut journal = Vec::new();
+            for number in numbers {
+                journal.extend_from_slice(&number.to_be_bytes());
+            }
+            if numbers.len() > 3 {
+                journal.extend_from_slice(&leaves[0]);
+            }
+            std::fs::write(directory.join("journal"), journal).unwrap();
+
+            let kind = FileStore::<Sha256>::open(&directory)
+                .err()
+                .map(|error| error.kind());
+            assert_eq!(kind, Some(ErrorKind::NodeStoreError), "{:?}", numbers);
+        }
+        assert_eq!(
+            std::fs::metadata(directory.join("layer_0")).unwrap().len(),
+            32
+        );
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
+
+pub mod failing_store {
+    use rs_merkle::{
+        algorithms::Sha256,
+        node_stores::{MemoryStore, NodeBatch},
+        Error, ErrorKind, MerkleTree, NodeStore,
+    };
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Keeps the nodes in memory, but fails to write batches while `fail_writes` is set
+    #[derive(Default)]
+    struct FailingStore {
+        store: MemoryStore<Sha256>,
+        fail_writes: Rc<Cell<bool>>,
+    }
+
+    impl NodeStore<Sha256> for FailingStore {
+        fn get(&self, layer_index: usize, node_index: usize) -> Result<Option<[u8; 32]>, Error> {
+            self.store.get(layer_index, node_index)
+        }
+
+        fn put(
+            &mut self,
+            layer_index: usize,
+            node_index: usize,
+            hash: [u8; 32],
+        ) -> Result<(), Error> {
+            self.store.put(layer_index, node_index, hash)
+        }
+
+        fn layer_len(&self, layer_index: usize) -> usize {
+            self.store.layer_len(layer_index)
+        }
+
+        fn layers_len(&self) -> usize {
+            self.store.layers_len()
+        }
+
+        fn write_batch(&mut self, batch: NodeBatch<Sha256>) -> Result<(), Error> {
+            if self.fail_writes.get() {
+                return Err(Error::node_store_error(String::from("disk is full")));
+            }
+            self.store.write_batch(batch)
+        }
+    }
+
+    fn failing_tree() -> (MerkleTree<Sha256, FailingStore>, Rc<Cell<bool>>) {
+        let fail_writes = Rc::new(Cell::new(false));
+        let merkle_tree = MerkleTree::with_store(FailingStore {
+            store: MemoryStore::new(),
+            fail_writes: fail_writes.clone(),
+        });
+
+        (merkle_tree, fail_writes)
+    }
+
+    #[test]
+    pub fn should_keep_the_changes_uncommitted_if_the_store_fails() {
+        let leaves = crate::leaf_hashes(3);
+        let (mut merkle_tree, fail_writes) = failing_tree();
+        merkle_tree.append(leaves.clone().as_mut());
+
+        fail_writes.set(true);
+        let kind = merkle_tree.try_commit().err().map(|error| error.kind());
+
+        assert_eq!(kind, Some(ErrorKind::NodeStoreError));
+        assert_eq!(merkle_tree.root(), None);
+        assert_eq!(
+            merkle_tree.uncommitted_root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves).root()
+        );
+    }
+
+    #[test]
+    pub fn should_keep_the_commit_if_the_store_fails_to_roll_it_back() {
+        let leaves = crate::leaf_hashes(3);
+        let (mut merkle_tree, fail_writes) = failing_tree();
+        merkle_tree.append(leaves[..2].to_vec().as_mut()).commit();
+        merkle_tree.insert(leaves[2]).commit();
+
+        fail_writes.set(true);
+        let kind = merkle_tree.try_rollback().err().map(|error| error.kind());
+
+        assert_eq!(kind, Some(ErrorKind::NodeStoreError));
+        assert_eq!(merkle_tree.commits().len(), 2);
+        assert_eq!(
+            merkle_tree.root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves).root()
+        );
+
+        fail_writes.set(false);
+        merkle_tree.try_rollback().unwrap();
+        assert_eq!(
+            merkle_tree.root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves[..2]).root()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "disk is full")]
+    pub fn should_panic_on_commit_if_the_store_fails() {
+        let (mut merkle_tree, fail_writes) = failing_tree();
+        fail_writes.set(true);
+
+        merkle_tree.insert(crate::leaf_hashes(1)[0]).commit();
+    }
+}
@@ -0,0 +1,194 @@
+// Transaction ids and merkle roots are in the display byte order, as shown by block explorers
+struct Block {
+    txids: Vec<&'static str>,
+    merkle_root: &'static str,
+}
+
+fn blocks() -> Vec<Block> {
+    vec![
+        // Genesis block
+        Block {
+            txids: vec!["4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"],
+            merkle_root: "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+        },
+        // Block 170
+        Block {
+            txids: vec![
+                "b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082",
+                "f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16",
+            ],
+            merkle_root: "7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff",
+        },
+        // Block 100000
+        Block {
+            txids: vec![
+                "8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87",
+                "fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4",
+                "6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4",
+                "e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d",
+            ],
+            merkle_root: "f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766",
+        },
+    ]
+}
+
+fn internal_hash(display_hex: &str) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&display_hex[i * 2..i * 2 + 2], 16).unwrap();
+    }
+    hash.reverse();
+    hash
+}
+
+fn display_hex(hash: [u8; 32]) -> String {
+    let mut reversed = hash;
+    reversed.reverse();
+    rs_merkle::utils::collections::to_hex_string(&reversed)
+}
+
+pub mod duplicate {
+    use crate::{blocks, display_hex, internal_hash};
+    use rs_merkle::{algorithms::Bitcoin, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_return_bitcoin_block_merkle_roots() {
+        for block in blocks() {
+            let txids: Vec<[u8; 32]> = block.txids.iter().map(|x| internal_hash(x)).collect();
+            let merkle_tree = MerkleTree::<Bitcoin>::from_leaves(&txids);
+
+            assert_eq!(
+                merkle_tree.root().map(display_hex),
+                Some(block.merkle_root.to_string())
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_verify_spv_proofs() {
+        for block in blocks() {
+            let txids: Vec<[u8; 32]> = block.txids.iter().map(|x| internal_hash(x)).collect();
+            let merkle_tree = MerkleTree::<Bitcoin>::from_leaves(&txids);
+            let merkle_root = internal_hash(block.merkle_root);
+
+            for index in 0..txids.len() {
+                let proof = merkle_tree.proof(&[index]);
+                assert!(proof.verify(merkle_root, &[index], &txids[index..=index], txids.len()));
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_duplicate_the_last_node_of_odd_layers() {
+        let txids: Vec<[u8; 32]> = blocks()[2].txids.iter().map(|x| internal_hash(x)).collect();
+        let merkle_tree = MerkleTree::<Bitcoin>::from_leaves(&txids[..3]);
+
+        let expected_root = Bitcoin::concat_and_hash(
+            &Bitcoin::concat_and_hash(&txids[0], Some(&txids[1])),
+            Some(&Bitcoin::concat_and_hash(&txids[2], Some(&txids[2]))),
+        );
+        assert_eq!(merkle_tree.root(), Some(expected_root));
+
+        for index in 0..3 {
+            let proof = merkle_tree.proof(&[index]);
+            assert!(proof.verify(expected_root, &[index], &txids[index..=index], 3));
+        }
+    }
+
+    #[test]
+    pub fn should_have_the_same_root_with_a_duplicated_last_transaction() {
+        // CVE-2012-2459: a block with the transactions [a, b, c] has the same merkle root as
+        // the block with the transactions [a, b, c, c]
+        let txids: Vec<[u8; 32]> = blocks()[2].txids.iter().map(|x| internal_hash(x)).collect();
+        let mut duplicated_txids = txids[..3].to_vec();
+        duplicated_txids.push(txids[2]);
+
+        let merkle_tree = MerkleTree::<Bitcoin>::from_leaves(&txids[..3]);
+        let duplicated_merkle_tree = MerkleTree::<Bitcoin>::from_leaves(&duplicated_txids);
+        assert_eq!(merkle_tree.root(), duplicated_merkle_tree.root());
+
+        // A proof for the duplicated transaction verifies against the original root, so the leaf
+        // count has to be checked separately
+        let proof = duplicated_merkle_tree.proof(&[3]);
+        assert!(proof.verify(merkle_tree.root().unwrap(), &[3], &txids[2..3], 4));
+    }
+
+    #[test]
+    pub fn should_keep_the_root_when_appending_to_a_tree() {
+        let txids: Vec<[u8; 32]> = blocks()[2].txids.iter().map(|x| internal_hash(x)).collect();
+        let mut merkle_tree = MerkleTree::<Bitcoin>::new();
+
+        for txid in &txids {
+            merkle_tree.insert(*txid).commit();
+        }
+
+        assert_eq!(
+            merkle_tree.root().map(display_hex),
+            Some(blocks()[2].merkle_root.to_string())
+        );
+    }
+
+    #[test]
+    pub fn should_match_a_rebuilt_tree_after_each_commit() {
+        let leaves: Vec<[u8; 32]> = (0..17)
+            .map(|i: usize| Bitcoin::hash(i.to_string().as_bytes()))
+            .collect();
+        let mut merkle_tree = MerkleTree::<Bitcoin>::new();
+
+        for (i, leaf) in leaves.iter().enumerate() {
+            merkle_tree.insert(*leaf).commit();
+            let leaves_count = i + 1;
+            let rebuilt_tree = MerkleTree::<Bitcoin>::from_leaves(&leaves[..leaves_count]);
+            assert_eq!(merkle_tree.root(), rebuilt_tree.root());
+
+            let root = rebuilt_tree.root().unwrap();
+            for index in 0..leaves_count {
+                let proof = merkle_tree.proof(&[index]);
+                assert!(proof.verify(root, &[index], &leaves[index..=index], leaves_count));
+            }
+        }
+    }
+}
+
+pub mod hash_with_zero {
+    use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree, OddNodePolicy};
+
+    #[derive(Clone)]
+    struct ZeroPaddedSha256 {}
+
+    impl Hasher for ZeroPaddedSha256 {
+        type Hash = [u8; 32];
+
+        const ODD_NODE_POLICY: OddNodePolicy = OddNodePolicy::HashWithZero;
+
+        fn hash(data: &[u8]) -> [u8; 32] {
+            Sha256::hash(data)
+        }
+    }
+
+    #[test]
+    pub fn should_hash_the_last_node_of_odd_layers_with_zeros() {
+        let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|x| Sha256::hash(x.as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<ZeroPaddedSha256>::from_leaves(&leaves);
+
+        let zero = [0u8; 32];
+        let ab = Sha256::concat_and_hash(&leaves[0], Some(&leaves[1]));
+        let cd = Sha256::concat_and_hash(&leaves[2], Some(&leaves[3]));
+        let e0 = Sha256::concat_and_hash(&leaves[4], Some(&zero));
+        let abcd = Sha256::concat_and_hash(&ab, Some(&cd));
+        let e000 = Sha256::concat_and_hash(&e0, Some(&zero));
+        let expected_root = Sha256::concat_and_hash(&abcd, Some(&e000));
+        assert_eq!(merkle_tree.root(), Some(expected_root));
+
+        for index in 0..leaves.len() {
+            let proof = merkle_tree.proof(&[index]);
+            assert!(proof.verify(expected_root, &[index], &leaves[index..=index], 5));
+        }
+
+        let proof = merkle_tree.proof(&[1, 4]);
+        assert!(proof.verify(expected_root, &[1, 4], &[leaves[1], leaves[4]], 5));
+    }
+}
@@ -0,0 +1,180 @@
+// Test vectors from the Certificate Transparency reference implementation
+const LEAVES: [&[u8]; 8] = [
+    b"",
+    b"\x00",
+    b"\x10",
+    b"\x20\x21",
+    b"\x30\x31",
+    b"\x40\x41\x42\x43",
+    b"\x50\x51\x52\x53\x54\x55\x56\x57",
+    b"\x60\x61\x62\x63\x64\x65\x66\x67\x68\x69\x6a\x6b\x6c\x6d\x6e\x6f",
+];
+
+const ROOTS: [&str; 8] = [
+    "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+    "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+    "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+    "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+    "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+    "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+    "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+];
+
+fn leaf_hashes() -> Vec<[u8; 32]> {
+    use rs_merkle::{
+        algorithms::{Rfc6962, Sha256},
+        Hasher,
+    };
+
+    LEAVES.iter().map(|x| Rfc6962::<Sha256>::hash(x)).collect()
+}
+
+pub mod root {
+    use crate::{leaf_hashes, ROOTS};
+    use rs_merkle::{
+        algorithms::{Rfc6962, Sha256},
+        MerkleTree,
+    };
+
+    #[test]
+    pub fn should_return_rfc6962_roots() {
+        let leaves = leaf_hashes();
+
+        for (i, expected_root) in ROOTS.iter().enumerate() {
+            let merkle_tree = MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves[..=i]);
+            assert_eq!(merkle_tree.root_hex(), Some(expected_root.to_string()));
+        }
+    }
+
+    #[test]
+    pub fn should_return_rfc6962_roots_when_appending() {
+        let leaves = leaf_hashes();
+        let mut merkle_tree = MerkleTree::<Rfc6962<Sha256>>::new();
+
+        for (leaf, expected_root) in leaves.iter().zip(ROOTS.iter()) {
+            merkle_tree.insert(*leaf).commit();
+            assert_eq!(merkle_tree.root_hex(), Some(expected_root.to_string()));
+        }
+    }
+}
+
+pub mod proof {
+    use crate::{leaf_hashes, ROOTS};
+    use rs_merkle::{
+        algorithms::{Rfc6962, Sha256},
+        utils, MerkleTree,
+    };
+
+    #[test]
+    pub fn should_return_rfc6962_audit_paths() {
+        let leaves = leaf_hashes();
+        let merkle_tree = MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves);
+
+        let expected_paths = [
+            (
+                0,
+                vec![
+                    "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+                    "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+                    "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+                ],
+            ),
+            (
+                5,
+                vec![
+                    "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+                    "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+                    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+                ],
+            ),
+        ];
+
+        for (index, expected_path) in expected_paths {
+            let proof = merkle_tree.proof(&[index]);
+            assert_eq!(proof.proof_hashes_hex(), expected_path);
+        }
+    }
+
+    #[test]
+    pub fn should_verify_rfc6962_proofs() {
+        let leaves = leaf_hashes();
+
+        for tree_size in 1..=leaves.len() {
+            let merkle_tree = MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves[..tree_size]);
+
+            for index in 0..tree_size {
+                let proof = merkle_tree.proof(&[index]);
+                let root = proof
+                    .root(&[index], &leaves[index..=index], tree_size)
+                    .unwrap();
+                assert_eq!(
+                    utils::collections::to_hex_string(&root),
+                    ROOTS[tree_size - 1]
+                );
+            }
+        }
+    }
+}
+
+pub mod consistency_proof {
+    use crate::leaf_hashes;
+    use rs_merkle::{
+        algorithms::{Rfc6962, Sha256},
+        MerkleTree,
+    };
+
+    #[test]
+    pub fn should_return_rfc6962_consistency_proofs() {
+        let leaves = leaf_hashes();
+        let merkle_tree = MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves);
+
+        let expected_proofs = [
+            (
+                1,
+                vec![
+                    "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+                    "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+                    "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+                ],
+            ),
+            (
+                6,
+                vec![
+                    "0ebc5d3437fbe2db158b9f126a1d118e308181031d0a949f8dededebc558ef6a",
+                    "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+                    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+                ],
+            ),
+        ];
+
+        for (old_size, expected_proof) in expected_proofs {
+            let proof = merkle_tree.consistency_proof(old_size, 8).unwrap();
+            assert_eq!(proof.proof_hashes_hex(), expected_proof);
+        }
+
+        for old_size in 1..=8 {
+            let old_root = MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves[..old_size])
+                .root()
+                .unwrap();
+            let proof = merkle_tree.consistency_proof(old_size, 8).unwrap();
+            assert!(proof.verify(old_root, merkle_tree.root().unwrap(), old_size, 8));
+        }
+
+        let proof = merkle_tree.consistency_proof(2, 5).unwrap();
+        assert!(proof.verify(
+            MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves[..2])
+                .root()
+                .unwrap(),
+            MerkleTree::<Rfc6962<Sha256>>::from_leaves(&leaves[..5])
+                .root()
+                .unwrap(),
+            2,
+            5
+        ));
+        assert_eq!(
+            proof.proof_hashes_hex()[0],
+            "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "serde")]
+
+use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree};
+
+fn leaf_hashes(count: usize) -> Vec<[u8; 32]> {
+    (0..count)
+        .map(|i| Sha256::hash(i.to_string().as_bytes()))
+        .collect()
+}
+
+fn to_cbor<V: serde::Serialize>(value: &V) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(value, &mut bytes).unwrap();
+    bytes
+}
+
+/// Returns a tree with a couple of commits and uncommitted changes
+fn tree_with_history() -> MerkleTree<Sha256> {
+    let leaves = leaf_hashes(7);
+    let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..5]);
+    merkle_tree.truncate(3).insert(leaves[5]).commit();
+    merkle_tree.update(0, leaves[6]).unwrap();
+    merkle_tree
+}
+
+pub mod merkle_proof {
+    use crate::{leaf_hashes, to_cbor};
+    use rs_merkle::{algorithms::Sha256, utils, MerkleProof, MerkleTree};
+
+    #[test]
+    pub fn should_write_hashes_as_hex_strings_to_json() {
+        let leaves = leaf_hashes(4);
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let proof = merkle_tree.proof(&[0]);
+
+        let json = serde_json::to_string(&proof).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"proof_hashes":["{}","{}"],"metadata":{{"leaf_indices":[0],"total_leaves_count":4}}}}"#,
+                utils::collections::to_hex_string(&proof.proof_hashes()[0]),
+                utils::collections::to_hex_string(&proof.proof_hashes()[1])
+            )
+        );
+
+        let parsed_proof: MerkleProof<Sha256> = serde_json::from_str(&json).unwrap();
+        let root = merkle_tree.root().unwrap();
+        assert!(parsed_proof.verify_with_metadata(root, &[leaves[0]]));
+    }
+
+    #[test]
+    pub fn should_read_proofs_without_metadata() {
+        let leaves = leaf_hashes(4);
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let proof = MerkleProof::<Sha256>::new(merkle_tree.proof(&[0]).proof_hashes().to_vec());
+
+        let json = serde_json::to_string(&proof).unwrap();
+        assert!(json.ends_with(r#","metadata":null}"#));
+
+        for json in [json.clone(), json.replace(r#","metadata":null"#, "")] {
+            let parsed_proof: MerkleProof<Sha256> = serde_json::from_str(&json).unwrap();
+            let root = merkle_tree.root().unwrap();
+            assert_eq!(parsed_proof.leaf_indices(), None);
+            assert!(parsed_proof.verify(root, &[0], &[leaves[0]], 4));
+        }
+    }
+
+    #[test]
+    pub fn should_write_hashes_as_bytes_to_cbor() {
+        let leaves = leaf_hashes(4);
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let proof = merkle_tree.proof(&[0]);
+
+        let cbor = to_cbor(&proof);
+        // A byte string of 32 bytes followed by the hash itself
+        let mut encoded_hash = vec![0x58, 32];
+        encoded_hash.extend_from_slice(&proof.proof_hashes()[0]);
+        assert!(cbor
+            .windows(encoded_hash.len())
+            .any(|window| window == encoded_hash.as_slice()));
+
+        let parsed_proof: MerkleProof<Sha256> = ciborium::from_reader(cbor.as_slice()).unwrap();
+        assert_eq!(parsed_proof.proof_hashes(), proof.proof_hashes());
+        assert_eq!(parsed_proof.leaf_indices(), Some(&[0][..]));
+        assert_eq!(parsed_proof.total_leaves_count(), Some(4));
+    }
+
+    #[test]
+    pub fn should_not_deserialize_invalid_hashes() {
+        for json in [
+            r#"{"proof_hashes":["abcd"]}"#,
+            r#"{"proof_hashes":["not a hex string"]}"#,
+        ] {
+            assert!(serde_json::from_str::<MerkleProof<Sha256>>(json).is_err());
+        }
+    }
+}
+
+pub mod partial_tree {
+    use crate::{leaf_hashes, to_cbor};
+    use rs_merkle::{algorithms::Sha256, PartialTree};
+
+    #[test]
+    pub fn should_restore_the_layers() {
+        let partial_tree = PartialTree::<Sha256>::from_leaves(&leaf_hashes(5)).unwrap();
+
+        let json = serde_json::to_string(&partial_tree).unwrap();
+        let parsed_tree: PartialTree<Sha256> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed_tree.layers(), partial_tree.layers());
+
+        let cbor = to_cbor(&partial_tree);
+        let parsed_tree: PartialTree<Sha256> = ciborium::from_reader(cbor.as_slice()).unwrap();
+        assert_eq!(parsed_tree.layers(), partial_tree.layers());
+    }
+
+    #[test]
+    pub fn should_not_deserialize_unsorted_layers() {
+        let hash = "00".repeat(32);
+        let json = format!(r#"{{"layers":[[[1,"{}"],[0,"{}"]]]}}"#, hash, hash);
+
+        assert!(serde_json::from_str::<PartialTree<Sha256>>(&json).is_err());
+    }
+}
+
+pub mod merkle_tree {
+    use crate::{to_cbor, tree_with_history};
+    use rs_merkle::{algorithms::Sha256, MerkleTree};
+
+    #[test]
+    pub fn should_restore_the_tree_with_history_and_uncommitted_changes() {
+        let merkle_tree = tree_with_history();
+        let json = serde_json::to_string(&merkle_tree).unwrap();
+        let cbor = to_cbor(&merkle_tree);
+
+        for mut parsed_tree in [
+            serde_json::from_str::<MerkleTree<Sha256>>(&json).unwrap(),
+            ciborium::from_reader::<MerkleTree<Sha256>, _>(cbor.as_slice()).unwrap(),
+        ] {
+            assert_eq!(parsed_tree.to_bytes(), merkle_tree.to_bytes());
+
+            let first_commit = parsed_tree.commits()[0];
+            parsed_tree.rollback_to(&first_commit).unwrap();
+            assert_eq!(parsed_tree.leaves_len(), 5);
+        }
+    }
+
+    #[test]
+    pub fn should_not_deserialize_a_tree_with_a_wrong_root() {
+        let merkle_tree = tree_with_history();
+        let root_hex = merkle_tree.root_hex().unwrap();
+        let json = serde_json::to_string(&merkle_tree).unwrap();
+
+        let tampered_json = json.replacen(&root_hex, &"00".repeat(32), 1);
+        let error = serde_json::from_str::<MerkleTree<Sha256>>(&tampered_json)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("don't match the leaves"));
+    }
+}
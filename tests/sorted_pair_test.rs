@@ -0,0 +1,183 @@
+mod common;
+
+pub mod merkle_tree {
+    use crate::common;
+    use rs_merkle::{
+        algorithms::{Sha256, SortedPair},
+        MerkleTree,
+    };
+
+    #[test]
+    pub fn should_verify_proofs_without_indices() {
+        let test_data = common::setup();
+
+        for leaves_count in 1..=test_data.leaf_hashes.len() {
+            let leaves = &test_data.leaf_hashes[..leaves_count];
+            let merkle_tree = MerkleTree::<SortedPair<Sha256>>::from_leaves(leaves);
+            let root = merkle_tree.root().unwrap();
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = merkle_tree.proof(&[index]);
+                assert!(proof.verify_leaf(root, *leaf));
+                assert!(proof.verify(root, &[index], &[*leaf], leaves_count));
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_not_verify_a_leaf_from_another_position() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<SortedPair<Sha256>>::from_leaves(&test_data.leaf_hashes);
+        let root = merkle_tree.root().unwrap();
+
+        let proof = merkle_tree.proof(&[2]);
+        assert!(!proof.verify_leaf(root, test_data.leaf_hashes[3]));
+    }
+}
+
+pub mod simple_merkle_tree {
+    use crate::common;
+    use rs_merkle::{
+        algorithms::{Sha256, SortedPair},
+        ErrorKind, Hasher, SimpleMerkleTree,
+    };
+
+    fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        SortedPair::<Sha256>::concat_and_hash(left, Some(right))
+    }
+
+    #[test]
+    pub fn should_build_an_openzeppelin_tree_layout() {
+        let leaves = common::setup().leaf_hashes;
+
+        let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves(&leaves[..3]);
+        let expected_root = hash_pair(&hash_pair(&leaves[0], &leaves[1]), &leaves[2]);
+        assert_eq!(merkle_tree.root(), Some(expected_root));
+
+        // Unlike in MerkleTree, the last leaf is paired with the first internal node
+        let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves(&leaves[..5]);
+        let expected_root = hash_pair(
+            &hash_pair(&hash_pair(&leaves[0], &leaves[1]), &leaves[4]),
+            &hash_pair(&leaves[2], &leaves[3]),
+        );
+        assert_eq!(merkle_tree.root(), Some(expected_root));
+        assert_eq!(merkle_tree.leaves(), leaves[..5].to_vec());
+    }
+
+    #[test]
+    pub fn should_not_depend_on_the_leaves_order_when_sorted() {
+        let leaves = common::setup().leaf_hashes;
+        let mut reversed_leaves = leaves.clone();
+        reversed_leaves.reverse();
+
+        let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves_sorted(&leaves);
+        let reversed_merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves_sorted(&reversed_leaves);
+        assert_eq!(merkle_tree.root(), reversed_merkle_tree.root());
+        assert_eq!(merkle_tree.leaves(), reversed_merkle_tree.leaves());
+    }
+
+    #[test]
+    pub fn should_verify_single_proofs() {
+        let leaves = common::setup().leaf_hashes;
+
+        for leaves_count in 1..=leaves.len() {
+            let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves(&leaves[..leaves_count]);
+            let root = merkle_tree.root().unwrap();
+
+            for (index, leaf) in leaves[..leaves_count].iter().enumerate() {
+                let proof = merkle_tree.proof(index).unwrap();
+                assert!(proof.verify_leaf(root, *leaf));
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_verify_all_multi_proofs() {
+        let leaves = common::setup().leaf_hashes;
+
+        for leaves_count in 1..=leaves.len() {
+            let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves(&leaves[..leaves_count]);
+            let root = merkle_tree.root().unwrap();
+            let indices: Vec<usize> = (0..leaves_count).collect();
+
+            for indices_to_prove in common::combinations(indices) {
+                let leaves_to_prove: Vec<[u8; 32]> =
+                    indices_to_prove.iter().map(|i| leaves[*i]).collect();
+
+                let mut reversed_indices = indices_to_prove.clone();
+                reversed_indices.reverse();
+                let proof = merkle_tree.multi_proof(&reversed_indices).unwrap();
+
+                assert!(proof.verify(root, &leaves_to_prove));
+                assert_eq!(
+                    proof.proof_flags().len() + 1,
+                    leaves_to_prove.len() + proof.proof_hashes().len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_not_verify_an_invalid_multi_proof() {
+        let leaves = common::setup().leaf_hashes;
+        let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves(&leaves);
+        let root = merkle_tree.root().unwrap();
+
+        let proof = merkle_tree.multi_proof(&[1, 3]).unwrap();
+        assert!(proof.verify(root, &[leaves[1], leaves[3]]));
+        assert!(!proof.verify(root, &[leaves[1], leaves[2]]));
+        assert_eq!(
+            proof.root(&[leaves[1]]).err().map(|error| error.kind()),
+            Some(ErrorKind::InvalidProofFlags)
+        );
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_invalid_indices() {
+        let leaves = common::setup().leaf_hashes;
+        let merkle_tree = SimpleMerkleTree::<Sha256>::from_leaves(&leaves);
+
+        assert_eq!(
+            merkle_tree
+                .proof(leaves.len())
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::LeafIndexOutOfBounds)
+        );
+        assert_eq!(
+            merkle_tree
+                .multi_proof(&[0, leaves.len()])
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::LeafIndexOutOfBounds)
+        );
+        assert_eq!(
+            merkle_tree
+                .multi_proof(&[1, 1])
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::DuplicateLeafIndex)
+        );
+    }
+
+    #[cfg(feature = "keccak256")]
+    #[test]
+    pub fn should_hash_sorted_pairs_with_keccak256() {
+        use rs_merkle::algorithms::Keccak256;
+
+        let leaves = common::setup_keccak256().leaf_hashes;
+        let merkle_tree = SimpleMerkleTree::<Keccak256>::from_leaves(&leaves[..2]);
+
+        let (min, max) = if leaves[0] < leaves[1] {
+            (leaves[0], leaves[1])
+        } else {
+            (leaves[1], leaves[0])
+        };
+        let expected_root = Keccak256::hash(&[min, max].concat());
+        assert_eq!(merkle_tree.root(), Some(expected_root));
+        assert!(merkle_tree
+            .proof(0)
+            .unwrap()
+            .verify_leaf(expected_root, leaves[0]));
+    }
+}
@@ -0,0 +1,239 @@
+use rs_merkle::{algorithms::Sha256, Hasher};
+
+fn key(name: &str) -> [u8; 32] {
+    Sha256::hash(name.as_bytes())
+}
+
+fn value(amount: u64) -> [u8; 32] {
+    Sha256::hash(&amount.to_be_bytes())
+}
+
+pub mod root {
+    use crate::{key, value};
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, SparseMerkleTree};
+
+    #[test]
+    pub fn should_return_the_root_of_empty_subtrees_for_an_empty_tree() {
+        let tree = SparseMerkleTree::<Sha256>::new();
+
+        let mut expected_root = [0u8; 32];
+        for _ in 0..256 {
+            expected_root = Sha256::concat_and_hash(&expected_root, Some(&expected_root));
+        }
+
+        assert_eq!(tree.root(), expected_root);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    pub fn should_calculate_the_root_of_a_single_key_by_hand() {
+        let mut tree = SparseMerkleTree::<Sha256>::new();
+        // The key 1 is the second leftmost leaf, so its only non-empty sibling is on the left
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        tree.insert(one, value(1));
+
+        let mut empty_hash = [0u8; 32];
+        let mut expected_root = Sha256::concat_and_hash(&empty_hash, Some(&value(1)));
+        for _ in 1..256 {
+            empty_hash = Sha256::concat_and_hash(&empty_hash, Some(&empty_hash));
+            expected_root = Sha256::concat_and_hash(&expected_root, Some(&empty_hash));
+        }
+
+        assert_eq!(tree.root(), expected_root);
+    }
+
+    #[test]
+    pub fn should_not_depend_on_the_insertion_order() {
+        let names = ["alice", "bob", "carol", "dave", "eve"];
+
+        let mut tree = SparseMerkleTree::<Sha256>::new();
+        let mut reversed_tree = SparseMerkleTree::<Sha256>::new();
+        for (amount, name) in names.iter().enumerate() {
+            tree.insert(key(name), value(amount as u64));
+        }
+        for (amount, name) in names.iter().enumerate().rev() {
+            reversed_tree.insert(key(name), value(amount as u64));
+        }
+
+        assert_eq!(tree.root(), reversed_tree.root());
+        assert_eq!(tree.len(), names.len());
+    }
+
+    #[test]
+    pub fn should_update_and_remove_keys() {
+        let mut tree = SparseMerkleTree::<Sha256>::new();
+        let empty_root = tree.root();
+
+        tree.insert(key("alice"), value(100));
+        let alice_root = tree.root();
+        assert_eq!(tree.insert(key("bob"), value(50)), None);
+
+        assert_eq!(tree.update(key("bob"), value(75)).unwrap(), value(50));
+        assert_eq!(tree.get(&key("bob")), Some(value(75)));
+
+        assert_eq!(tree.remove(&key("bob")), Some(value(75)));
+        assert_eq!(tree.remove(&key("bob")), None);
+        assert_eq!(tree.root(), alice_root);
+
+        assert_eq!(tree.remove(&key("alice")), Some(value(100)));
+        assert_eq!(tree.root(), empty_root);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    pub fn should_return_an_error_when_updating_an_absent_key() {
+        let mut tree = SparseMerkleTree::<Sha256>::new();
+        tree.insert(key("alice"), value(100));
+        let root = tree.root();
+
+        let kind = tree
+            .update(key("bob"), value(50))
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::KeyNotFound));
+        assert_eq!(tree.root(), root);
+    }
+}
+
+pub mod proof {
+    use crate::{key, value};
+    use rs_merkle::{
+        algorithms::Sha256, proof_serializers::ReverseHashesOrder, SparseMerkleProof,
+        SparseMerkleTree,
+    };
+
+    fn tree() -> SparseMerkleTree<Sha256> {
+        let mut tree = SparseMerkleTree::new();
+        for (amount, name) in ["alice", "bob", "carol", "dave", "eve"].iter().enumerate() {
+            tree.insert(key(name), value(amount as u64));
+        }
+        tree
+    }
+
+    #[test]
+    pub fn should_verify_membership_proofs() {
+        let tree = tree();
+
+        for (amount, name) in ["alice", "bob", "carol", "dave", "eve"].iter().enumerate() {
+            let proof = tree.proof(&key(name));
+            assert!(proof.verify(tree.root(), &key(name), Some(value(amount as u64))));
+            assert!(!proof.verify(tree.root(), &key(name), Some(value(42))));
+            assert!(!proof.verify(tree.root(), &key(name), None));
+        }
+    }
+
+    #[test]
+    pub fn should_verify_non_membership_proofs() {
+        let tree = tree();
+
+        for name in ["frank", "grace", "heidi"] {
+            let proof = tree.proof(&key(name));
+            assert!(proof.verify(tree.root(), &key(name), None));
+            assert!(!proof.verify(tree.root(), &key(name), Some(value(0))));
+            // Only the siblings that aren't empty are included
+            assert!(proof.proof_hashes().len() < 10);
+        }
+    }
+
+    #[test]
+    pub fn should_not_verify_tampered_proofs() {
+        let tree = tree();
+        let proof = tree.proof(&key("alice"));
+
+        let mut proof_hashes = proof.proof_hashes().to_vec();
+        proof_hashes[0][0] ^= 1;
+        let tampered_proof = SparseMerkleProof::<Sha256>::new(*proof.bitmap(), proof_hashes);
+        assert!(!tampered_proof.verify(tree.root(), &key("alice"), Some(value(0))));
+
+        let mut bitmap = *proof.bitmap();
+        bitmap[31] ^= 0x80;
+        let tampered_proof =
+            SparseMerkleProof::<Sha256>::new(bitmap, proof.proof_hashes().to_vec());
+        assert!(tampered_proof.root(&key("alice"), Some(value(0))).is_err());
+        assert!(!tampered_proof.verify(tree.root(), &key("alice"), Some(value(0))));
+    }
+
+    #[test]
+    pub fn should_verify_proofs_with_precalculated_empty_hashes() {
+        let tree = tree();
+        let empty_hashes = SparseMerkleProof::<Sha256>::empty_hashes();
+        assert_eq!(tree.empty_hashes(), empty_hashes.as_slice());
+
+        for (name, value) in [("bob", Some(value(1))), ("mallory", None)] {
+            let proof = tree.proof(&key(name));
+            assert_eq!(
+                proof
+                    .root_with_empty_hashes(&key(name), value, &empty_hashes)
+                    .unwrap(),
+                proof.root(&key(name), value).unwrap()
+            );
+            assert!(proof.verify_with_empty_hashes(tree.root(), &key(name), value, &empty_hashes));
+        }
+
+        let proof = tree.proof(&key("bob"));
+        assert!(proof
+            .root_with_empty_hashes(&key("bob"), Some(value(1)), &empty_hashes[..100])
+            .is_err());
+        assert!(!proof.verify_with_empty_hashes(
+            tree.root(),
+            &key("bob"),
+            Some(value(1)),
+            &empty_hashes[..100]
+        ));
+    }
+
+    #[test]
+    pub fn should_serialize_and_deserialize_proofs() {
+        let tree = tree();
+        let proof = tree.proof(&key("carol"));
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 32 + proof.proof_hashes().len() * 32);
+        let parsed_proof = SparseMerkleProof::<Sha256>::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed_proof.bitmap(), proof.bitmap());
+        assert_eq!(parsed_proof.proof_hashes(), proof.proof_hashes());
+
+        let bytes = proof.serialize::<ReverseHashesOrder>();
+        let parsed_proof =
+            SparseMerkleProof::<Sha256>::deserialize::<ReverseHashesOrder>(&bytes).unwrap();
+        assert!(parsed_proof.verify(tree.root(), &key("carol"), Some(value(2))));
+
+        assert!(SparseMerkleProof::<Sha256>::from_bytes(&bytes[..16]).is_err());
+        assert!(SparseMerkleProof::<Sha256>::from_bytes(&bytes[..40]).is_err());
+    }
+}
+
+pub mod keys {
+    use rs_merkle::utils::keys;
+
+    #[test]
+    pub fn should_read_bits_from_the_lowest() {
+        let mut key = [0u8; 32];
+        key[31] = 0b0000_0101;
+        key[0] = 0b1000_0000;
+
+        assert!(keys::bit(&key, 0));
+        assert!(!keys::bit(&key, 1));
+        assert!(keys::bit(&key, 2));
+        assert!(keys::bit(&key, 255));
+        assert!(!keys::bit(&key, 254));
+    }
+
+    #[test]
+    pub fn should_shift_keys_right() {
+        let mut key = [0u8; 32];
+        key[30] = 0b0000_0011;
+        key[31] = 0b1000_0000;
+
+        let mut expected = [0u8; 32];
+        expected[31] = 0b0000_0111;
+        assert_eq!(keys::shift_right(&key, 7), expected);
+
+        let mut expected = [0u8; 32];
+        expected[31] = 0b0000_0011;
+        assert_eq!(keys::shift_right(&key, 8), expected);
+        assert_eq!(keys::shift_right(&key, 10), [0u8; 32]);
+        assert_eq!(keys::shift_right(&key, 0), key);
+    }
+}
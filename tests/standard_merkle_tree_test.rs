@@ -0,0 +1,250 @@
+#[cfg(feature = "keccak256")]
+mod common;
+
+// The expected values are produced by OpenZeppelin's @openzeppelin/merkle-tree package
+#[cfg(feature = "keccak256")]
+const VALUES: [[&str; 2]; 2] = [
+    [
+        "0x1111111111111111111111111111111111111111",
+        "5000000000000000000",
+    ],
+    [
+        "0x2222222222222222222222222222222222222222",
+        "2500000000000000000",
+    ],
+];
+
+#[cfg(feature = "keccak256")]
+const LEAF_ENCODING: [&str; 2] = ["address", "uint256"];
+
+#[cfg(feature = "keccak256")]
+pub mod root {
+    use crate::{LEAF_ENCODING, VALUES};
+    use rs_merkle::{ErrorKind, StandardMerkleTree};
+
+    #[test]
+    pub fn should_return_the_same_root_as_openzeppelin() {
+        let merkle_tree = StandardMerkleTree::of(&VALUES, &LEAF_ENCODING).unwrap();
+
+        assert_eq!(
+            merkle_tree.root_hex(),
+            Some("d4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77".to_string())
+        );
+    }
+
+    #[test]
+    pub fn should_not_depend_on_the_values_order() {
+        let mut values = VALUES.to_vec();
+        values.reverse();
+
+        let merkle_tree = StandardMerkleTree::of(&VALUES, &LEAF_ENCODING).unwrap();
+        let reversed_merkle_tree = StandardMerkleTree::of(&values, &LEAF_ENCODING).unwrap();
+        assert_eq!(merkle_tree.root(), reversed_merkle_tree.root());
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_invalid_values() {
+        let invalid_values = [
+            vec!["0x11", "1"],
+            vec!["0x1111111111111111111111111111111111111111", "-1"],
+            vec!["0x1111111111111111111111111111111111111111"],
+        ];
+
+        for value in invalid_values {
+            let kind = StandardMerkleTree::of(&[value], &LEAF_ENCODING)
+                .err()
+                .map(|error| error.kind());
+            assert_eq!(kind, Some(ErrorKind::InvalidLeafValue));
+        }
+
+        let kind = StandardMerkleTree::of(&[["1"]], &["uint7"])
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::InvalidLeafValue));
+    }
+}
+
+#[cfg(feature = "keccak256")]
+pub mod proof {
+    use crate::common;
+    use rs_merkle::StandardMerkleTree;
+
+    fn values() -> Vec<Vec<String>> {
+        (1..=7)
+            .map(|i: u8| {
+                vec![
+                    format!("0x{}", format!("{:02x}", i).repeat(20)),
+                    (i as u64 * 1_000_000_000).to_string(),
+                    (i % 2 == 0).to_string(),
+                    format!("user {}", i),
+                ]
+            })
+            .collect()
+    }
+
+    const LEAF_ENCODING: [&str; 4] = ["address", "uint96", "bool", "string"];
+
+    #[test]
+    pub fn should_verify_single_proofs() {
+        let values = values();
+        let merkle_tree = StandardMerkleTree::of(&values, &LEAF_ENCODING).unwrap();
+        let root = merkle_tree.root().unwrap();
+
+        for (index, value) in values.iter().enumerate() {
+            let proof = merkle_tree.proof(index).unwrap();
+            assert!(proof.verify_leaf(root, merkle_tree.leaf_hash(value).unwrap()));
+        }
+    }
+
+    #[test]
+    pub fn should_verify_multi_proofs() {
+        let values = values();
+        let merkle_tree = StandardMerkleTree::of(&values, &LEAF_ENCODING).unwrap();
+        let root = merkle_tree.root().unwrap();
+
+        for indices in common::combinations((0..values.len()).collect::<Vec<usize>>()) {
+            let (proof, ordered_indices) = merkle_tree.multi_proof(&indices).unwrap();
+            let leaves: Vec<[u8; 32]> = ordered_indices
+                .iter()
+                .map(|i| merkle_tree.leaf_hash(&values[*i]).unwrap())
+                .collect();
+
+            assert!(proof.verify(root, &leaves));
+        }
+    }
+}
+
+pub mod abi {
+    use rs_merkle::utils::abi;
+
+    fn word(last_bytes: &[u8]) -> Vec<u8> {
+        let mut word = vec![0u8; 32 - last_bytes.len()];
+        word.extend_from_slice(last_bytes);
+        word
+    }
+
+    #[test]
+    pub fn should_encode_static_types() {
+        assert_eq!(abi::encode(&["uint256"], &["1"]).unwrap(), word(&[1]));
+        assert_eq!(
+            abi::encode(&["uint16"], &["0x1234"]).unwrap(),
+            word(&[0x12, 0x34])
+        );
+        assert_eq!(abi::encode(&["bool"], &["true"]).unwrap(), word(&[1]));
+        assert_eq!(abi::encode(&["int8"], &["-1"]).unwrap(), vec![0xff; 32]);
+        assert_eq!(abi::encode(&["int8"], &["-128"]).unwrap()[31], 0x80);
+
+        let mut bytes4 = vec![0x12, 0x34, 0x56, 0x78];
+        bytes4.resize(32, 0);
+        assert_eq!(abi::encode(&["bytes4"], &["0x12345678"]).unwrap(), bytes4);
+    }
+
+    #[test]
+    pub fn should_encode_dynamic_types() {
+        let encoded = abi::encode(&["uint8", "string", "bytes"], &["7", "abc", "0x0102"]).unwrap();
+
+        let mut expected = word(&[7]);
+        expected.extend(word(&[0x60]));
+        expected.extend(word(&[0xa0]));
+        expected.extend(word(&[3]));
+        expected.extend(b"abc");
+        expected.resize(expected.len() + 29, 0);
+        expected.extend(word(&[2]));
+        expected.extend([1, 2]);
+        expected.resize(expected.len() + 30, 0);
+
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    pub fn should_reject_values_out_of_range() {
+        assert!(abi::encode(&["uint8"], &["256"]).is_err());
+        assert!(abi::encode(&["int8"], &["128"]).is_err());
+        assert!(abi::encode(&["int8"], &["-129"]).is_err());
+        assert!(abi::encode(&["bytes2"], &["0x123456"]).is_err());
+        assert!(abi::encode(&["bool"], &["1"]).is_err());
+        assert!(abi::encode(&["uint256[]"], &["1"]).is_err());
+    }
+}
+
+#[cfg(feature = "json")]
+pub mod dump {
+    use crate::{LEAF_ENCODING, VALUES};
+    use rs_merkle::{ErrorKind, StandardMerkleTree};
+
+    const DUMP: &str = concat!(
+        r#"{"format":"standard-v1","tree":["#,
+        r#""0xd4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77","#,
+        r#""0xeb02c421cfa48976e66dfb29120745909ea3a0f843456c263cf8f1253483e283","#,
+        r#""0xb92c48e9d7abe27fd8dfd6b5dfdbfb1c9a463f80c712b66f3a5180a090cccafc"],"#,
+        r#""values":[{"value":["0x1111111111111111111111111111111111111111","5000000000000000000"],"treeIndex":1},"#,
+        r#"{"value":["0x2222222222222222222222222222222222222222","2500000000000000000"],"treeIndex":2}],"#,
+        r#""leafEncoding":["address","uint256"]}"#
+    );
+
+    #[test]
+    pub fn should_dump_the_openzeppelin_format() {
+        let merkle_tree = StandardMerkleTree::of(&VALUES, &LEAF_ENCODING).unwrap();
+        assert_eq!(merkle_tree.dump(), DUMP);
+    }
+
+    #[test]
+    pub fn should_load_a_dumped_tree() {
+        let merkle_tree = StandardMerkleTree::load(DUMP).unwrap();
+
+        assert_eq!(
+            merkle_tree.root_hex(),
+            Some("d4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77".to_string())
+        );
+        assert_eq!(merkle_tree.values().len(), 2);
+        assert_eq!(merkle_tree.leaf_encoding(), LEAF_ENCODING);
+        assert_eq!(merkle_tree.dump(), DUMP);
+    }
+
+    #[test]
+    pub fn should_load_pretty_printed_dumps_with_numbers() {
+        let dump = r#"{
+            "format": "standard-v1",
+            "leafEncoding": ["uint256", "bool"],
+            "tree": [
+                "0x0000000000000000000000000000000000000000000000000000000000000000"
+            ],
+            "values": [{ "value": [5, true], "treeIndex": 0 }]
+        }"#;
+
+        // The tree node doesn't match the value
+        let kind = StandardMerkleTree::load(dump)
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(kind, Some(ErrorKind::InvalidTreeDump));
+
+        let merkle_tree = StandardMerkleTree::of(&[["5", "true"]], &["uint256", "bool"]).unwrap();
+        let dump = dump.replace(
+            "0x0000000000000000000000000000000000000000000000000000000000000000",
+            &format!("0x{}", merkle_tree.root_hex().unwrap()),
+        );
+        let loaded_tree = StandardMerkleTree::load(&dump).unwrap();
+        assert_eq!(loaded_tree.root(), merkle_tree.root());
+        assert_eq!(loaded_tree.dump(), merkle_tree.dump());
+    }
+
+    #[test]
+    pub fn should_not_load_invalid_dumps() {
+        let invalid_dumps = [
+            DUMP.replace("standard-v1", "standard-v2"),
+            DUMP.replace("5000000000000000000", "6000000000000000000"),
+            DUMP.replace("0xd4dee0", "0xd4dee1"),
+            DUMP.replace(r#""treeIndex":2"#, r#""treeIndex":1"#),
+            DUMP.replace(r#""treeIndex":2"#, r#""treeIndex":0"#),
+            DUMP.replace(r#""treeIndex":2"#, r#""treeIndex":18446744073709551615"#),
+            DUMP[1..].to_string(),
+        ];
+
+        for dump in invalid_dumps {
+            let kind = StandardMerkleTree::load(&dump)
+                .err()
+                .map(|error| error.kind());
+            assert_eq!(kind, Some(ErrorKind::InvalidTreeDump), "{}", dump);
+        }
+    }
+}